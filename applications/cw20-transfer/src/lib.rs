@@ -3,7 +3,7 @@ use cosmwasm_std::{to_json_binary, Addr, Coin, Reply, SubMsg, Uint128, WasmMsg};
 use cw20::Cw20ExecuteMsg;
 use cw_storey::containers::{Item, Map};
 use cw_storey::CwStorage;
-use eureka_application_interface::Application;
+use eureka_application_interface::{Application, PayloadResult};
 use sylvia::contract;
 use sylvia::cw_std::{Response, StdError, StdResult};
 use sylvia::types::{ExecCtx, InstantiateCtx, QueryCtx, ReplyCtx};
@@ -478,6 +478,48 @@ impl Application for Contract {
             return Err(StdError::generic_err("not allowed channel"));
         }
 
+        self.refund(&packet, relayer)
+    }
+
+    fn acknowledge(
+        &self,
+        ctx: ExecCtx,
+        lightclient_local: (Addr, Vec<u8>),
+        lightclient_remote: (Addr, Vec<u8>),
+        application_remote: Addr,
+        packet: Vec<u8>,
+        result: PayloadResult,
+        relayer: Addr,
+        _sent_funds: Vec<Coin>,
+    ) -> Result<Response, Self::Error> {
+        let mut storage = CwStorage(ctx.deps.storage);
+
+        if Some(&ctx.info.sender) != self.tao_contract.access(&mut storage).get()?.as_ref() {
+            return Err(StdError::generic_err(
+                "acknowledge can only be called by tao",
+            ));
+        }
+
+        let channel = Channel {
+            lightclient_local,
+            lightclient_remote,
+            application_remote,
+        };
+
+        if Some(&channel) != self.allowed_channel.access(&mut storage).get()?.as_ref() {
+            return Err(StdError::generic_err("not allowed channel"));
+        }
+
+        match result {
+            PayloadResult::Success => Ok(Response::default()),
+            // the transfer failed at the destination, refund as if it timed out
+            PayloadResult::Error(_) => self.refund(&packet, relayer),
+        }
+    }
+}
+
+impl Contract {
+    fn refund(&self, packet: &[u8], relayer: Addr) -> StdResult<Response> {
         // // ignoring the packet size, as it is originated at the same contract
         // assert!(
         //     packet.len() <= 1024,
@@ -485,7 +527,7 @@ impl Application for Contract {
         // );
 
         let transfer_packet =
-            serde_json::from_slice(&packet).map_err(|e| StdError::generic_err(e.to_string()))?;
+            serde_json::from_slice(packet).map_err(|e| StdError::generic_err(e.to_string()))?;
 
         let TransferPacket {
             sender,
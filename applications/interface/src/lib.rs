@@ -1,7 +1,18 @@
+// generated message builders mirror the callback arguments
+#![allow(clippy::too_many_arguments)]
+
+use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{Addr, Coin, Response, StdError};
 use sylvia::interface;
 use sylvia::types::ExecCtx;
 
+// outcome of a payload at the destination application
+#[cw_serde]
+pub enum PayloadResult {
+    Success,
+    Error(String),
+}
+
 #[interface]
 pub trait Application {
     type Error: From<StdError>;
@@ -42,4 +53,18 @@ pub trait Application {
         relayer: Addr,
         sent_funds: Vec<Coin>,
     ) -> Result<Response, Self::Error>;
+
+    #[allow(clippy::too_many_arguments)]
+    #[sv::msg(exec)]
+    fn acknowledge(
+        &self,
+        ctx: ExecCtx,
+        lightclient_local: (Addr, Vec<u8>),
+        lightclient_remote: (Addr, Vec<u8>),
+        application_remote: Addr,
+        packet: Vec<u8>,
+        result: PayloadResult,
+        relayer: Addr,
+        sent_funds: Vec<Coin>,
+    ) -> Result<Response, Self::Error>;
 }
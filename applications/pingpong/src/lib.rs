@@ -2,7 +2,7 @@ use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{Addr, Coin};
 use cw_storey::containers::Item;
 use cw_storey::CwStorage;
-use eureka_application_interface::Application;
+use eureka_application_interface::{Application, PayloadResult};
 use sylvia::contract;
 use sylvia::cw_std::{Response, StdError, StdResult};
 use sylvia::types::{ExecCtx, InstantiateCtx, QueryCtx};
@@ -26,6 +26,7 @@ pub struct Contract {
 
     pub sent: Item<String>,
     pub received: Item<String>,
+    pub acknowledged: Item<String>,
 }
 
 #[cfg_attr(not(feature = "library"), sylvia::entry_points)]
@@ -40,6 +41,7 @@ impl Contract {
             allowed_channel: Item::new(b'C'),
             sent: Item::new(b'D'),
             received: Item::new(b'E'),
+            acknowledged: Item::new(b'F'),
         }
     }
 
@@ -53,6 +55,9 @@ impl Contract {
         self.received
             .access(&mut storage)
             .set(&"null".to_string())?;
+        self.acknowledged
+            .access(&mut storage)
+            .set(&"null".to_string())?;
 
         Ok(Response::default())
     }
@@ -119,6 +124,16 @@ impl Contract {
             .get()?
             .unwrap_or_default())
     }
+
+    #[sv::msg(query)]
+    fn acknowledged_value(&self, ctx: QueryCtx) -> StdResult<String> {
+        let mut storage = CwStorage(ctx.deps.storage);
+        Ok(self
+            .acknowledged
+            .access(&mut storage)
+            .get()?
+            .unwrap_or_default())
+    }
 }
 
 impl Application for Contract {
@@ -230,4 +245,42 @@ impl Application for Contract {
         ))?;
         Ok(Response::default())
     }
+
+    fn acknowledge(
+        &self,
+        ctx: ExecCtx,
+        lightclient_local: (Addr, Vec<u8>),
+        lightclient_remote: (Addr, Vec<u8>),
+        application_remote: Addr,
+        packet: Vec<u8>,
+        result: PayloadResult,
+        _relayer: Addr,
+        _sent_funds: Vec<Coin>,
+    ) -> Result<Response, Self::Error> {
+        let mut storage = CwStorage(ctx.deps.storage);
+
+        if Some(&ctx.info.sender) != self.tao_contract.access(&mut storage).get()?.as_ref() {
+            return Err(StdError::generic_err(
+                "acknowledge can only be called by tao",
+            ));
+        }
+
+        if Some(&Channel {
+            lightclient_local,
+            lightclient_remote,
+            application_remote: application_remote.clone(),
+        }) != self.allowed_channel.access(&mut storage).get()?.as_ref()
+        {
+            return Err(StdError::generic_err("not allowed channel"));
+        }
+
+        self.acknowledged.access(&mut storage).set(&format!(
+            "{}(via {}) acknowledged {} with {:?}",
+            application_remote,
+            ctx.info.sender,
+            String::from_utf8_lossy(&packet),
+            result,
+        ))?;
+        Ok(Response::default())
+    }
 }
@@ -0,0 +1,37 @@
+use cosmwasm_std::StdError;
+use eureka_ics23_verifier::ProofError;
+use eureka_lightclient_interface::Height;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("invalid proof: {0}")]
+    Proof(#[from] ProofError),
+
+    #[error("invalid attestor set: {0}")]
+    InvalidAttestorSet(String),
+
+    #[error("client is frozen")]
+    ClientFrozen,
+
+    #[error("consensus state not found at height: {height}")]
+    ConsensusStateNotFound { height: Height },
+
+    #[error("signature count mismatch: signers: {signers}, signatures: {signatures}")]
+    SignatureCountMismatch { signers: usize, signatures: usize },
+
+    #[error("invalid signature of signer: {index}")]
+    InvalidSignature { index: usize },
+
+    #[error("attestation threshold not met: signed: {signed}, threshold: {threshold}")]
+    ThresholdNotMet { signed: u64, threshold: u64 },
+
+    #[error("no misbehaviour")]
+    NoMisbehaviour,
+
+    #[error("upgrade not supported")]
+    UpgradeNotSupported,
+}
@@ -0,0 +1,610 @@
+// attestor light client, for counterparties that cannot be verified on chain yet
+// a threshold of configured signers attests consensus states or individual commitments
+
+pub mod error;
+
+use std::collections::BTreeSet;
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{from_json, to_json_vec, Api, StdError, StdResult, Storage};
+use cw_storey::containers::{Item, Map};
+use cw_storey::CwStorage;
+use eureka_ics23_verifier::{apply_prefix, sdk_specs};
+use eureka_lightclient_interface::{
+    self as interface, apply_headers, check_each_membership, Height, LightClient, Status,
+    UpdatePolicy,
+};
+use sha2::{Digest, Sha256};
+use sylvia::contract;
+use sylvia::cw_std::Response;
+use sylvia::types::{ExecCtx, InstantiateCtx, QueryCtx};
+
+pub use crate::error::ContractError;
+
+#[cw_serde]
+pub enum PublicKey {
+    // compressed SEC1 encoding
+    Secp256k1(Vec<u8>),
+    Ed25519(Vec<u8>),
+}
+
+#[cw_serde]
+pub struct AttestorSet {
+    pub signers: Vec<PublicKey>,
+    // minimum number of distinct signers
+    pub threshold: u64,
+}
+
+#[cw_serde]
+pub struct ClientState {
+    // identifies the counterparty in every attestation
+    pub chain_id: String,
+    pub attestor_set: AttestorSet,
+    // number of attestor set rotations so far
+    pub sequence: u64,
+    pub latest_height: Height,
+    pub frozen: bool,
+}
+
+#[cw_serde]
+pub struct ConsensusState {
+    // in seconds
+    pub timestamp: u64,
+    pub root: Vec<u8>,
+}
+
+// messages signed by the attestors
+#[cw_serde]
+pub enum Attestation {
+    State {
+        chain_id: String,
+        height: Height,
+        timestamp: u64,
+        root: Vec<u8>,
+    },
+    // `value` is `None` for non-membership
+    Commitment {
+        chain_id: String,
+        height: Height,
+        path: Vec<Vec<u8>>,
+        value: Option<Vec<u8>>,
+    },
+    AttestorSet {
+        chain_id: String,
+        sequence: u64,
+        attestor_set: AttestorSet,
+    },
+}
+
+impl Attestation {
+    // sha256 of the JSON encoding, signed by every signer type
+    pub fn sign_bytes(&self) -> StdResult<Vec<u8>> {
+        Ok(Sha256::digest(to_json_vec(self)?).to_vec())
+    }
+}
+
+#[cw_serde]
+pub struct StateAttestation {
+    pub height: Height,
+    pub timestamp: u64,
+    pub root: Vec<u8>,
+    // signatures in attestor set order, `None` for absent signers
+    pub signatures: Vec<Option<Vec<u8>>>,
+}
+
+#[cw_serde]
+pub enum Header {
+    State(StateAttestation),
+    // the next attestor set, signed by the current one
+    Rotation {
+        attestor_set: AttestorSet,
+        signatures: Vec<Option<Vec<u8>>>,
+    },
+}
+
+#[cw_serde]
+pub enum MembershipProof {
+    // ICS23 proof against the attested root
+    Merkle(Vec<u8>),
+    // signatures over the attested commitment
+    Attested(Vec<Option<Vec<u8>>>),
+}
+
+// conflicting state attestations for the same height
+#[cw_serde]
+pub struct Misbehaviour {
+    pub attestation_1: StateAttestation,
+    pub attestation_2: StateAttestation,
+}
+
+fn validate_attestor_set(attestor_set: &AttestorSet) -> Result<(), ContractError> {
+    if attestor_set.threshold == 0 || attestor_set.threshold > attestor_set.signers.len() as u64 {
+        return Err(ContractError::InvalidAttestorSet(
+            "threshold out of range".to_string(),
+        ));
+    }
+
+    // a single encoding per key, so that duplicates are found by their bytes
+    let keys = attestor_set
+        .signers
+        .iter()
+        .map(|signer| match signer {
+            PublicKey::Secp256k1(key) if key.len() == 33 && matches!(key[0], 0x02 | 0x03) => {
+                Ok(key)
+            }
+            PublicKey::Ed25519(key) if key.len() == 32 => Ok(key),
+            _ => Err(ContractError::InvalidAttestorSet(
+                "invalid public key".to_string(),
+            )),
+        })
+        .collect::<Result<BTreeSet<_>, _>>()?;
+
+    if keys.len() != attestor_set.signers.len() {
+        return Err(ContractError::InvalidAttestorSet(
+            "duplicate signer".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+fn verify_attestation(
+    api: &dyn Api,
+    attestor_set: &AttestorSet,
+    attestation: &Attestation,
+    signatures: &[Option<Vec<u8>>],
+) -> Result<(), ContractError> {
+    if signatures.len() != attestor_set.signers.len() {
+        return Err(ContractError::SignatureCountMismatch {
+            signers: attestor_set.signers.len(),
+            signatures: signatures.len(),
+        });
+    }
+
+    let sign_bytes = attestation.sign_bytes()?;
+
+    let mut signed = 0;
+
+    for (index, (signer, signature)) in attestor_set.signers.iter().zip(signatures).enumerate() {
+        let Some(signature) = signature else {
+            continue;
+        };
+
+        let valid = match signer {
+            PublicKey::Secp256k1(key) => api.secp256k1_verify(&sign_bytes, signature, key),
+            PublicKey::Ed25519(key) => api.ed25519_verify(&sign_bytes, signature, key),
+        };
+
+        if !matches!(valid, Ok(true)) {
+            return Err(ContractError::InvalidSignature { index });
+        }
+
+        signed += 1;
+    }
+
+    if signed < attestor_set.threshold {
+        return Err(ContractError::ThresholdNotMet {
+            signed,
+            threshold: attestor_set.threshold,
+        });
+    }
+
+    Ok(())
+}
+
+pub struct Contract {
+    pub client_state: Item<ClientState>,
+    // revision number -> revision height -> consensus state
+    pub consensus_states: Map<u64, Map<u64, Item<ConsensusState>>>,
+}
+
+#[cfg_attr(not(feature = "library"), sylvia::entry_points)]
+#[contract]
+#[sv::error(ContractError)]
+#[sv::messages(eureka_lightclient_interface)]
+impl Contract {
+    pub const fn new() -> Self {
+        Self {
+            client_state: Item::new(b'C'),
+            consensus_states: Map::new(b'S'),
+        }
+    }
+
+    #[sv::msg(instantiate)]
+    fn instantiate(
+        &self,
+        ctx: InstantiateCtx,
+        client_state: ClientState,
+        consensus_state: ConsensusState,
+    ) -> Result<Response, ContractError> {
+        validate_attestor_set(&client_state.attestor_set)?;
+
+        let mut storage = CwStorage(ctx.deps.storage);
+
+        self.consensus_states
+            .access(&mut storage)
+            .entry_mut(&client_state.latest_height.revision_number)
+            .entry_mut(&client_state.latest_height.revision_height)
+            .set(&consensus_state)?;
+        self.client_state.access(&mut storage).set(&client_state)?;
+
+        Ok(Response::default())
+    }
+}
+
+impl Contract {
+    fn load_client_state(&self, storage: &dyn Storage) -> Result<ClientState, ContractError> {
+        self.client_state
+            .access(&CwStorage(storage))
+            .get()?
+            .ok_or_else(|| StdError::not_found("client state").into())
+    }
+
+    fn find_consensus_state(
+        &self,
+        storage: &dyn Storage,
+        height: Height,
+    ) -> Result<Option<ConsensusState>, ContractError> {
+        Ok(self
+            .consensus_states
+            .access(&CwStorage(storage))
+            .entry(&height.revision_number)
+            .entry(&height.revision_height)
+            .get()?)
+    }
+
+    fn load_consensus_state(
+        &self,
+        storage: &dyn Storage,
+        height: Height,
+    ) -> Result<ConsensusState, ContractError> {
+        self.find_consensus_state(storage, height)?
+            .ok_or(ContractError::ConsensusStateNotFound { height })
+    }
+
+    fn verify_state(
+        api: &dyn Api,
+        client_state: &ClientState,
+        attestation: &StateAttestation,
+    ) -> Result<(), ContractError> {
+        verify_attestation(
+            api,
+            &client_state.attestor_set,
+            &Attestation::State {
+                chain_id: client_state.chain_id.clone(),
+                height: attestation.height,
+                timestamp: attestation.timestamp,
+                root: attestation.root.clone(),
+            },
+            &attestation.signatures,
+        )
+    }
+
+    // checks the commitment at `path` against the attested root or the attestors' signatures
+    fn verify_commitment(
+        &self,
+        storage: &dyn Storage,
+        api: &dyn Api,
+        path: Vec<Vec<u8>>,
+        value: Option<Vec<u8>>,
+        height: Height,
+        proof: Vec<u8>,
+    ) -> Result<(), ContractError> {
+        let client_state = self.load_client_state(storage)?;
+        let consensus_state = self.load_consensus_state(storage, height)?;
+
+        match from_json(proof)? {
+            MembershipProof::Merkle(proof) => match value {
+                Some(value) => eureka_ics23_verifier::verify_membership(
+                    &sdk_specs(),
+                    &consensus_state.root,
+                    &path,
+                    &proof,
+                    value,
+                )?,
+                None => eureka_ics23_verifier::verify_non_membership(
+                    &sdk_specs(),
+                    &consensus_state.root,
+                    &path,
+                    &proof,
+                )?,
+            },
+            MembershipProof::Attested(signatures) => verify_attestation(
+                api,
+                &client_state.attestor_set,
+                &Attestation::Commitment {
+                    chain_id: client_state.chain_id.clone(),
+                    height,
+                    path,
+                    value,
+                },
+                &signatures,
+            )?,
+        }
+
+        Ok(())
+    }
+}
+
+impl LightClient for Contract {
+    type Error = ContractError;
+
+    fn update(&self, ctx: ExecCtx, header: Vec<u8>) -> Result<Response, Self::Error> {
+        let mut client_state = self.load_client_state(ctx.deps.storage)?;
+
+        if client_state.frozen {
+            return Err(ContractError::ClientFrozen);
+        }
+
+        match from_json(header)? {
+            Header::State(attestation) => {
+                Self::verify_state(ctx.deps.api, &client_state, &attestation)?;
+
+                let height = attestation.height;
+                let consensus_state = ConsensusState {
+                    timestamp: attestation.timestamp,
+                    root: attestation.root,
+                };
+
+                let mut storage = CwStorage(ctx.deps.storage);
+
+                let existing = self
+                    .consensus_states
+                    .access(&storage)
+                    .entry(&height.revision_number)
+                    .entry(&height.revision_height)
+                    .get()?;
+
+                match existing {
+                    Some(existing) if existing == consensus_state => {}
+                    // attestors signed two different states for the same height
+                    Some(_) => {
+                        client_state.frozen = true;
+                        self.client_state.access(&mut storage).set(&client_state)?;
+                    }
+                    None => {
+                        self.consensus_states
+                            .access(&mut storage)
+                            .entry_mut(&height.revision_number)
+                            .entry_mut(&height.revision_height)
+                            .set(&consensus_state)?;
+
+                        if height > client_state.latest_height {
+                            client_state.latest_height = height;
+                            self.client_state.access(&mut storage).set(&client_state)?;
+                        }
+                    }
+                }
+            }
+            Header::Rotation {
+                attestor_set,
+                signatures,
+            } => {
+                validate_attestor_set(&attestor_set)?;
+
+                verify_attestation(
+                    ctx.deps.api,
+                    &client_state.attestor_set,
+                    &Attestation::AttestorSet {
+                        chain_id: client_state.chain_id.clone(),
+                        sequence: client_state.sequence,
+                        attestor_set: attestor_set.clone(),
+                    },
+                    &signatures,
+                )?;
+
+                client_state.attestor_set = attestor_set;
+                client_state.sequence += 1;
+
+                self.client_state
+                    .access(&mut CwStorage(ctx.deps.storage))
+                    .set(&client_state)?;
+            }
+        }
+
+        Ok(Response::default())
+    }
+
+    fn update_batch(&self, ctx: ExecCtx, headers: Vec<Vec<u8>>) -> Result<Response, Self::Error> {
+        apply_headers(ctx, headers, |ctx, header| self.update(ctx, header))
+    }
+
+    // headers are verified against the trusted attestor set
+    fn update_policy(&self, _ctx: QueryCtx) -> Result<UpdatePolicy, Self::Error> {
+        Ok(UpdatePolicy::Permissionless)
+    }
+
+    fn check_for_misbehaviour(
+        &self,
+        ctx: QueryCtx,
+        misbehaviour: Vec<u8>,
+    ) -> Result<bool, Self::Error> {
+        let Misbehaviour {
+            attestation_1,
+            attestation_2,
+        } = from_json(misbehaviour)?;
+
+        let client_state = self.load_client_state(ctx.deps.storage)?;
+
+        Self::verify_state(ctx.deps.api, &client_state, &attestation_1)?;
+        Self::verify_state(ctx.deps.api, &client_state, &attestation_2)?;
+
+        Ok(attestation_1.height == attestation_2.height
+            && (attestation_1.timestamp, &attestation_1.root)
+                != (attestation_2.timestamp, &attestation_2.root))
+    }
+
+    fn update_on_misbehaviour(
+        &self,
+        ctx: ExecCtx,
+        misbehaviour: Vec<u8>,
+    ) -> Result<Response, Self::Error> {
+        if !self.check_for_misbehaviour(
+            QueryCtx::from((ctx.deps.as_ref(), ctx.env.clone())),
+            misbehaviour,
+        )? {
+            return Err(ContractError::NoMisbehaviour);
+        }
+
+        let mut client_state = self.load_client_state(ctx.deps.storage)?;
+        client_state.frozen = true;
+
+        self.client_state
+            .access(&mut CwStorage(ctx.deps.storage))
+            .set(&client_state)?;
+
+        Ok(Response::default())
+    }
+
+    // the attestors follow the counterparty across upgrades, see `Header::Rotation`
+    fn verify_upgrade_and_update_state(
+        &self,
+        _ctx: ExecCtx,
+        _upgraded_client: Vec<u8>,
+        _upgraded_consensus: Vec<u8>,
+        _proofs: Vec<u8>,
+    ) -> Result<Response, Self::Error> {
+        Err(ContractError::UpgradeNotSupported)
+    }
+
+    fn client_state(&self, ctx: QueryCtx) -> Result<interface::ClientState, Self::Error> {
+        let client_state = self.load_client_state(ctx.deps.storage)?;
+
+        Ok(interface::ClientState {
+            chain_id: client_state.chain_id.clone(),
+            latest_height: client_state.latest_height,
+            frozen_height: client_state.frozen.then_some(client_state.latest_height),
+            data: to_json_vec(&client_state)?,
+        })
+    }
+
+    fn consensus_state(
+        &self,
+        ctx: QueryCtx,
+        height: Height,
+    ) -> Result<interface::ConsensusState, Self::Error> {
+        let consensus_state = self.load_consensus_state(ctx.deps.storage, height)?;
+
+        Ok(interface::ConsensusState {
+            timestamp: consensus_state.timestamp,
+            root: consensus_state.root.clone(),
+            data: to_json_vec(&consensus_state)?,
+        })
+    }
+
+    fn status(&self, ctx: QueryCtx) -> Result<Status, Self::Error> {
+        if self.load_client_state(ctx.deps.storage)?.frozen {
+            Ok(Status::Frozen)
+        } else {
+            Ok(Status::Active)
+        }
+    }
+
+    fn latest_height(&self, ctx: QueryCtx) -> Result<Height, Self::Error> {
+        Ok(self.load_client_state(ctx.deps.storage)?.latest_height)
+    }
+
+    fn timestamp(&self, ctx: QueryCtx, height: Height) -> Result<u64, Self::Error> {
+        Ok(self
+            .load_consensus_state(ctx.deps.storage, height)?
+            .timestamp)
+    }
+
+    fn consensus_timestamp(
+        &self,
+        ctx: QueryCtx,
+        height: Height,
+    ) -> Result<Option<u64>, Self::Error> {
+        Ok(self
+            .find_consensus_state(ctx.deps.storage, height)?
+            .map(|consensus_state| consensus_state.timestamp))
+    }
+
+    // attestations do not expire
+    fn trusting_period(&self, _ctx: QueryCtx) -> Result<u64, Self::Error> {
+        Ok(u64::MAX)
+    }
+
+    // a substitute may be attested by another attestor set, only the chain has to match
+    fn client_parameters(&self, ctx: QueryCtx) -> Result<Vec<u8>, Self::Error> {
+        Ok(to_json_vec(
+            &self.load_client_state(ctx.deps.storage)?.chain_id,
+        )?)
+    }
+
+    // attestations do not expire, nothing to prune
+    fn prune(&self, _ctx: ExecCtx) -> Result<Response, Self::Error> {
+        Ok(Response::default())
+    }
+
+    fn check_membership(
+        &self,
+        ctx: QueryCtx,
+        key: Vec<u8>,
+        value: Vec<u8>,
+        commitment_prefix: Vec<u8>,
+        height: Height,
+        proof: Vec<u8>,
+    ) -> Result<bool, Self::Error> {
+        self.verify_commitment(
+            ctx.deps.storage,
+            ctx.deps.api,
+            apply_prefix(&commitment_prefix, &key),
+            Some(value),
+            height,
+            proof,
+        )?;
+
+        Ok(true)
+    }
+
+    fn verify_batch_membership(
+        &self,
+        ctx: QueryCtx,
+        items: Vec<(Vec<u8>, Vec<u8>)>,
+        commitment_prefix: Vec<u8>,
+        height: Height,
+        proof: Vec<u8>,
+    ) -> Result<bool, Self::Error> {
+        check_each_membership(items, &proof, |key, value, proof| {
+            self.check_membership(
+                QueryCtx::from((ctx.deps, ctx.env.clone())),
+                key,
+                value,
+                commitment_prefix.clone(),
+                height,
+                proof,
+            )
+        })
+    }
+
+    fn check_non_membership(
+        &self,
+        ctx: QueryCtx,
+        key: Vec<u8>,
+        commitment_prefix: Vec<u8>,
+        height: Height,
+        proof: Vec<u8>,
+    ) -> Result<bool, Self::Error> {
+        self.verify_commitment(
+            ctx.deps.storage,
+            ctx.deps.api,
+            apply_prefix(&commitment_prefix, &key),
+            None,
+            height,
+            proof,
+        )?;
+
+        Ok(true)
+    }
+
+    // proofs are checked against the attested roots, they stay valid
+    fn consume_proofs(
+        &self,
+        _ctx: ExecCtx,
+        _height: Height,
+        _count: u64,
+    ) -> Result<Response, Self::Error> {
+        Ok(Response::default())
+    }
+}
@@ -1,14 +1,54 @@
-use cosmwasm_std::StdError;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{from_json, Addr, StdError, Storage};
 use cw_storey::containers::{Item, Map};
 use cw_storey::CwStorage;
-use eureka_lightclient_interface::{LightClient, Status};
+use eureka_lightclient_interface::{
+    apply_headers, ClientState, ConsensusState, Height, LightClient, Status, UpdatePolicy,
+};
 use sylvia::contract;
 use sylvia::cw_std::{Response, StdResult};
 use sylvia::types::{ExecCtx, InstantiateCtx, QueryCtx};
 
+// consensus timestamp at a height
+#[cw_serde]
+pub struct Header {
+    pub height: Height,
+    // in seconds
+    pub timestamp: u64,
+}
+
+// two encoded `Header`s for the same height with conflicting timestamps
+#[cw_serde]
+pub struct Misbehaviour {
+    pub height: Height,
+    pub header_1: Vec<u8>,
+    pub header_2: Vec<u8>,
+}
+
+// both headers must be for the evidence height, the client misbehaved if their timestamps differ
+fn is_misbehaviour(misbehaviour: &Misbehaviour) -> StdResult<bool> {
+    let header_1: Header = from_json(&misbehaviour.header_1)?;
+    let header_2: Header = from_json(&misbehaviour.header_2)?;
+
+    if header_1.height != misbehaviour.height || header_2.height != misbehaviour.height {
+        return Err(StdError::generic_err("header height mismatch"));
+    }
+
+    Ok(header_1.timestamp != header_2.timestamp)
+}
+
 pub struct Contract {
-    pub lightclient_state: Item<Vec<u8>>,
-    pub consensus_states: Map<u64, Item<Vec<u8>>>,
+    pub client_state: Item<ClientState>,
+    // revision number -> revision height -> consensus state, holding the header as data
+    pub consensus_states: Map<u64, Map<u64, Item<ConsensusState>>>,
+    // status is switchable by the owner for testing
+    pub status: Item<Status>,
+    // answer of the membership checks, switchable by the owner to mimic clients reporting
+    // failed verifications as `false`
+    pub verified: Item<bool>,
+    pub owner: Item<Addr>,
+    // headers are not verified, only the owner and these can update
+    pub submitters: Item<Vec<Addr>>,
 }
 
 #[cfg_attr(not(feature = "library"), sylvia::entry_points)]
@@ -18,8 +58,12 @@ pub struct Contract {
 impl Contract {
     pub const fn new() -> Self {
         Self {
-            lightclient_state: Item::new(b'C'),
+            client_state: Item::new(b'C'),
             consensus_states: Map::new(b'S'),
+            status: Item::new(b'T'),
+            verified: Item::new(b'V'),
+            owner: Item::new(b'O'),
+            submitters: Item::new(b'U'),
         }
     }
 
@@ -27,60 +71,289 @@ impl Contract {
     fn instantiate(
         &self,
         ctx: InstantiateCtx,
-        lightclient_state: Vec<u8>,
-        consensus_state: Vec<u8>,
+        client_state: ClientState,
+        // consensus state at the latest height
+        consensus_state: ConsensusState,
+        // not the instantiator, which is tao for light clients created by its factory
+        owner: Addr,
     ) -> StdResult<Response> {
         let mut storage = CwStorage(ctx.deps.storage);
-        self.lightclient_state
-            .access(&mut storage)
-            .set(&lightclient_state)?;
+        let height = client_state.latest_height;
         self.consensus_states
             .access(&mut storage)
-            .entry_mut(&0)
+            .entry_mut(&height.revision_number)
+            .entry_mut(&height.revision_height)
             .set(&consensus_state)?;
+        self.client_state.access(&mut storage).set(&client_state)?;
+        self.status.access(&mut storage).set(&Status::Active)?;
+        self.owner.access(&mut storage).set(&owner)?;
+        Ok(Response::default())
+    }
+
+    #[sv::msg(exec)]
+    fn set_status(&self, ctx: ExecCtx, status: Status) -> StdResult<Response> {
+        let mut storage = CwStorage(ctx.deps.storage);
+
+        if self.owner.access(&storage).get()?.as_ref() != Some(&ctx.info.sender) {
+            return Err(StdError::generic_err("unauthorized"));
+        }
+
+        self.status.access(&mut storage).set(&status)?;
+        Ok(Response::default())
+    }
+
+    #[sv::msg(exec)]
+    fn set_verified(&self, ctx: ExecCtx, verified: bool) -> StdResult<Response> {
+        let mut storage = CwStorage(ctx.deps.storage);
+
+        if self.owner.access(&storage).get()?.as_ref() != Some(&ctx.info.sender) {
+            return Err(StdError::generic_err("unauthorized"));
+        }
+
+        self.verified.access(&mut storage).set(&verified)?;
+        Ok(Response::default())
+    }
+
+    #[sv::msg(exec)]
+    fn set_submitters(&self, ctx: ExecCtx, submitters: Vec<Addr>) -> StdResult<Response> {
+        let mut storage = CwStorage(ctx.deps.storage);
+
+        if self.owner.access(&storage).get()?.as_ref() != Some(&ctx.info.sender) {
+            return Err(StdError::generic_err("unauthorized"));
+        }
+
+        self.submitters.access(&mut storage).set(&submitters)?;
         Ok(Response::default())
     }
 }
 
+impl Contract {
+    fn load_client_state(&self, storage: &dyn Storage) -> StdResult<ClientState> {
+        self.client_state
+            .access(&CwStorage(storage))
+            .get()?
+            .ok_or_else(|| StdError::not_found("client state"))
+    }
+
+    fn find_consensus_state(
+        &self,
+        storage: &dyn Storage,
+        height: Height,
+    ) -> StdResult<Option<ConsensusState>> {
+        self.consensus_states
+            .access(&CwStorage(storage))
+            .entry(&height.revision_number)
+            .entry(&height.revision_height)
+            .get()
+    }
+
+    fn load_consensus_state(
+        &self,
+        storage: &dyn Storage,
+        height: Height,
+    ) -> StdResult<ConsensusState> {
+        self.find_consensus_state(storage, height)?
+            .ok_or_else(|| StdError::not_found(format!("consensus state at {height}")))
+    }
+
+    fn load_verified(&self, storage: &dyn Storage) -> StdResult<bool> {
+        Ok(self
+            .verified
+            .access(&CwStorage(storage))
+            .get()?
+            .unwrap_or(true))
+    }
+
+    fn load_update_policy(&self, storage: &dyn Storage) -> StdResult<UpdatePolicy> {
+        let storage = CwStorage(storage);
+        let mut submitters = self
+            .owner
+            .access(&storage)
+            .get()?
+            .into_iter()
+            .collect::<Vec<_>>();
+        submitters.extend(self.submitters.access(&storage).get()?.unwrap_or_default());
+        Ok(UpdatePolicy::AllowList(submitters))
+    }
+}
+
 impl LightClient for Contract {
     type Error = StdError;
 
-    fn update(&self, _ctx: ExecCtx, _header: Vec<u8>) -> Result<Response, Self::Error> {
+    fn update(&self, ctx: ExecCtx, header: Vec<u8>) -> Result<Response, Self::Error> {
+        if !self
+            .load_update_policy(ctx.deps.storage)?
+            .is_allowed(&ctx.info.sender)
+        {
+            return Err(StdError::generic_err("unauthorized"));
+        }
+
+        let Header { height, timestamp } = from_json(&header)?;
+
+        let mut client_state = self.load_client_state(ctx.deps.storage)?;
+
+        let mut storage = CwStorage(ctx.deps.storage);
+
+        self.consensus_states
+            .access(&mut storage)
+            .entry_mut(&height.revision_number)
+            .entry_mut(&height.revision_height)
+            .set(&ConsensusState {
+                timestamp,
+                root: vec![],
+                data: header,
+            })?;
+
+        if height > client_state.latest_height {
+            client_state.latest_height = height;
+            self.client_state.access(&mut storage).set(&client_state)?;
+        }
+
+        Ok(Response::default())
+    }
+
+    fn update_batch(&self, ctx: ExecCtx, headers: Vec<Vec<u8>>) -> Result<Response, Self::Error> {
+        apply_headers(ctx, headers, |ctx, header| self.update(ctx, header))
+    }
+
+    fn update_policy(&self, ctx: QueryCtx) -> Result<UpdatePolicy, Self::Error> {
+        self.load_update_policy(ctx.deps.storage)
+    }
+
+    fn check_for_misbehaviour(
+        &self,
+        _ctx: QueryCtx,
+        misbehaviour: Vec<u8>,
+    ) -> Result<bool, Self::Error> {
+        is_misbehaviour(&from_json(misbehaviour)?)
+    }
+
+    fn update_on_misbehaviour(
+        &self,
+        ctx: ExecCtx,
+        misbehaviour: Vec<u8>,
+    ) -> Result<Response, Self::Error> {
+        let misbehaviour: Misbehaviour = from_json(misbehaviour)?;
+
+        if !is_misbehaviour(&misbehaviour)? {
+            return Err(StdError::generic_err("no misbehaviour"));
+        }
+
+        let mut client_state = self.load_client_state(ctx.deps.storage)?;
+        client_state.frozen_height = Some(misbehaviour.height);
+
+        let mut storage = CwStorage(ctx.deps.storage);
+        self.client_state.access(&mut storage).set(&client_state)?;
+        self.status.access(&mut storage).set(&Status::Frozen)?;
         Ok(Response::default())
     }
 
-    fn status(&self, _ctx: QueryCtx) -> Result<Status, Self::Error> {
-        Ok(Status::Active)
+    fn verify_upgrade_and_update_state(
+        &self,
+        _ctx: ExecCtx,
+        _upgraded_client: Vec<u8>,
+        _upgraded_consensus: Vec<u8>,
+        _proofs: Vec<u8>,
+    ) -> Result<Response, Self::Error> {
+        Err(StdError::generic_err("upgrade not supported"))
+    }
+
+    fn client_state(&self, ctx: QueryCtx) -> Result<ClientState, Self::Error> {
+        self.load_client_state(ctx.deps.storage)
     }
 
-    fn timestamp(&self, _ctx: QueryCtx, _height: u64) -> Result<u64, Self::Error> {
+    fn consensus_state(
+        &self,
+        ctx: QueryCtx,
+        height: Height,
+    ) -> Result<ConsensusState, Self::Error> {
+        self.load_consensus_state(ctx.deps.storage, height)
+    }
+
+    fn status(&self, ctx: QueryCtx) -> Result<Status, Self::Error> {
+        let storage = CwStorage(ctx.deps.storage);
+        Ok(self
+            .status
+            .access(&storage)
+            .get()?
+            .unwrap_or(Status::Active))
+    }
+
+    fn latest_height(&self, ctx: QueryCtx) -> Result<Height, Self::Error> {
+        Ok(self.load_client_state(ctx.deps.storage)?.latest_height)
+    }
+
+    fn timestamp(&self, ctx: QueryCtx, height: Height) -> Result<u64, Self::Error> {
+        Ok(self
+            .load_consensus_state(ctx.deps.storage, height)?
+            .timestamp)
+    }
+
+    fn consensus_timestamp(
+        &self,
+        ctx: QueryCtx,
+        height: Height,
+    ) -> Result<Option<u64>, Self::Error> {
+        Ok(self
+            .find_consensus_state(ctx.deps.storage, height)?
+            .map(|consensus_state| consensus_state.timestamp))
+    }
+
+    // the dummy never expires, its status is set by the owner
+    fn trusting_period(&self, _ctx: QueryCtx) -> Result<u64, Self::Error> {
         Ok(u64::MAX)
     }
 
+    fn client_parameters(&self, ctx: QueryCtx) -> Result<Vec<u8>, Self::Error> {
+        Ok(self.load_client_state(ctx.deps.storage)?.data)
+    }
+
+    // the dummy never expires, nothing to prune
     fn prune(&self, _ctx: ExecCtx) -> Result<Response, Self::Error> {
         Ok(Response::default())
     }
 
     fn check_membership(
         &self,
-        _ctx: QueryCtx,
+        ctx: QueryCtx,
         _key: Vec<u8>,
         _value: Vec<u8>,
         _commitment_prefix: Vec<u8>,
-        _height: u64,
+        _height: Height,
         _proof: Vec<u8>,
     ) -> Result<bool, Self::Error> {
-        Ok(true)
+        self.load_verified(ctx.deps.storage)
+    }
+
+    fn verify_batch_membership(
+        &self,
+        ctx: QueryCtx,
+        _items: Vec<(Vec<u8>, Vec<u8>)>,
+        _commitment_prefix: Vec<u8>,
+        _height: Height,
+        _proof: Vec<u8>,
+    ) -> Result<bool, Self::Error> {
+        self.load_verified(ctx.deps.storage)
     }
 
     fn check_non_membership(
         &self,
-        _ctx: QueryCtx,
+        ctx: QueryCtx,
         _key: Vec<u8>,
         _commitment_prefix: Vec<u8>,
-        _height: u64,
+        _height: Height,
         _proof: Vec<u8>,
     ) -> Result<bool, Self::Error> {
-        Ok(true)
+        self.load_verified(ctx.deps.storage)
+    }
+
+    // proofs are not checked, nothing to consume
+    fn consume_proofs(
+        &self,
+        _ctx: ExecCtx,
+        _height: Height,
+        _count: u64,
+    ) -> Result<Response, Self::Error> {
+        Ok(Response::default())
     }
 }
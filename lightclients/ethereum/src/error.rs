@@ -0,0 +1,94 @@
+use cosmwasm_std::{StdError, VerificationError};
+use eureka_lightclient_interface::Height;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Verification(#[from] VerificationError),
+
+    #[error("no fork scheduled at epoch: {epoch}")]
+    ForkNotFound { epoch: u64 },
+
+    #[error("sync committee aggregate public key mismatch")]
+    AggregatePubkeyMismatch,
+
+    #[error("client is frozen at slot: {slot}")]
+    ClientFrozen { slot: u64 },
+
+    #[error("consensus state not found at height: {height}")]
+    ConsensusStateNotFound { height: Height },
+
+    #[error("trusted consensus state expired: timestamp: {timestamp}, trusting period: {trusting_period}, now: {now}")]
+    TrustedConsensusStateExpired {
+        timestamp: u64,
+        trusting_period: u64,
+        now: u64,
+    },
+
+    #[error("update slots must be ordered: signature: {signature_slot}, attested: {attested_slot}, finalized: {finalized_slot}")]
+    InvalidUpdateSlots {
+        signature_slot: u64,
+        attested_slot: u64,
+        finalized_slot: u64,
+    },
+
+    #[error("signature slot in the future: slot: {slot}, current slot: {current_slot}")]
+    SignatureSlotFromFuture { slot: u64, current_slot: u64 },
+
+    #[error(
+        "finalized slot not above latest slot: latest: {latest_slot}, finalized: {finalized_slot}"
+    )]
+    NonIncreasingSlot {
+        latest_slot: u64,
+        finalized_slot: u64,
+    },
+
+    #[error("sync committee period out of reach: trusted: {trusted_period}, update: {period}")]
+    UnexpectedPeriod { trusted_period: u64, period: u64 },
+
+    #[error("next sync committee unknown for period: {period}")]
+    NextSyncCommitteeUnknown { period: u64 },
+
+    #[error("sync committee bits mismatch: committee: {committee}, bits: {bits}")]
+    SyncCommitteeBitsMismatch { committee: usize, bits: usize },
+
+    #[error("insufficient sync committee participation: participants: {participants}, committee: {committee}")]
+    InsufficientParticipation {
+        participants: usize,
+        committee: usize,
+    },
+
+    #[error("invalid sync committee signature")]
+    InvalidSignature,
+
+    #[error("no execution payload before the merge, at epoch: {epoch}")]
+    ExecutionPayloadNotFound { epoch: u64 },
+
+    #[error("invalid merkle branch at generalized index: {gindex}")]
+    InvalidMerkleBranch { gindex: u64 },
+
+    #[error("invalid trie proof: {0}")]
+    InvalidTrieProof(String),
+
+    #[error("ibc contract account not found")]
+    AccountNotFound,
+
+    #[error("storage value mismatch: {key}")]
+    StorageValueMismatch { key: String },
+
+    #[error("storage value not found: {key}")]
+    StorageValueNotFound { key: String },
+
+    #[error("unexpected storage value: {key}")]
+    UnexpectedStorageValue { key: String },
+
+    #[error("no misbehaviour")]
+    NoMisbehaviour,
+
+    #[error("upgrade not supported")]
+    UpgradeNotSupported,
+}
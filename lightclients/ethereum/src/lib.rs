@@ -0,0 +1,671 @@
+// Ethereum light client, following the finalized beacon chain through its sync committee
+// headers are JSON encoded `Update`s of the altair light client sync protocol, proofs are
+// JSON encoded `StorageProof`s of the IBC contract storage in the execution state
+
+pub mod error;
+pub mod ssz;
+pub mod trie;
+pub mod verify;
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{from_json, to_json_vec, Api, HexBinary, StdError, Storage};
+use cw_storey::containers::{Item, Map};
+use cw_storey::CwStorage;
+use eureka_lightclient_interface::{
+    self as interface, apply_headers, check_each_membership, is_expired, Height, LightClient,
+    Status, UpdatePolicy, PRUNE_LIMIT,
+};
+use storey::containers::IterableAccessor;
+use sylvia::contract;
+use sylvia::cw_std::Response;
+use sylvia::types::{ExecCtx, InstantiateCtx, QueryCtx};
+
+pub use crate::error::ContractError;
+use crate::trie::{keccak256, storage_value, verify_account, verify_proof};
+use crate::verify::verify_update;
+
+// consensus forks, by their beacon state layout
+#[cw_serde]
+#[derive(Copy)]
+pub enum ForkName {
+    Phase0,
+    Altair,
+    Bellatrix,
+    Capella,
+    Deneb,
+    Electra,
+}
+
+#[cw_serde]
+pub struct Fork {
+    pub name: ForkName,
+    pub epoch: u64,
+    pub version: HexBinary,
+}
+
+#[cw_serde]
+pub struct ClientState {
+    pub chain_id: u64,
+    pub genesis_validators_root: HexBinary,
+    // in seconds
+    pub genesis_time: u64,
+    pub seconds_per_slot: u64,
+    pub slots_per_epoch: u64,
+    pub epochs_per_sync_committee_period: u64,
+    // sorted by epoch
+    pub forks: Vec<Fork>,
+    // counterparty contract whose storage holds the IBC commitments
+    pub ibc_contract_address: HexBinary,
+    // in seconds
+    pub trusting_period: u64,
+    // of the latest finalized header
+    pub latest_slot: u64,
+    pub frozen_slot: Option<u64>,
+}
+
+#[cw_serde]
+pub struct ConsensusState {
+    // execution state root of the finalized block
+    pub state_root: HexBinary,
+    // in seconds
+    pub timestamp: u64,
+}
+
+#[cw_serde]
+pub struct SyncCommittee {
+    pub pubkeys: Vec<HexBinary>,
+    pub aggregate_pubkey: HexBinary,
+}
+
+#[cw_serde]
+pub struct BeaconBlockHeader {
+    pub slot: u64,
+    pub proposer_index: u64,
+    pub parent_root: HexBinary,
+    pub state_root: HexBinary,
+    pub body_root: HexBinary,
+}
+
+#[cw_serde]
+pub struct LightClientHeader {
+    pub beacon: BeaconBlockHeader,
+    pub execution_state_root: HexBinary,
+    // proves the execution state root in the beacon block body
+    pub execution_branch: Vec<HexBinary>,
+}
+
+#[cw_serde]
+pub struct SyncAggregate {
+    // one bit per committee member, least significant first
+    pub sync_committee_bits: HexBinary,
+    pub sync_committee_signature: HexBinary,
+}
+
+#[cw_serde]
+pub struct Update {
+    pub attested_header: LightClientHeader,
+    // committee of the period after the attested header, proven in its state
+    pub next_sync_committee: Option<SyncCommittee>,
+    pub next_sync_committee_branch: Vec<HexBinary>,
+    // proven in the attested state
+    pub finalized_header: LightClientHeader,
+    pub finality_branch: Vec<HexBinary>,
+    pub sync_aggregate: SyncAggregate,
+    pub signature_slot: u64,
+}
+
+// two valid updates finalizing different headers at the same slot
+#[cw_serde]
+pub struct Misbehaviour {
+    pub update_1: Update,
+    pub update_2: Update,
+}
+
+// `eth_getProof` of the IBC contract account and of the commitment storage slot
+#[cw_serde]
+pub struct StorageProof {
+    pub account_proof: Vec<HexBinary>,
+    pub storage_proof: Vec<HexBinary>,
+}
+
+// storage slot of the commitment at `commitment_prefix` and `key` in the IBC contract
+pub fn commitment_slot(commitment_prefix: &[u8], key: &[u8]) -> [u8; 32] {
+    keccak256(&[commitment_prefix, key].concat())
+}
+
+pub struct Contract {
+    pub client_state: Item<ClientState>,
+    // finalized slot -> consensus state
+    pub consensus_states: Map<u64, Item<ConsensusState>>,
+    // committees of the period of the latest slot and of the one after
+    pub current_sync_committee: Item<SyncCommittee>,
+    pub next_sync_committee: Item<SyncCommittee>,
+}
+
+#[cfg_attr(not(feature = "library"), sylvia::entry_points)]
+#[contract]
+#[sv::error(ContractError)]
+#[sv::messages(eureka_lightclient_interface)]
+impl Contract {
+    pub const fn new() -> Self {
+        Self {
+            client_state: Item::new(b'C'),
+            consensus_states: Map::new(b'S'),
+            current_sync_committee: Item::new(b'Y'),
+            next_sync_committee: Item::new(b'N'),
+        }
+    }
+
+    #[sv::msg(instantiate)]
+    fn instantiate(
+        &self,
+        ctx: InstantiateCtx,
+        client_state: ClientState,
+        consensus_state: ConsensusState,
+        current_sync_committee: SyncCommittee,
+        next_sync_committee: Option<SyncCommittee>,
+    ) -> Result<Response, ContractError> {
+        client_state.fork_version(0)?;
+
+        for sync_committee in [Some(&current_sync_committee), next_sync_committee.as_ref()]
+            .into_iter()
+            .flatten()
+        {
+            validate_sync_committee(ctx.deps.api, sync_committee)?;
+        }
+
+        let mut storage = CwStorage(ctx.deps.storage);
+
+        self.consensus_states
+            .access(&mut storage)
+            .entry_mut(&client_state.latest_slot)
+            .set(&consensus_state)?;
+        self.current_sync_committee
+            .access(&mut storage)
+            .set(&current_sync_committee)?;
+        if let Some(next_sync_committee) = next_sync_committee {
+            self.next_sync_committee
+                .access(&mut storage)
+                .set(&next_sync_committee)?;
+        }
+        self.client_state.access(&mut storage).set(&client_state)?;
+
+        Ok(Response::default())
+    }
+}
+
+fn validate_sync_committee(
+    api: &dyn Api,
+    sync_committee: &SyncCommittee,
+) -> Result<(), ContractError> {
+    let pubkeys: Vec<u8> = sync_committee
+        .pubkeys
+        .iter()
+        .flat_map(|pubkey| pubkey.to_vec())
+        .collect();
+
+    if api.bls12_381_aggregate_g1(&pubkeys)? != sync_committee.aggregate_pubkey.as_slice() {
+        return Err(ContractError::AggregatePubkeyMismatch);
+    }
+
+    Ok(())
+}
+
+impl Contract {
+    fn load_client_state(&self, storage: &dyn Storage) -> Result<ClientState, ContractError> {
+        self.client_state
+            .access(&CwStorage(storage))
+            .get()?
+            .ok_or_else(|| StdError::not_found("client state").into())
+    }
+
+    // consensus states are at revision 0, by slot
+    fn find_consensus_state(
+        &self,
+        storage: &dyn Storage,
+        height: Height,
+    ) -> Result<Option<ConsensusState>, ContractError> {
+        if height.revision_number != 0 {
+            return Ok(None);
+        }
+
+        Ok(self
+            .consensus_states
+            .access(&CwStorage(storage))
+            .entry(&height.revision_height)
+            .get()?)
+    }
+
+    fn load_consensus_state(
+        &self,
+        storage: &dyn Storage,
+        height: Height,
+    ) -> Result<ConsensusState, ContractError> {
+        self.find_consensus_state(storage, height)?
+            .ok_or(ContractError::ConsensusStateNotFound { height })
+    }
+
+    fn verify_update(
+        &self,
+        storage: &dyn Storage,
+        api: &dyn Api,
+        update: &Update,
+    ) -> Result<(), ContractError> {
+        let storage = CwStorage(storage);
+
+        let current_sync_committee = self
+            .current_sync_committee
+            .access(&storage)
+            .get()?
+            .ok_or_else(|| StdError::not_found("sync committee"))?;
+        let next_sync_committee = self.next_sync_committee.access(&storage).get()?;
+
+        verify_update(
+            api,
+            &self.load_client_state(storage.0)?,
+            &current_sync_committee,
+            next_sync_committee.as_ref(),
+            update,
+        )
+    }
+
+    // storage value of the commitment at `commitment_prefix` and `key`, if any
+    fn load_commitment(
+        &self,
+        storage: &dyn Storage,
+        key: &[u8],
+        commitment_prefix: &[u8],
+        height: Height,
+        proof: &[u8],
+    ) -> Result<Option<Vec<u8>>, ContractError> {
+        let client_state = self.load_client_state(storage)?;
+        let consensus_state = self.load_consensus_state(storage, height)?;
+
+        let StorageProof {
+            account_proof,
+            storage_proof,
+        } = from_json(proof)?;
+
+        let storage_root = verify_account(
+            &consensus_state.state_root,
+            &client_state.ibc_contract_address,
+            &account_proof,
+        )?;
+
+        verify_proof(
+            &storage_root,
+            &commitment_slot(commitment_prefix, key),
+            &storage_proof,
+        )
+    }
+}
+
+impl LightClient for Contract {
+    type Error = ContractError;
+
+    fn update(&self, ctx: ExecCtx, header: Vec<u8>) -> Result<Response, Self::Error> {
+        let update: Update = from_json(header)?;
+
+        let mut client_state = self.load_client_state(ctx.deps.storage)?;
+
+        if let Some(slot) = client_state.frozen_slot {
+            return Err(ContractError::ClientFrozen { slot });
+        }
+
+        let now = ctx.env.block.time.seconds();
+
+        let trusted_consensus_state =
+            self.load_consensus_state(ctx.deps.storage, Height::new(0, client_state.latest_slot))?;
+
+        if is_expired(
+            trusted_consensus_state.timestamp,
+            client_state.trusting_period,
+            now,
+        ) {
+            return Err(ContractError::TrustedConsensusStateExpired {
+                timestamp: trusted_consensus_state.timestamp,
+                trusting_period: client_state.trusting_period,
+                now,
+            });
+        }
+
+        let current_slot =
+            now.saturating_sub(client_state.genesis_time) / client_state.seconds_per_slot;
+
+        if update.signature_slot > current_slot {
+            return Err(ContractError::SignatureSlotFromFuture {
+                slot: update.signature_slot,
+                current_slot,
+            });
+        }
+
+        let finalized_slot = update.finalized_header.beacon.slot;
+
+        let consensus_state = ConsensusState {
+            state_root: update.finalized_header.execution_state_root.clone(),
+            timestamp: client_state.slot_timestamp(finalized_slot),
+        };
+
+        let mut storage = CwStorage(ctx.deps.storage);
+
+        // resubmitting a finalized header is a no-op
+        if self
+            .consensus_states
+            .access(&storage)
+            .entry(&finalized_slot)
+            .get()?
+            .as_ref()
+            == Some(&consensus_state)
+        {
+            return Ok(Response::default());
+        }
+
+        if finalized_slot <= client_state.latest_slot {
+            return Err(ContractError::NonIncreasingSlot {
+                latest_slot: client_state.latest_slot,
+                finalized_slot,
+            });
+        }
+
+        self.verify_update(storage.0, ctx.deps.api, &update)?;
+
+        let trusted_period = client_state.period(client_state.latest_slot);
+        let finalized_period = client_state.period(finalized_slot);
+        let attested_period = client_state.period(update.attested_header.beacon.slot);
+
+        // the next committee is only learnt from updates attested in its previous period
+        let next_sync_committee = update
+            .next_sync_committee
+            .filter(|_| attested_period == finalized_period);
+
+        if finalized_period == trusted_period + 1 {
+            let current_sync_committee = self.next_sync_committee.access(&storage).get()?.ok_or(
+                ContractError::NextSyncCommitteeUnknown {
+                    period: finalized_period,
+                },
+            )?;
+
+            self.current_sync_committee
+                .access(&mut storage)
+                .set(&current_sync_committee)?;
+
+            match next_sync_committee {
+                Some(next_sync_committee) => self
+                    .next_sync_committee
+                    .access(&mut storage)
+                    .set(&next_sync_committee)?,
+                None => self.next_sync_committee.access(&mut storage).remove(),
+            }
+        } else if finalized_period == trusted_period {
+            if let Some(next_sync_committee) = next_sync_committee {
+                self.next_sync_committee
+                    .access(&mut storage)
+                    .set(&next_sync_committee)?;
+            }
+        } else {
+            return Err(ContractError::UnexpectedPeriod {
+                trusted_period,
+                period: finalized_period,
+            });
+        }
+
+        self.consensus_states
+            .access(&mut storage)
+            .entry_mut(&finalized_slot)
+            .set(&consensus_state)?;
+
+        client_state.latest_slot = finalized_slot;
+        self.client_state.access(&mut storage).set(&client_state)?;
+
+        Ok(Response::default())
+    }
+
+    fn update_batch(&self, ctx: ExecCtx, headers: Vec<Vec<u8>>) -> Result<Response, Self::Error> {
+        apply_headers(ctx, headers, |ctx, header| self.update(ctx, header))
+    }
+
+    // updates are verified against the trusted sync committees
+    fn update_policy(&self, _ctx: QueryCtx) -> Result<UpdatePolicy, Self::Error> {
+        Ok(UpdatePolicy::Permissionless)
+    }
+
+    fn check_for_misbehaviour(
+        &self,
+        ctx: QueryCtx,
+        misbehaviour: Vec<u8>,
+    ) -> Result<bool, Self::Error> {
+        let Misbehaviour { update_1, update_2 } = from_json(misbehaviour)?;
+
+        let finalized_1 = &update_1.finalized_header;
+        let finalized_2 = &update_2.finalized_header;
+
+        if finalized_1.beacon.slot != finalized_2.beacon.slot || finalized_1 == finalized_2 {
+            return Ok(false);
+        }
+
+        self.verify_update(ctx.deps.storage, ctx.deps.api, &update_1)?;
+        self.verify_update(ctx.deps.storage, ctx.deps.api, &update_2)?;
+
+        Ok(true)
+    }
+
+    fn update_on_misbehaviour(
+        &self,
+        ctx: ExecCtx,
+        misbehaviour: Vec<u8>,
+    ) -> Result<Response, Self::Error> {
+        let Misbehaviour { update_1, .. } = from_json(&misbehaviour)?;
+
+        if !self.check_for_misbehaviour(
+            QueryCtx::from((ctx.deps.as_ref(), ctx.env.clone())),
+            misbehaviour,
+        )? {
+            return Err(ContractError::NoMisbehaviour);
+        }
+
+        let mut client_state = self.load_client_state(ctx.deps.storage)?;
+        client_state.frozen_slot = Some(update_1.finalized_header.beacon.slot);
+
+        self.client_state
+            .access(&mut CwStorage(ctx.deps.storage))
+            .set(&client_state)?;
+
+        Ok(Response::default())
+    }
+
+    // forks are scheduled in the client state
+    fn verify_upgrade_and_update_state(
+        &self,
+        _ctx: ExecCtx,
+        _upgraded_client: Vec<u8>,
+        _upgraded_consensus: Vec<u8>,
+        _proofs: Vec<u8>,
+    ) -> Result<Response, Self::Error> {
+        Err(ContractError::UpgradeNotSupported)
+    }
+
+    fn client_state(&self, ctx: QueryCtx) -> Result<interface::ClientState, Self::Error> {
+        let client_state = self.load_client_state(ctx.deps.storage)?;
+
+        Ok(interface::ClientState {
+            chain_id: client_state.chain_id.to_string(),
+            latest_height: Height::new(0, client_state.latest_slot),
+            frozen_height: client_state.frozen_slot.map(|slot| Height::new(0, slot)),
+            data: to_json_vec(&client_state)?,
+        })
+    }
+
+    // the root is the execution state root
+    fn consensus_state(
+        &self,
+        ctx: QueryCtx,
+        height: Height,
+    ) -> Result<interface::ConsensusState, Self::Error> {
+        let consensus_state = self.load_consensus_state(ctx.deps.storage, height)?;
+
+        Ok(interface::ConsensusState {
+            timestamp: consensus_state.timestamp,
+            root: consensus_state.state_root.to_vec(),
+            data: to_json_vec(&consensus_state)?,
+        })
+    }
+
+    fn status(&self, ctx: QueryCtx) -> Result<Status, Self::Error> {
+        let client_state = self.load_client_state(ctx.deps.storage)?;
+
+        if client_state.frozen_slot.is_some() {
+            return Ok(Status::Frozen);
+        }
+
+        let Ok(consensus_state) =
+            self.load_consensus_state(ctx.deps.storage, Height::new(0, client_state.latest_slot))
+        else {
+            return Ok(Status::Unknown);
+        };
+
+        if is_expired(
+            consensus_state.timestamp,
+            client_state.trusting_period,
+            ctx.env.block.time.seconds(),
+        ) {
+            Ok(Status::Expired)
+        } else {
+            Ok(Status::Active)
+        }
+    }
+
+    fn latest_height(&self, ctx: QueryCtx) -> Result<Height, Self::Error> {
+        Ok(Height::new(
+            0,
+            self.load_client_state(ctx.deps.storage)?.latest_slot,
+        ))
+    }
+
+    fn timestamp(&self, ctx: QueryCtx, height: Height) -> Result<u64, Self::Error> {
+        Ok(self
+            .load_consensus_state(ctx.deps.storage, height)?
+            .timestamp)
+    }
+
+    fn consensus_timestamp(
+        &self,
+        ctx: QueryCtx,
+        height: Height,
+    ) -> Result<Option<u64>, Self::Error> {
+        Ok(self
+            .find_consensus_state(ctx.deps.storage, height)?
+            .map(|consensus_state| consensus_state.timestamp))
+    }
+
+    fn trusting_period(&self, ctx: QueryCtx) -> Result<u64, Self::Error> {
+        Ok(self.load_client_state(ctx.deps.storage)?.trusting_period)
+    }
+
+    fn client_parameters(&self, ctx: QueryCtx) -> Result<Vec<u8>, Self::Error> {
+        Ok(to_json_vec(&ClientState {
+            trusting_period: 0,
+            latest_slot: 0,
+            frozen_slot: None,
+            ..self.load_client_state(ctx.deps.storage)?
+        })?)
+    }
+
+    fn prune(&self, ctx: ExecCtx) -> Result<Response, Self::Error> {
+        let client_state = self.load_client_state(ctx.deps.storage)?;
+
+        let now = ctx.env.block.time.seconds();
+
+        let mut storage = CwStorage(ctx.deps.storage);
+
+        let mut expired = vec![];
+
+        for pair in self.consensus_states.access(&storage).pairs() {
+            let ((slot, ()), consensus_state) =
+                pair.map_err(|_| StdError::generic_err("invalid consensus state"))?;
+
+            if expired.len() == PRUNE_LIMIT
+                || slot >= client_state.latest_slot
+                || !is_expired(consensus_state.timestamp, client_state.trusting_period, now)
+            {
+                break;
+            }
+
+            expired.push(slot);
+        }
+
+        for slot in expired {
+            self.consensus_states
+                .access(&mut storage)
+                .entry_mut(&slot)
+                .remove();
+        }
+
+        Ok(Response::default())
+    }
+
+    fn check_membership(
+        &self,
+        ctx: QueryCtx,
+        key: Vec<u8>,
+        value: Vec<u8>,
+        commitment_prefix: Vec<u8>,
+        height: Height,
+        proof: Vec<u8>,
+    ) -> Result<bool, Self::Error> {
+        match self.load_commitment(ctx.deps.storage, &key, &commitment_prefix, height, &proof)? {
+            Some(stored) if stored == storage_value(&value) => Ok(true),
+            Some(_) => Err(ContractError::StorageValueMismatch {
+                key: String::from_utf8_lossy(&key).into_owned(),
+            }),
+            None => Err(ContractError::StorageValueNotFound {
+                key: String::from_utf8_lossy(&key).into_owned(),
+            }),
+        }
+    }
+
+    fn verify_batch_membership(
+        &self,
+        ctx: QueryCtx,
+        items: Vec<(Vec<u8>, Vec<u8>)>,
+        commitment_prefix: Vec<u8>,
+        height: Height,
+        proof: Vec<u8>,
+    ) -> Result<bool, Self::Error> {
+        check_each_membership(items, &proof, |key, value, proof| {
+            self.check_membership(
+                QueryCtx::from((ctx.deps, ctx.env.clone())),
+                key,
+                value,
+                commitment_prefix.clone(),
+                height,
+                proof,
+            )
+        })
+    }
+
+    fn check_non_membership(
+        &self,
+        ctx: QueryCtx,
+        key: Vec<u8>,
+        commitment_prefix: Vec<u8>,
+        height: Height,
+        proof: Vec<u8>,
+    ) -> Result<bool, Self::Error> {
+        match self.load_commitment(ctx.deps.storage, &key, &commitment_prefix, height, &proof)? {
+            Some(_) => Err(ContractError::UnexpectedStorageValue {
+                key: String::from_utf8_lossy(&key).into_owned(),
+            }),
+            None => Ok(true),
+        }
+    }
+
+    // proofs are checked against the stored state roots, they stay valid
+    fn consume_proofs(
+        &self,
+        _ctx: ExecCtx,
+        _height: Height,
+        _count: u64,
+    ) -> Result<Response, Self::Error> {
+        Ok(Response::default())
+    }
+}
@@ -0,0 +1,105 @@
+// SSZ merkleization of the few beacon chain containers the client needs
+
+use cosmwasm_std::HexBinary;
+use sha2::{Digest, Sha256};
+
+use crate::{BeaconBlockHeader, SyncCommittee};
+
+pub type Root = [u8; 32];
+
+pub fn hash(left: &[u8], right: &[u8]) -> Root {
+    Sha256::digest([left, right].concat()).into()
+}
+
+// root of `chunks`, padded with zero chunks to a power of two
+pub fn merkleize(mut chunks: Vec<Root>) -> Root {
+    let width = chunks.len().next_power_of_two();
+    chunks.resize(width, Root::default());
+
+    while chunks.len() > 1 {
+        chunks = chunks
+            .chunks(2)
+            .map(|pair| hash(&pair[0], &pair[1]))
+            .collect();
+    }
+
+    chunks[0]
+}
+
+pub fn uint64_root(value: u64) -> Root {
+    let mut root = Root::default();
+    root[..8].copy_from_slice(&value.to_le_bytes());
+    root
+}
+
+// root of a fixed size byte vector, packed in chunks
+pub fn bytes_root(bytes: &[u8]) -> Root {
+    merkleize(
+        bytes
+            .chunks(32)
+            .map(|chunk| {
+                let mut root = Root::default();
+                root[..chunk.len()].copy_from_slice(chunk);
+                root
+            })
+            .collect(),
+    )
+}
+
+// whether `leaf` is at generalized index `gindex` below `root`
+pub fn is_valid_merkle_branch(leaf: Root, branch: &[HexBinary], gindex: u64, root: &[u8]) -> bool {
+    if branch.len() != gindex.ilog2() as usize {
+        return false;
+    }
+
+    let value = branch
+        .iter()
+        .enumerate()
+        .fold(leaf, |value, (level, sibling)| {
+            if gindex >> level & 1 == 1 {
+                hash(sibling, &value)
+            } else {
+                hash(&value, sibling)
+            }
+        });
+
+    value == root
+}
+
+impl BeaconBlockHeader {
+    pub fn hash_tree_root(&self) -> Root {
+        merkleize(vec![
+            uint64_root(self.slot),
+            uint64_root(self.proposer_index),
+            bytes_root(&self.parent_root),
+            bytes_root(&self.state_root),
+            bytes_root(&self.body_root),
+        ])
+    }
+}
+
+impl SyncCommittee {
+    pub fn hash_tree_root(&self) -> Root {
+        hash(
+            &merkleize(
+                self.pubkeys
+                    .iter()
+                    .map(|pubkey| bytes_root(pubkey))
+                    .collect(),
+            ),
+            &bytes_root(&self.aggregate_pubkey),
+        )
+    }
+}
+
+// domain of sync committee signatures under the fork `version`
+pub fn sync_committee_domain(version: &[u8], genesis_validators_root: &[u8]) -> Root {
+    const DOMAIN_SYNC_COMMITTEE: [u8; 4] = [7, 0, 0, 0];
+
+    let fork_data_root = hash(&bytes_root(version), genesis_validators_root);
+
+    let mut domain = Root::default();
+    domain[..4].copy_from_slice(&DOMAIN_SYNC_COMMITTEE);
+    domain[4..].copy_from_slice(&fork_data_root[..28]);
+    domain
+}
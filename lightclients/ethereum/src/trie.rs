@@ -0,0 +1,236 @@
+// Merkle-Patricia trie proofs, as returned by `eth_getProof`
+// keys are hashed with keccak256 before lookup, as in the state and storage tries
+
+use cosmwasm_std::HexBinary;
+use sha3::{Digest, Keccak256};
+
+use crate::error::ContractError;
+
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    Keccak256::digest(data).into()
+}
+
+fn invalid(reason: &str) -> ContractError {
+    ContractError::InvalidTrieProof(reason.to_string())
+}
+
+// decoded RLP item, along with its encoding
+pub struct Rlp<'a> {
+    pub raw: &'a [u8],
+    pub item: RlpItem<'a>,
+}
+
+pub enum RlpItem<'a> {
+    Bytes(&'a [u8]),
+    List(Vec<Rlp<'a>>),
+}
+
+impl<'a> Rlp<'a> {
+    // the whole of `data` must be a single item
+    pub fn decode(data: &'a [u8]) -> Result<Self, ContractError> {
+        match Self::decode_prefix(data)? {
+            (rlp, []) => Ok(rlp),
+            _ => Err(invalid("trailing rlp bytes")),
+        }
+    }
+
+    fn decode_prefix(data: &'a [u8]) -> Result<(Self, &'a [u8]), ContractError> {
+        let (&prefix, rest) = data.split_first().ok_or_else(|| invalid("empty rlp"))?;
+
+        let (is_list, offset, length) = match prefix {
+            0x00..=0x7f => (false, 0, 1),
+            0x80..=0xb7 => (false, 1, (prefix - 0x80) as usize),
+            0xb8..=0xbf => {
+                let size = (prefix - 0xb7) as usize;
+                (false, 1 + size, Self::decode_length(rest, size)?)
+            }
+            0xc0..=0xf7 => (true, 1, (prefix - 0xc0) as usize),
+            0xf8..=0xff => {
+                let size = (prefix - 0xf7) as usize;
+                (true, 1 + size, Self::decode_length(rest, size)?)
+            }
+        };
+
+        let end = offset
+            .checked_add(length)
+            .filter(|end| *end <= data.len())
+            .ok_or_else(|| invalid("rlp item out of bounds"))?;
+
+        let payload = &data[offset..end];
+
+        let item = if is_list {
+            let mut items = vec![];
+            let mut rest = payload;
+            while !rest.is_empty() {
+                let (item, tail) = Self::decode_prefix(rest)?;
+                items.push(item);
+                rest = tail;
+            }
+            RlpItem::List(items)
+        } else {
+            RlpItem::Bytes(payload)
+        };
+
+        Ok((
+            Self {
+                raw: &data[..end],
+                item,
+            },
+            &data[end..],
+        ))
+    }
+
+    fn decode_length(data: &[u8], size: usize) -> Result<usize, ContractError> {
+        if size > data.len() {
+            return Err(invalid("rlp length out of bounds"));
+        }
+
+        // lengths above `usize::MAX` cannot be in bounds, e.g. 8 byte lengths on wasm32
+        data[..size]
+            .iter()
+            .try_fold(0usize, |length, byte| {
+                length
+                    .checked_mul(1 << 8)
+                    .map(|length| length | *byte as usize)
+            })
+            .ok_or_else(|| invalid("rlp length out of bounds"))
+    }
+
+    pub fn bytes(&self) -> Result<&'a [u8], ContractError> {
+        match self.item {
+            RlpItem::Bytes(bytes) => Ok(bytes),
+            RlpItem::List(_) => Err(invalid("expected rlp bytes")),
+        }
+    }
+
+    pub fn list(&self) -> Result<&[Self], ContractError> {
+        match &self.item {
+            RlpItem::List(items) => Ok(items),
+            RlpItem::Bytes(_) => Err(invalid("expected rlp list")),
+        }
+    }
+}
+
+// nibbles of a hex-prefix encoded path, and whether it ends in a leaf
+fn decode_path(encoded: &[u8]) -> Result<(Vec<u8>, bool), ContractError> {
+    let (&first, rest) = encoded
+        .split_first()
+        .ok_or_else(|| invalid("empty node path"))?;
+
+    let flag = first >> 4;
+
+    if flag > 3 {
+        return Err(invalid("invalid node path flag"));
+    }
+
+    let mut nibbles = vec![];
+    if flag & 1 == 1 {
+        nibbles.push(first & 0x0f);
+    }
+    nibbles.extend(rest.iter().flat_map(|byte| [byte >> 4, byte & 0x0f]));
+
+    Ok((nibbles, flag & 2 == 2))
+}
+
+// value at `key` in the trie of `root`, `None` if the proof shows its absence
+pub fn verify_proof(
+    root: &[u8],
+    key: &[u8],
+    proof: &[HexBinary],
+) -> Result<Option<Vec<u8>>, ContractError> {
+    // keccak256 of the encoded empty string
+    if root == keccak256(&[0x80]) && proof.is_empty() {
+        return Ok(None);
+    }
+
+    let path: Vec<u8> = keccak256(key)
+        .iter()
+        .flat_map(|byte| [byte >> 4, byte & 0x0f])
+        .collect();
+
+    let mut nodes = proof.iter();
+    let mut expected = root.to_vec();
+    let mut position = 0;
+
+    let value = loop {
+        // references below 32 bytes are embedded in their parent
+        let node = if expected.len() == 32 {
+            let node = nodes.next().ok_or_else(|| invalid("missing trie node"))?;
+            if keccak256(node) != expected[..] {
+                return Err(invalid("trie node hash mismatch"));
+            }
+            node.to_vec()
+        } else {
+            expected
+        };
+
+        let node = Rlp::decode(&node)?;
+
+        let next = match node.list()? {
+            [branches @ .., value] if branches.len() == 16 => match path.get(position) {
+                Some(nibble) => {
+                    position += 1;
+                    &branches[*nibble as usize]
+                }
+                None => break Some(value.bytes()?.to_vec()),
+            },
+            [node_path, child] => {
+                let (node_path, is_leaf) = decode_path(node_path.bytes()?)?;
+
+                if !path[position..].starts_with(&node_path) {
+                    break None;
+                }
+
+                position += node_path.len();
+
+                if is_leaf {
+                    break (position == path.len())
+                        .then(|| child.bytes().map(<[u8]>::to_vec))
+                        .transpose()?;
+                }
+
+                child
+            }
+            _ => return Err(invalid("invalid trie node")),
+        };
+
+        expected = match next.item {
+            RlpItem::Bytes([]) => break None,
+            RlpItem::Bytes(hash) if hash.len() == 32 => hash.to_vec(),
+            RlpItem::List(_) => next.raw.to_vec(),
+            RlpItem::Bytes(_) => return Err(invalid("invalid trie node reference")),
+        };
+    };
+
+    if nodes.next().is_some() {
+        return Err(invalid("unused trie nodes"));
+    }
+
+    Ok(value.filter(|value| !value.is_empty()))
+}
+
+// storage root of the account at `address`
+pub fn verify_account(
+    state_root: &[u8],
+    address: &[u8],
+    proof: &[HexBinary],
+) -> Result<Vec<u8>, ContractError> {
+    let account =
+        verify_proof(state_root, address, proof)?.ok_or(ContractError::AccountNotFound)?;
+
+    // nonce, balance, storage root and code hash
+    match Rlp::decode(&account)?.list()? {
+        [_, _, storage_root, _] => Ok(storage_root.bytes()?.to_vec()),
+        _ => Err(invalid("invalid account")),
+    }
+}
+
+// storage slot values are encoded integers, without leading zeros
+pub fn storage_value(value: &[u8]) -> Vec<u8> {
+    let value = &value[value.iter().take_while(|byte| **byte == 0).count()..];
+
+    match value {
+        [byte] if *byte < 0x80 => vec![*byte],
+        _ => [&[0x80 + value.len() as u8], value].concat(),
+    }
+}
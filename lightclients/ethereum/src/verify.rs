@@ -0,0 +1,221 @@
+use cosmwasm_std::{Api, HashFunction, BLS12_381_G1_GENERATOR};
+
+use crate::error::ContractError;
+use crate::ssz::{is_valid_merkle_branch, sync_committee_domain, Root};
+use crate::{ClientState, Fork, ForkName, LightClientHeader, SyncCommittee, Update};
+
+// generalized indices in the beacon state, one level deeper since electra
+const FINALIZED_ROOT_GINDEX: u64 = 105;
+const NEXT_SYNC_COMMITTEE_GINDEX: u64 = 55;
+const FINALIZED_ROOT_GINDEX_ELECTRA: u64 = 169;
+const NEXT_SYNC_COMMITTEE_GINDEX_ELECTRA: u64 = 87;
+// `state_root` of the execution payload header, itself at 25 in the block body, one level
+// deeper since deneb
+const EXECUTION_STATE_ROOT_GINDEX: u64 = 25 * 16 + 2;
+const EXECUTION_STATE_ROOT_GINDEX_DENEB: u64 = 25 * 32 + 2;
+
+const BLS_DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+impl ClientState {
+    pub fn epoch(&self, slot: u64) -> u64 {
+        slot / self.slots_per_epoch
+    }
+
+    pub fn period(&self, slot: u64) -> u64 {
+        self.epoch(slot) / self.epochs_per_sync_committee_period
+    }
+
+    // in seconds
+    pub fn slot_timestamp(&self, slot: u64) -> u64 {
+        self.genesis_time + slot * self.seconds_per_slot
+    }
+
+    pub fn fork(&self, epoch: u64) -> Result<&Fork, ContractError> {
+        self.forks
+            .iter()
+            .rev()
+            .find(|fork| fork.epoch <= epoch)
+            .ok_or(ContractError::ForkNotFound { epoch })
+    }
+
+    pub fn fork_version(&self, epoch: u64) -> Result<&[u8], ContractError> {
+        Ok(self.fork(epoch)?.version.as_slice())
+    }
+}
+
+impl ForkName {
+    // of the finalized checkpoint root and of the next sync committee in the beacon state
+    pub fn state_gindices(self) -> (u64, u64) {
+        match self {
+            Self::Electra => (
+                FINALIZED_ROOT_GINDEX_ELECTRA,
+                NEXT_SYNC_COMMITTEE_GINDEX_ELECTRA,
+            ),
+            _ => (FINALIZED_ROOT_GINDEX, NEXT_SYNC_COMMITTEE_GINDEX),
+        }
+    }
+
+    // of the execution state root in the block body, `None` before the merge
+    pub fn execution_state_root_gindex(self) -> Option<u64> {
+        match self {
+            Self::Phase0 | Self::Altair => None,
+            Self::Bellatrix | Self::Capella => Some(EXECUTION_STATE_ROOT_GINDEX),
+            Self::Deneb | Self::Electra => Some(EXECUTION_STATE_ROOT_GINDEX_DENEB),
+        }
+    }
+}
+
+// the execution state root is committed in the beacon block body, laid out as of its fork
+fn verify_execution(
+    client_state: &ClientState,
+    header: &LightClientHeader,
+) -> Result<(), ContractError> {
+    let epoch = client_state.epoch(header.beacon.slot);
+    let gindex = client_state
+        .fork(epoch)?
+        .name
+        .execution_state_root_gindex()
+        .ok_or(ContractError::ExecutionPayloadNotFound { epoch })?;
+
+    let leaf = Root::try_from(header.execution_state_root.as_slice())
+        .map_err(|_| ContractError::InvalidMerkleBranch { gindex })?;
+
+    if !is_valid_merkle_branch(
+        leaf,
+        &header.execution_branch,
+        gindex,
+        &header.beacon.body_root,
+    ) {
+        return Err(ContractError::InvalidMerkleBranch { gindex });
+    }
+
+    Ok(())
+}
+
+// aggregate signature of the participating sync committee members over the attested header
+fn verify_sync_aggregate(
+    api: &dyn Api,
+    client_state: &ClientState,
+    sync_committee: &SyncCommittee,
+    update: &Update,
+) -> Result<(), ContractError> {
+    let bits = &update.sync_aggregate.sync_committee_bits;
+
+    if bits.len() * 8 != sync_committee.pubkeys.len() {
+        return Err(ContractError::SyncCommitteeBitsMismatch {
+            committee: sync_committee.pubkeys.len(),
+            bits: bits.len() * 8,
+        });
+    }
+
+    let pubkeys: Vec<u8> = sync_committee
+        .pubkeys
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| bits[index / 8] >> (index % 8) & 1 == 1)
+        .flat_map(|(_, pubkey)| pubkey.to_vec())
+        .collect();
+
+    let participants = pubkeys.len() / 48;
+
+    // a supermajority of the committee, as for finality on the beacon chain
+    if participants * 3 < sync_committee.pubkeys.len() * 2 {
+        return Err(ContractError::InsufficientParticipation {
+            participants,
+            committee: sync_committee.pubkeys.len(),
+        });
+    }
+
+    // signed with the fork of the slot before the signature slot
+    let version =
+        client_state.fork_version(client_state.epoch(update.signature_slot.max(1) - 1))?;
+
+    let signing_root = crate::ssz::hash(
+        &update.attested_header.beacon.hash_tree_root(),
+        &sync_committee_domain(version, &client_state.genesis_validators_root),
+    );
+
+    let pubkey = api.bls12_381_aggregate_g1(&pubkeys)?;
+    let message = api.bls12_381_hash_to_g2(HashFunction::Sha256, &signing_root, BLS_DST)?;
+
+    if !api.bls12_381_pairing_equality(
+        &BLS12_381_G1_GENERATOR,
+        &update.sync_aggregate.sync_committee_signature,
+        &pubkey,
+        &message,
+    )? {
+        return Err(ContractError::InvalidSignature);
+    }
+
+    Ok(())
+}
+
+// checks `update` against the trusted sync committees, see the altair light client sync protocol
+pub fn verify_update(
+    api: &dyn Api,
+    client_state: &ClientState,
+    current_sync_committee: &SyncCommittee,
+    next_sync_committee: Option<&SyncCommittee>,
+    update: &Update,
+) -> Result<(), ContractError> {
+    let attested = &update.attested_header.beacon;
+    let finalized = &update.finalized_header.beacon;
+
+    if update.signature_slot <= attested.slot || attested.slot < finalized.slot {
+        return Err(ContractError::InvalidUpdateSlots {
+            signature_slot: update.signature_slot,
+            attested_slot: attested.slot,
+            finalized_slot: finalized.slot,
+        });
+    }
+
+    let trusted_period = client_state.period(client_state.latest_slot);
+    let signature_period = client_state.period(update.signature_slot);
+
+    let sync_committee = if signature_period == trusted_period {
+        current_sync_committee
+    } else if signature_period == trusted_period + 1 {
+        next_sync_committee.ok_or(ContractError::NextSyncCommitteeUnknown {
+            period: signature_period,
+        })?
+    } else {
+        return Err(ContractError::UnexpectedPeriod {
+            trusted_period,
+            period: signature_period,
+        });
+    };
+
+    verify_execution(client_state, &update.finalized_header)?;
+
+    // proven in the attested state, laid out as of its fork
+    let (finalized_root_gindex, next_sync_committee_gindex) = client_state
+        .fork(client_state.epoch(attested.slot))?
+        .name
+        .state_gindices();
+
+    if !is_valid_merkle_branch(
+        finalized.hash_tree_root(),
+        &update.finality_branch,
+        finalized_root_gindex,
+        &attested.state_root,
+    ) {
+        return Err(ContractError::InvalidMerkleBranch {
+            gindex: finalized_root_gindex,
+        });
+    }
+
+    if let Some(next_sync_committee) = &update.next_sync_committee {
+        if !is_valid_merkle_branch(
+            next_sync_committee.hash_tree_root(),
+            &update.next_sync_committee_branch,
+            next_sync_committee_gindex,
+            &attested.state_root,
+        ) {
+            return Err(ContractError::InvalidMerkleBranch {
+                gindex: next_sync_committee_gindex,
+            });
+        }
+    }
+
+    verify_sync_aggregate(api, client_state, sync_committee, update)
+}
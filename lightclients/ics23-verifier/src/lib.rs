@@ -0,0 +1,963 @@
+// ICS23 commitment proofs, as produced by cosmos-sdk stores
+// decodes ibc-go `MerkleProof`s and verifies them through the module store and the multistore
+// only the existence and non-existence proofs of the IAVL and Tendermint specs are supported,
+// along with batches of existence proofs
+
+use prost::Message;
+use sha2::{Digest, Sha256, Sha512};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ProofError {
+    #[error("proof decoding failed: {0}")]
+    Decode(String),
+
+    #[error("unsupported operation: {0}")]
+    UnsupportedOperation(String),
+
+    #[error("proof does not match spec: {0}")]
+    SpecMismatch(String),
+
+    #[error("proven key does not match")]
+    KeyMismatch,
+
+    #[error("proven value does not match")]
+    ValueMismatch,
+
+    #[error("calculated root does not match")]
+    RootMismatch,
+
+    #[error("invalid non-existence proof: {0}")]
+    InvalidNonExistence(String),
+
+    #[error("proof count mismatch: expected: {expected}, got: {got}")]
+    ProofCountMismatch { expected: usize, got: usize },
+
+    #[error("batch items are not in the same store")]
+    StoreMismatch,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+pub enum HashOp {
+    NoHash = 0,
+    Sha256 = 1,
+    Sha512 = 2,
+    Keccak256 = 3,
+    Ripemd160 = 4,
+    Bitcoin = 5,
+    Sha512256 = 6,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+pub enum LengthOp {
+    NoPrefix = 0,
+    VarProto = 1,
+    VarRlp = 2,
+    Fixed32Big = 3,
+    Fixed32Little = 4,
+    Fixed64Big = 5,
+    Fixed64Little = 6,
+    Require32Bytes = 7,
+    Require64Bytes = 8,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct LeafOp {
+    #[prost(enumeration = "HashOp", tag = "1")]
+    pub hash: i32,
+    #[prost(enumeration = "HashOp", tag = "2")]
+    pub prehash_key: i32,
+    #[prost(enumeration = "HashOp", tag = "3")]
+    pub prehash_value: i32,
+    #[prost(enumeration = "LengthOp", tag = "4")]
+    pub length: i32,
+    #[prost(bytes = "vec", tag = "5")]
+    pub prefix: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct InnerOp {
+    #[prost(enumeration = "HashOp", tag = "1")]
+    pub hash: i32,
+    #[prost(bytes = "vec", tag = "2")]
+    pub prefix: Vec<u8>,
+    #[prost(bytes = "vec", tag = "3")]
+    pub suffix: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ExistenceProof {
+    #[prost(bytes = "vec", tag = "1")]
+    pub key: Vec<u8>,
+    #[prost(bytes = "vec", tag = "2")]
+    pub value: Vec<u8>,
+    #[prost(message, optional, tag = "3")]
+    pub leaf: Option<LeafOp>,
+    #[prost(message, repeated, tag = "4")]
+    pub path: Vec<InnerOp>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct NonExistenceProof {
+    #[prost(bytes = "vec", tag = "1")]
+    pub key: Vec<u8>,
+    #[prost(message, optional, tag = "2")]
+    pub left: Option<ExistenceProof>,
+    #[prost(message, optional, tag = "3")]
+    pub right: Option<ExistenceProof>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct CommitmentProof {
+    #[prost(oneof = "commitment_proof::Proof", tags = "1, 2, 3, 4")]
+    pub proof: Option<commitment_proof::Proof>,
+}
+
+pub mod commitment_proof {
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum Proof {
+        #[prost(message, tag = "1")]
+        Exist(super::ExistenceProof),
+        #[prost(message, tag = "2")]
+        Nonexist(super::NonExistenceProof),
+        #[prost(message, tag = "3")]
+        Batch(super::BatchProof),
+        #[prost(message, tag = "4")]
+        Compressed(super::CompressedBatchProof),
+    }
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct BatchProof {
+    #[prost(message, repeated, tag = "1")]
+    pub entries: Vec<BatchEntry>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct BatchEntry {
+    #[prost(oneof = "batch_entry::Proof", tags = "1, 2")]
+    pub proof: Option<batch_entry::Proof>,
+}
+
+pub mod batch_entry {
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum Proof {
+        #[prost(message, tag = "1")]
+        Exist(super::ExistenceProof),
+        #[prost(message, tag = "2")]
+        Nonexist(super::NonExistenceProof),
+    }
+}
+
+// batch whose inner ops are shared through `lookup_inners`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct CompressedBatchProof {
+    #[prost(message, repeated, tag = "1")]
+    pub entries: Vec<CompressedBatchEntry>,
+    #[prost(message, repeated, tag = "2")]
+    pub lookup_inners: Vec<InnerOp>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct CompressedBatchEntry {
+    #[prost(oneof = "compressed_batch_entry::Proof", tags = "1, 2")]
+    pub proof: Option<compressed_batch_entry::Proof>,
+}
+
+pub mod compressed_batch_entry {
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum Proof {
+        #[prost(message, tag = "1")]
+        Exist(super::CompressedExistenceProof),
+        #[prost(message, tag = "2")]
+        Nonexist(super::CompressedNonExistenceProof),
+    }
+}
+
+// existence proof whose path indexes the inner ops of the batch
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct CompressedExistenceProof {
+    #[prost(bytes = "vec", tag = "1")]
+    pub key: Vec<u8>,
+    #[prost(bytes = "vec", tag = "2")]
+    pub value: Vec<u8>,
+    #[prost(message, optional, tag = "3")]
+    pub leaf: Option<LeafOp>,
+    #[prost(int32, repeated, tag = "4")]
+    pub path: Vec<i32>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct CompressedNonExistenceProof {
+    #[prost(bytes = "vec", tag = "1")]
+    pub key: Vec<u8>,
+    #[prost(message, optional, tag = "2")]
+    pub left: Option<CompressedExistenceProof>,
+    #[prost(message, optional, tag = "3")]
+    pub right: Option<CompressedExistenceProof>,
+}
+
+// ibc-go `MerkleProof`, one commitment proof per store, innermost first
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MerkleProof {
+    #[prost(message, repeated, tag = "1")]
+    pub proofs: Vec<CommitmentProof>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct InnerSpec {
+    pub child_order: Vec<usize>,
+    pub child_size: usize,
+    pub min_prefix_length: usize,
+    pub max_prefix_length: usize,
+    pub empty_child: Vec<u8>,
+    pub hash: HashOp,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProofSpec {
+    pub leaf_spec: LeafOp,
+    pub inner_spec: InnerSpec,
+    pub max_depth: usize,
+    pub min_depth: usize,
+}
+
+// cosmos-sdk IAVL store
+pub fn iavl_spec() -> ProofSpec {
+    ProofSpec {
+        leaf_spec: LeafOp {
+            hash: HashOp::Sha256.into(),
+            prehash_key: HashOp::NoHash.into(),
+            prehash_value: HashOp::Sha256.into(),
+            length: LengthOp::VarProto.into(),
+            prefix: vec![0],
+        },
+        inner_spec: InnerSpec {
+            child_order: vec![0, 1],
+            child_size: 33,
+            min_prefix_length: 4,
+            max_prefix_length: 12,
+            empty_child: vec![],
+            hash: HashOp::Sha256,
+        },
+        max_depth: 0,
+        min_depth: 0,
+    }
+}
+
+// tendermint simple merkle tree, used by the cosmos-sdk multistore
+pub fn tendermint_spec() -> ProofSpec {
+    ProofSpec {
+        leaf_spec: LeafOp {
+            hash: HashOp::Sha256.into(),
+            prehash_key: HashOp::NoHash.into(),
+            prehash_value: HashOp::Sha256.into(),
+            length: LengthOp::VarProto.into(),
+            prefix: vec![0],
+        },
+        inner_spec: InnerSpec {
+            child_order: vec![0, 1],
+            child_size: 32,
+            min_prefix_length: 1,
+            max_prefix_length: 1,
+            empty_child: vec![],
+            hash: HashOp::Sha256,
+        },
+        max_depth: 0,
+        min_depth: 0,
+    }
+}
+
+// cosmos-sdk chain: the IAVL module store, then the multistore, innermost first
+pub fn sdk_specs() -> Vec<ProofSpec> {
+    vec![iavl_spec(), tendermint_spec()]
+}
+
+// multistore store names holding provable state
+pub const IBC_STORE: &[u8] = b"ibc";
+pub const WASM_STORE: &[u8] = b"wasm";
+
+// wasm module key prefix of contract storage, followed by the contract address
+pub const WASM_CONTRACT_STORE_PREFIX: u8 = 0x03;
+
+// path of `key` under `prefix`, outermost first
+// the prefix is a module store name, optionally followed by `/` and a key prefix within the store
+pub fn apply_prefix(prefix: &[u8], key: &[u8]) -> Vec<Vec<u8>> {
+    match prefix.iter().position(|byte| *byte == b'/') {
+        Some(index) => vec![
+            prefix[..index].to_vec(),
+            [&prefix[index + 1..], key].concat(),
+        ],
+        None => vec![prefix.to_vec(), key.to_vec()],
+    }
+}
+
+// prefix of the raw storage of the contract at canonical address `contract`
+// e.g. the counterparty tao, which commits packets to its own storage
+pub fn wasm_commitment_prefix(contract: &[u8]) -> Vec<u8> {
+    [WASM_STORE, b"/", &[WASM_CONTRACT_STORE_PREFIX], contract].concat()
+}
+
+fn hash_op(op: i32) -> Result<HashOp, ProofError> {
+    HashOp::try_from(op).map_err(|_| ProofError::UnsupportedOperation(format!("hash op {op}")))
+}
+
+fn length_op(op: i32) -> Result<LengthOp, ProofError> {
+    LengthOp::try_from(op).map_err(|_| ProofError::UnsupportedOperation(format!("length op {op}")))
+}
+
+fn do_hash(op: HashOp, data: &[u8]) -> Result<Vec<u8>, ProofError> {
+    match op {
+        HashOp::NoHash => Ok(data.to_vec()),
+        HashOp::Sha256 => Ok(Sha256::digest(data).to_vec()),
+        HashOp::Sha512 => Ok(Sha512::digest(data).to_vec()),
+        op => Err(ProofError::UnsupportedOperation(format!("{op:?}"))),
+    }
+}
+
+fn do_length(op: LengthOp, data: &[u8]) -> Result<Vec<u8>, ProofError> {
+    match op {
+        LengthOp::NoPrefix => Ok(data.to_vec()),
+        LengthOp::VarProto => {
+            let mut out = vec![];
+            prost::encoding::encode_varint(data.len() as u64, &mut out);
+            out.extend_from_slice(data);
+            Ok(out)
+        }
+        LengthOp::Require32Bytes if data.len() == 32 => Ok(data.to_vec()),
+        LengthOp::Require64Bytes if data.len() == 64 => Ok(data.to_vec()),
+        LengthOp::Fixed32Big => Ok([&(data.len() as u32).to_be_bytes()[..], data].concat()),
+        LengthOp::Fixed32Little => Ok([&(data.len() as u32).to_le_bytes()[..], data].concat()),
+        op => Err(ProofError::UnsupportedOperation(format!("{op:?}"))),
+    }
+}
+
+fn prepare_leaf_data(prehash: i32, length: i32, data: &[u8]) -> Result<Vec<u8>, ProofError> {
+    do_length(length_op(length)?, &do_hash(hash_op(prehash)?, data)?)
+}
+
+fn apply_leaf(leaf: &LeafOp, key: &[u8], value: &[u8]) -> Result<Vec<u8>, ProofError> {
+    if key.is_empty() {
+        return Err(ProofError::SpecMismatch("leaf with empty key".to_string()));
+    }
+
+    if value.is_empty() {
+        return Err(ProofError::SpecMismatch(
+            "leaf with empty value".to_string(),
+        ));
+    }
+
+    let data = [
+        &leaf.prefix[..],
+        &prepare_leaf_data(leaf.prehash_key, leaf.length, key)?,
+        &prepare_leaf_data(leaf.prehash_value, leaf.length, value)?,
+    ]
+    .concat();
+
+    do_hash(hash_op(leaf.hash)?, &data)
+}
+
+fn apply_inner(inner: &InnerOp, child: &[u8]) -> Result<Vec<u8>, ProofError> {
+    if child.is_empty() {
+        return Err(ProofError::SpecMismatch(
+            "inner op with empty child".to_string(),
+        ));
+    }
+
+    let data = [&inner.prefix[..], child, &inner.suffix[..]].concat();
+
+    do_hash(hash_op(inner.hash)?, &data)
+}
+
+pub fn calculate_existence_root(proof: &ExistenceProof) -> Result<Vec<u8>, ProofError> {
+    let leaf = proof
+        .leaf
+        .as_ref()
+        .ok_or_else(|| ProofError::SpecMismatch("missing leaf op".to_string()))?;
+
+    let mut hash = apply_leaf(leaf, &proof.key, &proof.value)?;
+
+    for step in &proof.path {
+        hash = apply_inner(step, &hash)?;
+    }
+
+    Ok(hash)
+}
+
+fn ensure_leaf(leaf: &LeafOp, spec: &LeafOp) -> Result<(), ProofError> {
+    if leaf.hash != spec.hash
+        || leaf.prehash_key != spec.prehash_key
+        || leaf.prehash_value != spec.prehash_value
+        || leaf.length != spec.length
+        || !leaf.prefix.starts_with(&spec.prefix)
+    {
+        return Err(ProofError::SpecMismatch("unexpected leaf op".to_string()));
+    }
+
+    Ok(())
+}
+
+fn ensure_inner(inner: &InnerOp, spec: &ProofSpec) -> Result<(), ProofError> {
+    let inner_spec = &spec.inner_spec;
+
+    if hash_op(inner.hash)? != inner_spec.hash {
+        return Err(ProofError::SpecMismatch(
+            "unexpected inner hash op".to_string(),
+        ));
+    }
+
+    // an inner node must never be mistaken for a leaf
+    if !spec.leaf_spec.prefix.is_empty() && inner.prefix.starts_with(&spec.leaf_spec.prefix) {
+        return Err(ProofError::SpecMismatch(
+            "inner prefix starts with leaf prefix".to_string(),
+        ));
+    }
+
+    let max_left_child_bytes = (inner_spec.child_order.len() - 1) * inner_spec.child_size;
+
+    if inner.prefix.len() < inner_spec.min_prefix_length
+        || inner.prefix.len() > inner_spec.max_prefix_length + max_left_child_bytes
+    {
+        return Err(ProofError::SpecMismatch(
+            "inner prefix length out of range".to_string(),
+        ));
+    }
+
+    if inner.suffix.len() % inner_spec.child_size != 0 {
+        return Err(ProofError::SpecMismatch(
+            "inner suffix length not a multiple of child size".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+fn is_iavl_spec(spec: &ProofSpec) -> bool {
+    let iavl = iavl_spec();
+    spec.leaf_spec == iavl.leaf_spec && spec.inner_spec == iavl.inner_spec
+}
+
+// IAVL nodes are prefixed by the zigzag varints of their height, size and version
+// returns the number of bytes following them
+fn iavl_prefix_remainder(prefix: &[u8], layer: usize) -> Result<usize, ProofError> {
+    let invalid = |reason: &str| ProofError::SpecMismatch(format!("invalid IAVL prefix: {reason}"));
+
+    let mut buffer = prefix;
+    let mut values = [0; 3];
+
+    for value in &mut values {
+        let varint =
+            prost::encoding::decode_varint(&mut buffer).map_err(|_| invalid("malformed varint"))?;
+        *value = (varint >> 1) as i64 ^ -((varint & 1) as i64);
+
+        if *value < 0 {
+            return Err(invalid("negative value"));
+        }
+    }
+
+    let [height, size, _] = values;
+
+    // a node is at least as high as its layer in the proof
+    if height < layer as i64 {
+        return Err(invalid("height below layer"));
+    }
+
+    // every node holds at least one leaf, a leaf only itself
+    if size < 1 {
+        return Err(invalid("empty node"));
+    }
+
+    if layer == 0 && (height != 0 || size != 1) {
+        return Err(invalid("leaf is not of height 0 and size 1"));
+    }
+
+    Ok(buffer.len())
+}
+
+// mirrors ics23 `validateIavlOps`, the leaf is layer 0 and the path steps follow
+// node heights, sizes and versions are checked as IAVL writes them
+fn ensure_iavl_ops(proof: &ExistenceProof, leaf: &LeafOp) -> Result<(), ProofError> {
+    if iavl_prefix_remainder(&leaf.prefix, 0)? != 0 {
+        return Err(ProofError::SpecMismatch(
+            "invalid IAVL prefix: trailing leaf bytes".to_string(),
+        ));
+    }
+
+    for (index, step) in proof.path.iter().enumerate() {
+        // followed by the length of the left child, or the left child and the length of the
+        // right one
+        if !matches!(iavl_prefix_remainder(&step.prefix, index + 1)?, 1 | 34) {
+            return Err(ProofError::SpecMismatch(
+                "invalid IAVL prefix: unexpected inner bytes".to_string(),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn check_existence_spec(proof: &ExistenceProof, spec: &ProofSpec) -> Result<(), ProofError> {
+    let leaf = proof
+        .leaf
+        .as_ref()
+        .ok_or_else(|| ProofError::SpecMismatch("missing leaf op".to_string()))?;
+
+    if is_iavl_spec(spec) {
+        ensure_iavl_ops(proof, leaf)?;
+    }
+
+    ensure_leaf(leaf, &spec.leaf_spec)?;
+
+    if spec.min_depth > 0 && proof.path.len() < spec.min_depth {
+        return Err(ProofError::SpecMismatch("proof path too short".to_string()));
+    }
+
+    if spec.max_depth > 0 && proof.path.len() > spec.max_depth {
+        return Err(ProofError::SpecMismatch("proof path too long".to_string()));
+    }
+
+    for step in &proof.path {
+        ensure_inner(step, spec)?;
+    }
+
+    Ok(())
+}
+
+pub fn verify_existence(
+    proof: &ExistenceProof,
+    spec: &ProofSpec,
+    root: &[u8],
+    key: &[u8],
+    value: &[u8],
+) -> Result<(), ProofError> {
+    check_existence_spec(proof, spec)?;
+
+    if proof.key != key {
+        return Err(ProofError::KeyMismatch);
+    }
+
+    if proof.value != value {
+        return Err(ProofError::ValueMismatch);
+    }
+
+    if calculate_existence_root(proof)? != root {
+        return Err(ProofError::RootMismatch);
+    }
+
+    Ok(())
+}
+
+struct Padding {
+    min_prefix: usize,
+    max_prefix: usize,
+    suffix: usize,
+}
+
+fn child_position(spec: &InnerSpec, branch: usize) -> Result<usize, ProofError> {
+    spec.child_order
+        .iter()
+        .position(|&child| child == branch)
+        .ok_or_else(|| {
+            ProofError::InvalidNonExistence(format!("branch {branch} not in child order"))
+        })
+}
+
+fn padding(spec: &InnerSpec, branch: usize) -> Result<Padding, ProofError> {
+    let position = child_position(spec, branch)?;
+    let prefix = position * spec.child_size;
+
+    Ok(Padding {
+        min_prefix: prefix + spec.min_prefix_length,
+        max_prefix: prefix + spec.max_prefix_length,
+        suffix: (spec.child_order.len() - 1 - position) * spec.child_size,
+    })
+}
+
+fn has_padding(op: &InnerOp, padding: &Padding) -> bool {
+    op.prefix.len() >= padding.min_prefix
+        && op.prefix.len() <= padding.max_prefix
+        && op.suffix.len() == padding.suffix
+}
+
+// branch taken by the inner op, derived from where the child sits
+fn branch_from_padding(spec: &InnerSpec, op: &InnerOp) -> Result<usize, ProofError> {
+    for branch in 0..spec.child_order.len() {
+        if has_padding(op, &padding(spec, branch)?) {
+            return Ok(branch);
+        }
+    }
+
+    Err(ProofError::InvalidNonExistence(
+        "inner op matches no branch".to_string(),
+    ))
+}
+
+fn left_branches_are_empty(spec: &InnerSpec, op: &InnerOp) -> Result<bool, ProofError> {
+    let Ok(branch) = branch_from_padding(spec, op) else {
+        return Ok(false);
+    };
+
+    let left_branches = child_position(spec, branch)?;
+
+    if left_branches == 0 {
+        return Ok(false);
+    }
+
+    let Some(actual_prefix) = op.prefix.len().checked_sub(left_branches * spec.child_size) else {
+        return Ok(false);
+    };
+
+    for i in 0..left_branches {
+        let from = actual_prefix + child_position(spec, i)? * spec.child_size;
+        if op.prefix[from..from + spec.child_size] != spec.empty_child[..] {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+fn right_branches_are_empty(spec: &InnerSpec, op: &InnerOp) -> Result<bool, ProofError> {
+    let Ok(branch) = branch_from_padding(spec, op) else {
+        return Ok(false);
+    };
+
+    let right_branches = spec.child_order.len() - 1 - child_position(spec, branch)?;
+
+    if right_branches == 0 || op.suffix.len() != right_branches * spec.child_size {
+        return Ok(false);
+    }
+
+    for i in 0..right_branches {
+        let from = child_position(spec, i)? * spec.child_size;
+        if op.suffix[from..from + spec.child_size] != spec.empty_child[..] {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+fn ensure_left_most(spec: &InnerSpec, path: &[InnerOp]) -> Result<(), ProofError> {
+    let padding = padding(spec, 0)?;
+
+    for step in path {
+        if !has_padding(step, &padding) && !left_branches_are_empty(spec, step)? {
+            return Err(ProofError::InvalidNonExistence(
+                "step not leftmost".to_string(),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+fn ensure_right_most(spec: &InnerSpec, path: &[InnerOp]) -> Result<(), ProofError> {
+    let padding = padding(spec, spec.child_order.len() - 1)?;
+
+    for step in path {
+        if !has_padding(step, &padding) && !right_branches_are_empty(spec, step)? {
+            return Err(ProofError::InvalidNonExistence(
+                "step not rightmost".to_string(),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+// `left` and `right` are adjacent leaves of the same tree
+fn ensure_left_neighbor(
+    spec: &InnerSpec,
+    left: &[InnerOp],
+    right: &[InnerOp],
+) -> Result<(), ProofError> {
+    let mut left = left.to_vec();
+    let mut right = right.to_vec();
+
+    let mut top_left = left.pop();
+    let mut top_right = right.pop();
+
+    // skip the common ancestors
+    while let (Some(l), Some(r)) = (&top_left, &top_right) {
+        if l.prefix != r.prefix || l.suffix != r.suffix {
+            break;
+        }
+        top_left = left.pop();
+        top_right = right.pop();
+    }
+
+    let (Some(top_left), Some(top_right)) = (top_left, top_right) else {
+        return Err(ProofError::InvalidNonExistence(
+            "paths do not diverge".to_string(),
+        ));
+    };
+
+    if branch_from_padding(spec, &top_right)? != branch_from_padding(spec, &top_left)? + 1 {
+        return Err(ProofError::InvalidNonExistence(
+            "not adjacent branches".to_string(),
+        ));
+    }
+
+    ensure_right_most(spec, &left)?;
+    ensure_left_most(spec, &right)
+}
+
+pub fn verify_non_existence(
+    proof: &NonExistenceProof,
+    spec: &ProofSpec,
+    root: &[u8],
+    key: &[u8],
+) -> Result<(), ProofError> {
+    if let Some(left) = &proof.left {
+        verify_existence(left, spec, root, &left.key, &left.value)?;
+        if left.key.as_slice() >= key {
+            return Err(ProofError::InvalidNonExistence(
+                "left key not before key".to_string(),
+            ));
+        }
+    }
+
+    if let Some(right) = &proof.right {
+        verify_existence(right, spec, root, &right.key, &right.value)?;
+        if right.key.as_slice() <= key {
+            return Err(ProofError::InvalidNonExistence(
+                "right key not after key".to_string(),
+            ));
+        }
+    }
+
+    match (&proof.left, &proof.right) {
+        (None, None) => Err(ProofError::InvalidNonExistence("no neighbors".to_string())),
+        (None, Some(right)) => ensure_left_most(&spec.inner_spec, &right.path),
+        (Some(left), None) => ensure_right_most(&spec.inner_spec, &left.path),
+        (Some(left), Some(right)) => {
+            ensure_left_neighbor(&spec.inner_spec, &left.path, &right.path)
+        }
+    }
+}
+
+fn non_existence_root(proof: &NonExistenceProof) -> Result<Vec<u8>, ProofError> {
+    match (&proof.left, &proof.right) {
+        (Some(proof), _) | (None, Some(proof)) => calculate_existence_root(proof),
+        (None, None) => Err(ProofError::InvalidNonExistence("no neighbors".to_string())),
+    }
+}
+
+fn decode_proof(proof: &[u8], specs: &[ProofSpec]) -> Result<MerkleProof, ProofError> {
+    let proof = MerkleProof::decode(proof).map_err(|err| ProofError::Decode(err.to_string()))?;
+
+    if proof.proofs.len() != specs.len() {
+        return Err(ProofError::ProofCountMismatch {
+            expected: specs.len(),
+            got: proof.proofs.len(),
+        });
+    }
+
+    Ok(proof)
+}
+
+fn existence_proof(proof: &CommitmentProof) -> Result<&ExistenceProof, ProofError> {
+    match &proof.proof {
+        Some(commitment_proof::Proof::Exist(proof)) => Ok(proof),
+        _ => Err(ProofError::Decode("expected existence proof".to_string())),
+    }
+}
+
+fn decompress_existence(
+    proof: &CompressedExistenceProof,
+    lookup_inners: &[InnerOp],
+) -> Result<ExistenceProof, ProofError> {
+    let path = proof
+        .path
+        .iter()
+        .map(|index| {
+            usize::try_from(*index)
+                .ok()
+                .and_then(|index| lookup_inners.get(index))
+                .cloned()
+                .ok_or_else(|| ProofError::Decode(format!("inner op {index} not found")))
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(ExistenceProof {
+        key: proof.key.clone(),
+        value: proof.value.clone(),
+        leaf: proof.leaf.clone(),
+        path,
+    })
+}
+
+// existence proofs of a batch, a single existence proof being a batch of one
+fn batch_existence_proofs(proof: &CommitmentProof) -> Result<Vec<ExistenceProof>, ProofError> {
+    let expected = || ProofError::Decode("expected existence proof".to_string());
+
+    match &proof.proof {
+        Some(commitment_proof::Proof::Exist(proof)) => Ok(vec![proof.clone()]),
+        Some(commitment_proof::Proof::Batch(batch)) => batch
+            .entries
+            .iter()
+            .map(|entry| match &entry.proof {
+                Some(batch_entry::Proof::Exist(proof)) => Ok(proof.clone()),
+                _ => Err(expected()),
+            })
+            .collect(),
+        Some(commitment_proof::Proof::Compressed(batch)) => batch
+            .entries
+            .iter()
+            .map(|entry| match &entry.proof {
+                Some(compressed_batch_entry::Proof::Exist(proof)) => {
+                    decompress_existence(proof, &batch.lookup_inners)
+                }
+                _ => Err(expected()),
+            })
+            .collect(),
+        _ => Err(ProofError::Decode("expected batch proof".to_string())),
+    }
+}
+
+// `path` is outermost first, e.g. `[b"ibc", key]`; proofs and specs are innermost first
+fn verify_chained_membership(
+    proofs: &[CommitmentProof],
+    specs: &[ProofSpec],
+    root: &[u8],
+    path: &[Vec<u8>],
+    value: Vec<u8>,
+) -> Result<(), ProofError> {
+    let mut value = value;
+
+    for (index, (proof, spec)) in proofs.iter().zip(specs).enumerate() {
+        let proof = existence_proof(proof)?;
+        let subroot = calculate_existence_root(proof)?;
+        let key = &path[path.len() - 1 - index];
+
+        verify_existence(proof, spec, &subroot, key, &value)?;
+
+        value = subroot;
+    }
+
+    if value != root {
+        return Err(ProofError::RootMismatch);
+    }
+
+    Ok(())
+}
+
+pub fn verify_membership(
+    specs: &[ProofSpec],
+    root: &[u8],
+    path: &[Vec<u8>],
+    proof: &[u8],
+    value: Vec<u8>,
+) -> Result<(), ProofError> {
+    let proof = decode_proof(proof, specs)?;
+
+    if path.len() != specs.len() {
+        return Err(ProofError::ProofCountMismatch {
+            expected: specs.len(),
+            got: path.len(),
+        });
+    }
+
+    verify_chained_membership(&proof.proofs, specs, root, path, value)
+}
+
+pub fn verify_non_membership(
+    specs: &[ProofSpec],
+    root: &[u8],
+    path: &[Vec<u8>],
+    proof: &[u8],
+) -> Result<(), ProofError> {
+    let proof = decode_proof(proof, specs)?;
+
+    if path.len() != specs.len() || specs.is_empty() {
+        return Err(ProofError::ProofCountMismatch {
+            expected: specs.len(),
+            got: path.len(),
+        });
+    }
+
+    // innermost store proves the absence, the outer stores prove its root
+    let Some(commitment_proof::Proof::Nonexist(non_existence)) = &proof.proofs[0].proof else {
+        return Err(ProofError::Decode(
+            "expected non-existence proof".to_string(),
+        ));
+    };
+
+    let subroot = non_existence_root(non_existence)?;
+
+    verify_non_existence(non_existence, &specs[0], &subroot, &path[path.len() - 1])?;
+
+    verify_chained_membership(
+        &proof.proofs[1..],
+        &specs[1..],
+        root,
+        &path[..path.len() - 1],
+        subroot,
+    )
+}
+
+// `items` are paths and values within the same store, e.g. `[b"ibc", key]`
+// the innermost proof proves every key as a batch, the outer ones prove the store root only once
+pub fn verify_batch_membership(
+    specs: &[ProofSpec],
+    root: &[u8],
+    items: &[(Vec<Vec<u8>>, Vec<u8>)],
+    proof: &[u8],
+) -> Result<(), ProofError> {
+    let proof = decode_proof(proof, specs)?;
+
+    let Some((first, _)) = items.first() else {
+        return Ok(());
+    };
+
+    if first.len() != specs.len() || specs.is_empty() {
+        return Err(ProofError::ProofCountMismatch {
+            expected: specs.len(),
+            got: first.len(),
+        });
+    }
+
+    let store = &first[..first.len() - 1];
+    let entries = batch_existence_proofs(&proof.proofs[0])?;
+
+    let mut subroot = None;
+
+    for (path, value) in items {
+        if path.len() != first.len() || &path[..path.len() - 1] != store {
+            return Err(ProofError::StoreMismatch);
+        }
+
+        let key = &path[path.len() - 1];
+
+        let entry = entries
+            .iter()
+            .find(|entry| entry.key == *key)
+            .ok_or(ProofError::KeyMismatch)?;
+
+        check_existence_spec(entry, &specs[0])?;
+
+        if entry.value != *value {
+            return Err(ProofError::ValueMismatch);
+        }
+
+        // every entry must lead to the same store root
+        let entry_root = calculate_existence_root(entry)?;
+
+        match &subroot {
+            Some(subroot) if *subroot != entry_root => return Err(ProofError::RootMismatch),
+            Some(_) => {}
+            None => subroot = Some(entry_root),
+        }
+    }
+
+    verify_chained_membership(
+        &proof.proofs[1..],
+        &specs[1..],
+        root,
+        store,
+        subroot.unwrap_or_default(),
+    )
+}
@@ -1,30 +1,227 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Response, StdError};
+use cosmwasm_std::{from_json, to_json_vec, Addr, Response, StdError, StdResult};
 use sylvia::interface;
 use sylvia::types::{ExecCtx, QueryCtx};
 
+// height of the counterparty chain, ordered by revision first
+#[cw_serde]
+#[derive(Copy, Default, Eq, PartialOrd, Ord)]
+pub struct Height {
+    pub revision_number: u64,
+    pub revision_height: u64,
+}
+
+impl Height {
+    pub const fn new(revision_number: u64, revision_height: u64) -> Self {
+        Self {
+            revision_number,
+            revision_height,
+        }
+    }
+}
+
+impl std::fmt::Display for Height {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.revision_number, self.revision_height)
+    }
+}
+
+// state common to every light client, for tooling and tao to introspect clients uniformly
+#[cw_serde]
+pub struct ClientState {
+    pub chain_id: String,
+    pub latest_height: Height,
+    // height of the misbehaviour that froze the client
+    pub frozen_height: Option<Height>,
+    // client specific encoding of the whole state
+    pub data: Vec<u8>,
+}
+
+#[cw_serde]
+pub struct ConsensusState {
+    // in seconds
+    pub timestamp: u64,
+    // commitment root of the counterparty, empty for clients not keeping one
+    pub root: Vec<u8>,
+    // client specific encoding of the whole state
+    pub data: Vec<u8>,
+}
+
+// applies `headers` in order through `update`, each on top of the previous ones
+pub fn apply_headers<E>(
+    mut ctx: ExecCtx,
+    headers: Vec<Vec<u8>>,
+    update: impl Fn(ExecCtx, Vec<u8>) -> Result<Response, E>,
+) -> Result<Response, E> {
+    let mut response = Response::new();
+
+    for header in headers {
+        let applied = update(
+            ExecCtx::from((ctx.deps.branch(), ctx.env.clone(), ctx.info.clone())),
+            header,
+        )?;
+        response = response
+            .add_submessages(applied.messages)
+            .add_attributes(applied.attributes)
+            .add_events(applied.events);
+    }
+
+    Ok(response)
+}
+
+// `verify_batch_membership` proof of clients without batch proofs, one proof per item
+pub fn batch_proof(proofs: &[Vec<u8>]) -> StdResult<Vec<u8>> {
+    to_json_vec(proofs)
+}
+
+// checks each item through `check_membership` against its own proof of a `batch_proof`
+pub fn check_each_membership<E: From<StdError>>(
+    items: Vec<(Vec<u8>, Vec<u8>)>,
+    proof: &[u8],
+    check_membership: impl Fn(Vec<u8>, Vec<u8>, Vec<u8>) -> Result<bool, E>,
+) -> Result<bool, E> {
+    let proofs: Vec<Vec<u8>> = from_json(proof)?;
+
+    if proofs.len() != items.len() {
+        return Err(StdError::generic_err(format!(
+            "expected {} proofs, got {}",
+            items.len(),
+            proofs.len()
+        ))
+        .into());
+    }
+
+    for ((key, value), proof) in items.into_iter().zip(proofs) {
+        if !check_membership(key, value, proof)? {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+// maximum number of consensus states removed by a single `prune`
+pub const PRUNE_LIMIT: usize = 16;
+
+// whether a consensus state at `timestamp` is past the trusting period at `now`, in seconds
+pub fn is_expired(timestamp: u64, trusting_period: u64, now: u64) -> bool {
+    timestamp.saturating_add(trusting_period) <= now
+}
+
 #[cw_serde]
 pub enum Status {
     Active,
     Inactive,
+    // misbehaviour detected, the client must not be trusted anymore
+    Frozen,
+    // trusting period elapsed since the latest consensus state
+    Expired,
+    // the client cannot determine its own status
+    Unknown,
+}
+
+// who may submit headers to `update`
+#[cw_serde]
+pub enum UpdatePolicy {
+    // headers are cryptographically verified by the client, anyone can relay them
+    Permissionless,
+    // headers are trusted as submitted, only these addresses can update the client
+    AllowList(Vec<Addr>),
+}
+
+impl UpdatePolicy {
+    pub fn is_allowed(&self, sender: &Addr) -> bool {
+        match self {
+            Self::Permissionless => true,
+            Self::AllowList(submitters) => submitters.contains(sender),
+        }
+    }
 }
 
 #[interface]
 pub trait LightClient {
     type Error: From<StdError>;
 
+    // permissionless only if the client verifies the header itself,
+    // clients trusting their submitters must reject anyone outside `update_policy`
     #[sv::msg(exec)]
     fn update(&self, ctx: ExecCtx, header: Vec<u8>) -> Result<Response, Self::Error>;
 
+    // same as `update` for each header, e.g. to bridge a validator set change
+    #[sv::msg(exec)]
+    fn update_batch(&self, ctx: ExecCtx, headers: Vec<Vec<u8>>) -> Result<Response, Self::Error>;
+
+    #[sv::msg(query)]
+    fn update_policy(&self, ctx: QueryCtx) -> Result<UpdatePolicy, Self::Error>;
+
+    // whether the encoded misbehaviour evidence is valid against the client
+    #[sv::msg(query)]
+    fn check_for_misbehaviour(
+        &self,
+        ctx: QueryCtx,
+        misbehaviour: Vec<u8>,
+    ) -> Result<bool, Self::Error>;
+
+    // freezes the client on valid misbehaviour evidence
+    #[sv::msg(exec)]
+    fn update_on_misbehaviour(
+        &self,
+        ctx: ExecCtx,
+        misbehaviour: Vec<u8>,
+    ) -> Result<Response, Self::Error>;
+
+    // migrates the client to the upgraded counterparty chain, keeping its address
+    // the upgraded states must be committed by the counterparty at the latest height
+    // and are given in the encoding the counterparty commits, protobuf `Any` for ibc-go
+    #[sv::msg(exec)]
+    fn verify_upgrade_and_update_state(
+        &self,
+        ctx: ExecCtx,
+        upgraded_client: Vec<u8>,
+        upgraded_consensus: Vec<u8>,
+        proofs: Vec<u8>,
+    ) -> Result<Response, Self::Error>;
+
+    #[sv::msg(query)]
+    fn client_state(&self, ctx: QueryCtx) -> Result<ClientState, Self::Error>;
+
+    #[sv::msg(query)]
+    fn consensus_state(&self, ctx: QueryCtx, height: Height)
+        -> Result<ConsensusState, Self::Error>;
+
     #[sv::msg(query)]
     fn status(&self, ctx: QueryCtx) -> Result<Status, Self::Error>;
 
     #[sv::msg(query)]
-    fn timestamp(&self, ctx: QueryCtx, height: u64) -> Result<u64, Self::Error>;
+    fn latest_height(&self, ctx: QueryCtx) -> Result<Height, Self::Error>;
+
+    #[sv::msg(query)]
+    fn timestamp(&self, ctx: QueryCtx, height: Height) -> Result<u64, Self::Error>;
 
+    // same as `timestamp`, `None` when the client does not track a consensus state at `height`
+    // so that a missing one is told apart from any other error
+    #[sv::msg(query)]
+    fn consensus_timestamp(
+        &self,
+        ctx: QueryCtx,
+        height: Height,
+    ) -> Result<Option<u64>, Self::Error>;
+
+    // in seconds, `u64::MAX` for clients that never expire
+    #[sv::msg(query)]
+    fn trusting_period(&self, ctx: QueryCtx) -> Result<u64, Self::Error>;
+
+    // client specific encoding of the parameters a substitute must share with the client it
+    // replaces, as in ibc-go `IsMatchingClientState`: the client state without its heights,
+    // frozen state nor trusting period
+    #[sv::msg(query)]
+    fn client_parameters(&self, ctx: QueryCtx) -> Result<Vec<u8>, Self::Error>;
+
+    // removes consensus states past the trusting period, at most `PRUNE_LIMIT` per call
     #[sv::msg(exec)]
     fn prune(&self, ctx: ExecCtx) -> Result<Response, Self::Error>;
 
+    // a failed verification should be an error, `false` is rejected by tao all the same
     #[sv::msg(query)]
     fn check_membership(
         &self,
@@ -32,7 +229,19 @@ pub trait LightClient {
         key: Vec<u8>,
         value: Vec<u8>,
         commitment_prefix: Vec<u8>,
-        height: u64,
+        height: Height,
+        proof: Vec<u8>,
+    ) -> Result<bool, Self::Error>;
+
+    // same as `check_membership` for each `(key, value)`, all proven at `height` by `proof`
+    // clients supporting batch proofs verify the shared part of the proof only once
+    #[sv::msg(query)]
+    fn verify_batch_membership(
+        &self,
+        ctx: QueryCtx,
+        items: Vec<(Vec<u8>, Vec<u8>)>,
+        commitment_prefix: Vec<u8>,
+        height: Height,
         proof: Vec<u8>,
     ) -> Result<bool, Self::Error>;
 
@@ -42,7 +251,17 @@ pub trait LightClient {
         ctx: QueryCtx,
         key: Vec<u8>,
         commitment_prefix: Vec<u8>,
-        height: u64,
+        height: Height,
         proof: Vec<u8>,
     ) -> Result<bool, Self::Error>;
+
+    // sent by tao once it accepted `count` proofs at `height`, a proof per proven key
+    // clients whose proofs are single use advance past them, e.g. the solo machine sequence
+    #[sv::msg(exec)]
+    fn consume_proofs(
+        &self,
+        ctx: ExecCtx,
+        height: Height,
+        count: u64,
+    ) -> Result<Response, Self::Error>;
 }
@@ -0,0 +1,30 @@
+use cosmwasm_std::StdError;
+use eureka_lightclient_interface::Height;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("unauthorized")]
+    Unauthorized,
+
+    #[error("consensus state not found at height: {height}")]
+    ConsensusStateNotFound { height: Height },
+
+    #[error("commitment not found: {key}")]
+    CommitmentNotFound { key: String },
+
+    #[error("commitment value mismatch: {key}")]
+    CommitmentValueMismatch { key: String },
+
+    #[error("unexpected commitment: {key}")]
+    UnexpectedCommitment { key: String },
+
+    #[error("no misbehaviour")]
+    NoMisbehaviour,
+
+    #[error("upgrade not supported")]
+    UpgradeNotSupported,
+}
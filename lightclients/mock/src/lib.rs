@@ -0,0 +1,333 @@
+// mock light client for tests
+// headers carry the counterparty commitments at a height and are trusted from
+// the submitters set at instantiation, proofs are ignored
+// and membership is checked against the stored commitments
+
+pub mod error;
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{from_json, to_json_vec, Addr, Storage};
+use cw_storey::containers::{Item, Map};
+use cw_storey::CwStorage;
+use eureka_lightclient_interface::{
+    self as interface, apply_headers, Height, LightClient, Status, UpdatePolicy,
+};
+use sylvia::contract;
+use sylvia::cw_std::Response;
+use sylvia::types::{ExecCtx, InstantiateCtx, QueryCtx};
+
+pub use crate::error::ContractError;
+
+#[cw_serde]
+pub struct Commitment {
+    pub prefix: Vec<u8>,
+    pub key: Vec<u8>,
+    pub value: Vec<u8>,
+}
+
+#[cw_serde]
+pub struct Header {
+    pub height: Height,
+    pub timestamp: u64,
+    // every commitment of the counterparty at `height`
+    pub commitments: Vec<Commitment>,
+}
+
+#[cw_serde]
+pub struct ConsensusState {
+    pub timestamp: u64,
+    pub commitments: Vec<Commitment>,
+}
+
+pub struct Contract {
+    // revision number -> revision height -> consensus state
+    pub consensus_states: Map<u64, Map<u64, Item<ConsensusState>>>,
+    pub latest_height: Item<Height>,
+    // headers are trusted as submitted
+    pub submitters: Item<Vec<Addr>>,
+}
+
+#[cfg_attr(not(feature = "library"), sylvia::entry_points)]
+#[contract]
+#[sv::error(ContractError)]
+#[sv::messages(eureka_lightclient_interface)]
+impl Contract {
+    pub const fn new() -> Self {
+        Self {
+            consensus_states: Map::new(b'S'),
+            latest_height: Item::new(b'L'),
+            submitters: Item::new(b'U'),
+        }
+    }
+
+    #[sv::msg(instantiate)]
+    fn instantiate(
+        &self,
+        ctx: InstantiateCtx,
+        submitters: Vec<Addr>,
+    ) -> Result<Response, ContractError> {
+        self.submitters
+            .access(&mut CwStorage(ctx.deps.storage))
+            .set(&submitters)?;
+        Ok(Response::default())
+    }
+}
+
+impl Contract {
+    fn find_consensus_state(
+        &self,
+        storage: &dyn Storage,
+        height: Height,
+    ) -> Result<Option<ConsensusState>, ContractError> {
+        Ok(self
+            .consensus_states
+            .access(&CwStorage(storage))
+            .entry(&height.revision_number)
+            .entry(&height.revision_height)
+            .get()?)
+    }
+
+    fn load_consensus_state(
+        &self,
+        storage: &dyn Storage,
+        height: Height,
+    ) -> Result<ConsensusState, ContractError> {
+        self.find_consensus_state(storage, height)?
+            .ok_or(ContractError::ConsensusStateNotFound { height })
+    }
+
+    fn load_update_policy(&self, storage: &dyn Storage) -> Result<UpdatePolicy, ContractError> {
+        Ok(UpdatePolicy::AllowList(
+            self.submitters
+                .access(&CwStorage(storage))
+                .get()?
+                .unwrap_or_default(),
+        ))
+    }
+
+    // value of the commitment at `commitment_prefix` and `key`, if any
+    fn load_commitment(
+        &self,
+        storage: &dyn Storage,
+        key: &[u8],
+        commitment_prefix: &[u8],
+        height: Height,
+    ) -> Result<Option<Vec<u8>>, ContractError> {
+        Ok(self
+            .load_consensus_state(storage, height)?
+            .commitments
+            .into_iter()
+            .find(|commitment| commitment.prefix == commitment_prefix && commitment.key == key)
+            .map(|commitment| commitment.value))
+    }
+}
+
+impl LightClient for Contract {
+    type Error = ContractError;
+
+    fn update(&self, ctx: ExecCtx, header: Vec<u8>) -> Result<Response, Self::Error> {
+        if !self
+            .load_update_policy(ctx.deps.storage)?
+            .is_allowed(&ctx.info.sender)
+        {
+            return Err(ContractError::Unauthorized);
+        }
+
+        let Header {
+            height,
+            timestamp,
+            commitments,
+        } = from_json(header)?;
+
+        let mut storage = CwStorage(ctx.deps.storage);
+
+        self.consensus_states
+            .access(&mut storage)
+            .entry_mut(&height.revision_number)
+            .entry_mut(&height.revision_height)
+            .set(&ConsensusState {
+                timestamp,
+                commitments,
+            })?;
+
+        let latest_height = self
+            .latest_height
+            .access(&storage)
+            .get()?
+            .unwrap_or_default();
+
+        if height > latest_height {
+            self.latest_height.access(&mut storage).set(&height)?;
+        }
+
+        Ok(Response::default())
+    }
+
+    fn update_batch(&self, ctx: ExecCtx, headers: Vec<Vec<u8>>) -> Result<Response, Self::Error> {
+        apply_headers(ctx, headers, |ctx, header| self.update(ctx, header))
+    }
+
+    fn update_policy(&self, ctx: QueryCtx) -> Result<UpdatePolicy, Self::Error> {
+        self.load_update_policy(ctx.deps.storage)
+    }
+
+    fn check_for_misbehaviour(
+        &self,
+        _ctx: QueryCtx,
+        _misbehaviour: Vec<u8>,
+    ) -> Result<bool, Self::Error> {
+        Ok(false)
+    }
+
+    fn update_on_misbehaviour(
+        &self,
+        _ctx: ExecCtx,
+        _misbehaviour: Vec<u8>,
+    ) -> Result<Response, Self::Error> {
+        Err(ContractError::NoMisbehaviour)
+    }
+
+    fn verify_upgrade_and_update_state(
+        &self,
+        _ctx: ExecCtx,
+        _upgraded_client: Vec<u8>,
+        _upgraded_consensus: Vec<u8>,
+        _proofs: Vec<u8>,
+    ) -> Result<Response, Self::Error> {
+        Err(ContractError::UpgradeNotSupported)
+    }
+
+    // the mock follows no particular chain and cannot be frozen
+    fn client_state(&self, ctx: QueryCtx) -> Result<interface::ClientState, Self::Error> {
+        Ok(interface::ClientState {
+            chain_id: String::new(),
+            latest_height: self.latest_height(ctx)?,
+            frozen_height: None,
+            data: vec![],
+        })
+    }
+
+    // commitments are kept as submitted, without a root
+    fn consensus_state(
+        &self,
+        ctx: QueryCtx,
+        height: Height,
+    ) -> Result<interface::ConsensusState, Self::Error> {
+        let consensus_state = self.load_consensus_state(ctx.deps.storage, height)?;
+
+        Ok(interface::ConsensusState {
+            timestamp: consensus_state.timestamp,
+            root: vec![],
+            data: to_json_vec(&consensus_state)?,
+        })
+    }
+
+    fn status(&self, _ctx: QueryCtx) -> Result<Status, Self::Error> {
+        Ok(Status::Active)
+    }
+
+    fn latest_height(&self, ctx: QueryCtx) -> Result<Height, Self::Error> {
+        Ok(self
+            .latest_height
+            .access(&CwStorage(ctx.deps.storage))
+            .get()?
+            .unwrap_or_default())
+    }
+
+    fn timestamp(&self, ctx: QueryCtx, height: Height) -> Result<u64, Self::Error> {
+        Ok(self
+            .load_consensus_state(ctx.deps.storage, height)?
+            .timestamp)
+    }
+
+    fn consensus_timestamp(
+        &self,
+        ctx: QueryCtx,
+        height: Height,
+    ) -> Result<Option<u64>, Self::Error> {
+        Ok(self
+            .find_consensus_state(ctx.deps.storage, height)?
+            .map(|consensus_state| consensus_state.timestamp))
+    }
+
+    fn trusting_period(&self, _ctx: QueryCtx) -> Result<u64, Self::Error> {
+        Ok(u64::MAX)
+    }
+
+    fn client_parameters(&self, _ctx: QueryCtx) -> Result<Vec<u8>, Self::Error> {
+        Ok(vec![])
+    }
+
+    fn prune(&self, _ctx: ExecCtx) -> Result<Response, Self::Error> {
+        Ok(Response::default())
+    }
+
+    fn check_membership(
+        &self,
+        ctx: QueryCtx,
+        key: Vec<u8>,
+        value: Vec<u8>,
+        commitment_prefix: Vec<u8>,
+        height: Height,
+        _proof: Vec<u8>,
+    ) -> Result<bool, Self::Error> {
+        match self.load_commitment(ctx.deps.storage, &key, &commitment_prefix, height)? {
+            Some(stored) if stored == value => Ok(true),
+            Some(_) => Err(ContractError::CommitmentValueMismatch {
+                key: String::from_utf8_lossy(&key).into_owned(),
+            }),
+            None => Err(ContractError::CommitmentNotFound {
+                key: String::from_utf8_lossy(&key).into_owned(),
+            }),
+        }
+    }
+
+    // proofs are not checked by the mock
+    fn verify_batch_membership(
+        &self,
+        ctx: QueryCtx,
+        items: Vec<(Vec<u8>, Vec<u8>)>,
+        commitment_prefix: Vec<u8>,
+        height: Height,
+        _proof: Vec<u8>,
+    ) -> Result<bool, Self::Error> {
+        for (key, value) in items {
+            self.check_membership(
+                QueryCtx::from((ctx.deps, ctx.env.clone())),
+                key,
+                value,
+                commitment_prefix.clone(),
+                height,
+                vec![],
+            )?;
+        }
+
+        Ok(true)
+    }
+
+    fn check_non_membership(
+        &self,
+        ctx: QueryCtx,
+        key: Vec<u8>,
+        commitment_prefix: Vec<u8>,
+        height: Height,
+        _proof: Vec<u8>,
+    ) -> Result<bool, Self::Error> {
+        match self.load_commitment(ctx.deps.storage, &key, &commitment_prefix, height)? {
+            Some(_) => Err(ContractError::UnexpectedCommitment {
+                key: String::from_utf8_lossy(&key).into_owned(),
+            }),
+            None => Ok(true),
+        }
+    }
+
+    // proofs are not checked, nothing to consume
+    fn consume_proofs(
+        &self,
+        _ctx: ExecCtx,
+        _height: Height,
+        _count: u64,
+    ) -> Result<Response, Self::Error> {
+        Ok(Response::default())
+    }
+}
@@ -0,0 +1,32 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("native query response decoding failed: {0}")]
+    Decode(String),
+
+    #[error("unsupported native client type: {type_url}")]
+    UnsupportedClientType { type_url: String },
+
+    #[error("native membership verification failed: {key}")]
+    MembershipFailed { key: String },
+
+    #[error("native client cannot verify non-membership")]
+    NonMembershipNotSupported,
+
+    #[error("empty commitment value")]
+    EmptyValue,
+
+    #[error("native client is updated by the host chain")]
+    UpdateNotSupported,
+
+    #[error("native client misbehaviour is handled by the host chain")]
+    MisbehaviourNotSupported,
+
+    #[error("native client is upgraded by the host chain")]
+    UpgradeNotSupported,
+}
@@ -0,0 +1,383 @@
+// light client proxying to a light client of the host chain ibc-go module
+// status, heights, timestamps and membership are forwarded as gRPC queries, the native
+// client is updated and frozen by the host chain itself so headers and misbehaviour are rejected
+// only native tendermint clients are supported
+
+pub mod error;
+pub mod proto;
+
+use cosmwasm_std::{Binary, Deps, StdError};
+use cw_storey::containers::Item;
+use cw_storey::CwStorage;
+use eureka_ics23_verifier::apply_prefix;
+use eureka_lightclient_interface::{
+    self as interface, apply_headers, check_each_membership, Height, LightClient, Status,
+    UpdatePolicy,
+};
+use prost::Message;
+use sylvia::contract;
+use sylvia::cw_std::Response;
+use sylvia::types::{ExecCtx, InstantiateCtx, QueryCtx};
+
+pub use crate::error::ContractError;
+use crate::proto::{
+    Any, MerklePath, QueryClientStateRequest, QueryClientStateResponse, QueryClientStatusRequest,
+    QueryClientStatusResponse, QueryConsensusStateRequest, QueryConsensusStateResponse,
+    QueryVerifyMembershipRequest, QueryVerifyMembershipResponse, TendermintClientState,
+    TendermintConsensusState, CLIENT_STATE_PATH, CLIENT_STATUS_PATH, CONSENSUS_STATE_PATH,
+    TENDERMINT_CLIENT_STATE_TYPE_URL, TENDERMINT_CONSENSUS_STATE_TYPE_URL, VERIFY_MEMBERSHIP_PATH,
+};
+
+pub struct Contract {
+    // id of the native client, e.g. `07-tendermint-0`
+    pub client_id: Item<String>,
+}
+
+#[cfg_attr(not(feature = "library"), sylvia::entry_points)]
+#[contract]
+#[sv::error(ContractError)]
+#[sv::messages(eureka_lightclient_interface)]
+impl Contract {
+    pub const fn new() -> Self {
+        Self {
+            client_id: Item::new(b'C'),
+        }
+    }
+
+    #[sv::msg(instantiate)]
+    fn instantiate(
+        &self,
+        ctx: InstantiateCtx,
+        client_id: String,
+    ) -> Result<Response, ContractError> {
+        // the native client must exist and be supported
+        query_client_state(ctx.deps.as_ref(), &client_id)?;
+
+        self.client_id
+            .access(&mut CwStorage(ctx.deps.storage))
+            .set(&client_id)?;
+
+        Ok(Response::default())
+    }
+
+    #[sv::msg(query)]
+    fn client_id(&self, ctx: QueryCtx) -> Result<String, ContractError> {
+        self.load_client_id(ctx.deps)
+    }
+}
+
+impl Contract {
+    fn load_client_id(&self, deps: Deps) -> Result<String, ContractError> {
+        self.client_id
+            .access(&CwStorage(deps.storage))
+            .get()?
+            .ok_or_else(|| StdError::not_found("client id").into())
+    }
+
+    // whether the native client verifies `value` at `key`
+    fn verify_membership(
+        &self,
+        deps: Deps,
+        key: &[u8],
+        value: Vec<u8>,
+        commitment_prefix: &[u8],
+        height: Height,
+        proof: Vec<u8>,
+    ) -> Result<bool, ContractError> {
+        let response: QueryVerifyMembershipResponse = query(
+            deps,
+            VERIFY_MEMBERSHIP_PATH,
+            QueryVerifyMembershipRequest {
+                client_id: self.load_client_id(deps)?,
+                proof,
+                proof_height: Some(proto::Height {
+                    revision_number: height.revision_number,
+                    revision_height: height.revision_height,
+                }),
+                value,
+                time_delay: 0,
+                block_delay: 0,
+                merkle_path: Some(MerklePath {
+                    key_path: apply_prefix(commitment_prefix, key),
+                }),
+            },
+        )?;
+
+        Ok(response.success)
+    }
+}
+
+fn query<T: Message + Default>(
+    deps: Deps,
+    path: &str,
+    request: impl Message,
+) -> Result<T, ContractError> {
+    let response = deps
+        .querier
+        .query_grpc(path.to_string(), Binary::from(request.encode_to_vec()))?;
+
+    T::decode(response.as_slice()).map_err(|error| ContractError::Decode(error.to_string()))
+}
+
+// decoded state, along with its encoding
+fn unpack<T: Message + Default>(
+    any: Option<Any>,
+    type_url: &str,
+) -> Result<(T, Vec<u8>), ContractError> {
+    let any = any.ok_or_else(|| ContractError::Decode("missing state".to_string()))?;
+
+    if any.type_url != type_url {
+        return Err(ContractError::UnsupportedClientType {
+            type_url: any.type_url,
+        });
+    }
+
+    let state = T::decode(any.value.as_slice())
+        .map_err(|error| ContractError::Decode(error.to_string()))?;
+
+    Ok((state, any.value))
+}
+
+fn native_height(height: Option<proto::Height>) -> Height {
+    let height = height.unwrap_or_default();
+    Height::new(height.revision_number, height.revision_height)
+}
+
+fn query_client_state(
+    deps: Deps,
+    client_id: &str,
+) -> Result<(TendermintClientState, Vec<u8>), ContractError> {
+    let response: QueryClientStateResponse = query(
+        deps,
+        CLIENT_STATE_PATH,
+        QueryClientStateRequest {
+            client_id: client_id.to_string(),
+        },
+    )?;
+
+    unpack(response.client_state, TENDERMINT_CLIENT_STATE_TYPE_URL)
+}
+
+fn query_consensus_state(
+    deps: Deps,
+    client_id: &str,
+    height: Height,
+) -> Result<(TendermintConsensusState, Vec<u8>), ContractError> {
+    let response: QueryConsensusStateResponse = query(
+        deps,
+        CONSENSUS_STATE_PATH,
+        QueryConsensusStateRequest {
+            client_id: client_id.to_string(),
+            revision_number: height.revision_number,
+            revision_height: height.revision_height,
+            latest_height: false,
+        },
+    )?;
+
+    unpack(
+        response.consensus_state,
+        TENDERMINT_CONSENSUS_STATE_TYPE_URL,
+    )
+}
+
+impl LightClient for Contract {
+    type Error = ContractError;
+
+    fn update(&self, _ctx: ExecCtx, _header: Vec<u8>) -> Result<Response, Self::Error> {
+        Err(ContractError::UpdateNotSupported)
+    }
+
+    fn update_batch(&self, ctx: ExecCtx, headers: Vec<Vec<u8>>) -> Result<Response, Self::Error> {
+        apply_headers(ctx, headers, |ctx, header| self.update(ctx, header))
+    }
+
+    // headers are submitted to the host chain, not to the proxy
+    fn update_policy(&self, _ctx: QueryCtx) -> Result<UpdatePolicy, Self::Error> {
+        Ok(UpdatePolicy::AllowList(vec![]))
+    }
+
+    fn check_for_misbehaviour(
+        &self,
+        _ctx: QueryCtx,
+        _misbehaviour: Vec<u8>,
+    ) -> Result<bool, Self::Error> {
+        Err(ContractError::MisbehaviourNotSupported)
+    }
+
+    fn update_on_misbehaviour(
+        &self,
+        _ctx: ExecCtx,
+        _misbehaviour: Vec<u8>,
+    ) -> Result<Response, Self::Error> {
+        Err(ContractError::MisbehaviourNotSupported)
+    }
+
+    fn verify_upgrade_and_update_state(
+        &self,
+        _ctx: ExecCtx,
+        _upgraded_client: Vec<u8>,
+        _upgraded_consensus: Vec<u8>,
+        _proofs: Vec<u8>,
+    ) -> Result<Response, Self::Error> {
+        Err(ContractError::UpgradeNotSupported)
+    }
+
+    // the states are protobuf encoded as in ibc-go
+    fn client_state(&self, ctx: QueryCtx) -> Result<interface::ClientState, Self::Error> {
+        let (client_state, data) = query_client_state(ctx.deps, &self.load_client_id(ctx.deps)?)?;
+
+        Ok(interface::ClientState {
+            chain_id: client_state.chain_id,
+            latest_height: native_height(client_state.latest_height),
+            frozen_height: Some(native_height(client_state.frozen_height))
+                .filter(|height| *height != Height::default()),
+            data,
+        })
+    }
+
+    fn consensus_state(
+        &self,
+        ctx: QueryCtx,
+        height: Height,
+    ) -> Result<interface::ConsensusState, Self::Error> {
+        let (consensus_state, data) =
+            query_consensus_state(ctx.deps, &self.load_client_id(ctx.deps)?, height)?;
+
+        Ok(interface::ConsensusState {
+            timestamp: consensus_state.timestamp.unwrap_or_default().seconds as u64,
+            root: consensus_state.root.unwrap_or_default().hash,
+            data,
+        })
+    }
+
+    fn status(&self, ctx: QueryCtx) -> Result<Status, Self::Error> {
+        let response: QueryClientStatusResponse = query(
+            ctx.deps,
+            CLIENT_STATUS_PATH,
+            QueryClientStatusRequest {
+                client_id: self.load_client_id(ctx.deps)?,
+            },
+        )?;
+
+        Ok(match response.status.as_str() {
+            "Active" => Status::Active,
+            "Frozen" => Status::Frozen,
+            "Expired" => Status::Expired,
+            // the client type is not allowed on the host chain
+            "Unauthorized" => Status::Inactive,
+            _ => Status::Unknown,
+        })
+    }
+
+    fn latest_height(&self, ctx: QueryCtx) -> Result<Height, Self::Error> {
+        let (client_state, _) = query_client_state(ctx.deps, &self.load_client_id(ctx.deps)?)?;
+
+        Ok(native_height(client_state.latest_height))
+    }
+
+    fn timestamp(&self, ctx: QueryCtx, height: Height) -> Result<u64, Self::Error> {
+        let (consensus_state, _) =
+            query_consensus_state(ctx.deps, &self.load_client_id(ctx.deps)?, height)?;
+
+        Ok(consensus_state.timestamp.unwrap_or_default().seconds as u64)
+    }
+
+    // the host chain does not tell a missing consensus state apart from other query failures,
+    // which are all passed through
+    fn consensus_timestamp(
+        &self,
+        ctx: QueryCtx,
+        height: Height,
+    ) -> Result<Option<u64>, Self::Error> {
+        self.timestamp(ctx, height).map(Some)
+    }
+
+    fn trusting_period(&self, ctx: QueryCtx) -> Result<u64, Self::Error> {
+        let (client_state, _) = query_client_state(ctx.deps, &self.load_client_id(ctx.deps)?)?;
+
+        Ok(client_state.trusting_period.unwrap_or_default().seconds as u64)
+    }
+
+    // protobuf encoded, the fields not decoded by the proxy are left out
+    fn client_parameters(&self, ctx: QueryCtx) -> Result<Vec<u8>, Self::Error> {
+        let (client_state, _) = query_client_state(ctx.deps, &self.load_client_id(ctx.deps)?)?;
+
+        Ok(TendermintClientState {
+            trusting_period: None,
+            frozen_height: None,
+            latest_height: None,
+            ..client_state
+        }
+        .encode_to_vec())
+    }
+
+    // consensus states are pruned by the native client
+    fn prune(&self, _ctx: ExecCtx) -> Result<Response, Self::Error> {
+        Ok(Response::default())
+    }
+
+    fn check_membership(
+        &self,
+        ctx: QueryCtx,
+        key: Vec<u8>,
+        value: Vec<u8>,
+        commitment_prefix: Vec<u8>,
+        height: Height,
+        proof: Vec<u8>,
+    ) -> Result<bool, Self::Error> {
+        // rejected by the native client, absence cannot be verified through it
+        if value.is_empty() {
+            return Err(ContractError::EmptyValue);
+        }
+
+        if !self.verify_membership(ctx.deps, &key, value, &commitment_prefix, height, proof)? {
+            return Err(ContractError::MembershipFailed {
+                key: String::from_utf8_lossy(&key).into_owned(),
+            });
+        }
+
+        Ok(true)
+    }
+
+    fn verify_batch_membership(
+        &self,
+        ctx: QueryCtx,
+        items: Vec<(Vec<u8>, Vec<u8>)>,
+        commitment_prefix: Vec<u8>,
+        height: Height,
+        proof: Vec<u8>,
+    ) -> Result<bool, Self::Error> {
+        check_each_membership(items, &proof, |key, value, proof| {
+            self.check_membership(
+                QueryCtx::from((ctx.deps, ctx.env.clone())),
+                key,
+                value,
+                commitment_prefix.clone(),
+                height,
+                proof,
+            )
+        })
+    }
+
+    // ibc-go only exposes membership verification to contracts
+    fn check_non_membership(
+        &self,
+        _ctx: QueryCtx,
+        _key: Vec<u8>,
+        _commitment_prefix: Vec<u8>,
+        _height: Height,
+        _proof: Vec<u8>,
+    ) -> Result<bool, Self::Error> {
+        Err(ContractError::NonMembershipNotSupported)
+    }
+
+    // the native client keeps its own state, nothing is consumed here
+    fn consume_proofs(
+        &self,
+        _ctx: ExecCtx,
+        _height: Height,
+        _count: u64,
+    ) -> Result<Response, Self::Error> {
+        Ok(Response::default())
+    }
+}
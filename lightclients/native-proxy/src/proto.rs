@@ -0,0 +1,151 @@
+// ibc-go query messages, only the fields read by the proxy are declared
+// unknown fields, such as the proofs of the state queries, are skipped when decoding
+
+pub const CLIENT_STATUS_PATH: &str = "/ibc.core.client.v1.Query/ClientStatus";
+pub const CLIENT_STATE_PATH: &str = "/ibc.core.client.v1.Query/ClientState";
+pub const CONSENSUS_STATE_PATH: &str = "/ibc.core.client.v1.Query/ConsensusState";
+pub const VERIFY_MEMBERSHIP_PATH: &str = "/ibc.core.client.v1.Query/VerifyMembership";
+
+pub const TENDERMINT_CLIENT_STATE_TYPE_URL: &str = "/ibc.lightclients.tendermint.v1.ClientState";
+pub const TENDERMINT_CONSENSUS_STATE_TYPE_URL: &str =
+    "/ibc.lightclients.tendermint.v1.ConsensusState";
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Any {
+    #[prost(string, tag = "1")]
+    pub type_url: String,
+    #[prost(bytes = "vec", tag = "2")]
+    pub value: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Height {
+    #[prost(uint64, tag = "1")]
+    pub revision_number: u64,
+    #[prost(uint64, tag = "2")]
+    pub revision_height: u64,
+}
+
+// google.protobuf.Duration and google.protobuf.Timestamp
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Seconds {
+    #[prost(int64, tag = "1")]
+    pub seconds: i64,
+    #[prost(int32, tag = "2")]
+    pub nanos: i32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryClientStatusRequest {
+    #[prost(string, tag = "1")]
+    pub client_id: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryClientStatusResponse {
+    #[prost(string, tag = "1")]
+    pub status: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryClientStateRequest {
+    #[prost(string, tag = "1")]
+    pub client_id: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryClientStateResponse {
+    #[prost(message, optional, tag = "1")]
+    pub client_state: Option<Any>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryConsensusStateRequest {
+    #[prost(string, tag = "1")]
+    pub client_id: String,
+    #[prost(uint64, tag = "2")]
+    pub revision_number: u64,
+    #[prost(uint64, tag = "3")]
+    pub revision_height: u64,
+    #[prost(bool, tag = "4")]
+    pub latest_height: bool,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryConsensusStateResponse {
+    #[prost(message, optional, tag = "1")]
+    pub consensus_state: Option<Any>,
+}
+
+// ibc.core.commitment.v2.MerklePath, outermost key first
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MerklePath {
+    #[prost(bytes = "vec", repeated, tag = "1")]
+    pub key_path: Vec<Vec<u8>>,
+}
+
+// `value` must not be empty, ibc-go has no query verifying absence
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryVerifyMembershipRequest {
+    #[prost(string, tag = "1")]
+    pub client_id: String,
+    #[prost(bytes = "vec", tag = "2")]
+    pub proof: Vec<u8>,
+    #[prost(message, optional, tag = "3")]
+    pub proof_height: Option<Height>,
+    #[prost(bytes = "vec", tag = "5")]
+    pub value: Vec<u8>,
+    #[prost(uint64, tag = "6")]
+    pub time_delay: u64,
+    #[prost(uint64, tag = "7")]
+    pub block_delay: u64,
+    #[prost(message, optional, tag = "8")]
+    pub merkle_path: Option<MerklePath>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryVerifyMembershipResponse {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct TendermintClientState {
+    #[prost(string, tag = "1")]
+    pub chain_id: String,
+    #[prost(message, optional, tag = "2")]
+    pub trust_level: Option<Fraction>,
+    #[prost(message, optional, tag = "3")]
+    pub trusting_period: Option<Seconds>,
+    #[prost(message, optional, tag = "4")]
+    pub unbonding_period: Option<Seconds>,
+    #[prost(message, optional, tag = "5")]
+    pub max_clock_drift: Option<Seconds>,
+    // zero while the client is not frozen
+    #[prost(message, optional, tag = "6")]
+    pub frozen_height: Option<Height>,
+    #[prost(message, optional, tag = "7")]
+    pub latest_height: Option<Height>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Fraction {
+    #[prost(uint64, tag = "1")]
+    pub numerator: u64,
+    #[prost(uint64, tag = "2")]
+    pub denominator: u64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct TendermintConsensusState {
+    #[prost(message, optional, tag = "1")]
+    pub timestamp: Option<Seconds>,
+    #[prost(message, optional, tag = "2")]
+    pub root: Option<MerkleRoot>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MerkleRoot {
+    #[prost(bytes = "vec", tag = "1")]
+    pub hash: Vec<u8>,
+}
@@ -0,0 +1,42 @@
+use cosmwasm_std::StdError;
+use eureka_lightclient_interface::Height;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("unauthorized")]
+    Unauthorized,
+
+    #[error("client is frozen")]
+    ClientFrozen,
+
+    #[error("empty diversifier")]
+    EmptyDiversifier,
+
+    #[error("proof height does not match the sequence: height: {height}, sequence: {sequence}")]
+    SequenceMismatch { height: Height, sequence: u64 },
+
+    #[error("timestamp older than the consensus state: timestamp: {timestamp}, consensus timestamp: {consensus_timestamp}")]
+    TimestampTooOld {
+        timestamp: u64,
+        consensus_timestamp: u64,
+    },
+
+    #[error("consensus state not found at height: {height}")]
+    ConsensusStateNotFound { height: Height },
+
+    #[error("proof count mismatch: expected: {expected}, got: {got}")]
+    ProofCountMismatch { expected: usize, got: usize },
+
+    #[error("invalid signature")]
+    InvalidSignature,
+
+    #[error("no misbehaviour")]
+    NoMisbehaviour,
+
+    #[error("upgrade not supported")]
+    UpgradeNotSupported,
+}
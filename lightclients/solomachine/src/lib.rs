@@ -0,0 +1,585 @@
+// solo machine light client, for a single off-chain party holding one key
+// the party signs every header and proof over its current sequence, exposed as height 0-sequence
+// proofs are single use as in ibc-go: `update` advances the sequence, and so does each proof tao
+// accepted, through `consume_proofs`
+
+pub mod error;
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{from_json, to_json_vec, Addr, Api, StdError, StdResult, Storage};
+use cw_storey::containers::{Item, Map};
+use cw_storey::CwStorage;
+use eureka_ics23_verifier::apply_prefix;
+use eureka_lightclient_interface::{
+    self as interface, apply_headers, Height, LightClient, Status, UpdatePolicy,
+};
+use sha2::{Digest, Sha256};
+use sylvia::contract;
+use sylvia::cw_std::Response;
+use sylvia::types::{ExecCtx, InstantiateCtx, QueryCtx};
+
+pub use crate::error::ContractError;
+
+#[cw_serde]
+pub enum PublicKey {
+    // compressed or uncompressed SEC1 encoding
+    Secp256k1(Vec<u8>),
+    Ed25519(Vec<u8>),
+}
+
+impl PublicKey {
+    fn verify(&self, api: &dyn Api, message: &[u8], signature: &[u8]) -> bool {
+        let valid = match self {
+            Self::Secp256k1(key) => api.secp256k1_verify(message, signature, key),
+            Self::Ed25519(key) => api.ed25519_verify(message, signature, key),
+        };
+
+        matches!(valid, Ok(true))
+    }
+}
+
+#[cw_serde]
+pub struct ConsensusState {
+    pub public_key: PublicKey,
+    // separates signatures of the same key used by several solo machines
+    pub diversifier: String,
+    // in seconds
+    pub timestamp: u64,
+}
+
+#[cw_serde]
+pub struct ClientState {
+    pub sequence: u64,
+    pub frozen: bool,
+    pub consensus_state: ConsensusState,
+}
+
+// the message behind every solo machine signature
+// `data` is `None` for non-membership
+#[cw_serde]
+pub struct SignBytes {
+    pub sequence: u64,
+    pub timestamp: u64,
+    pub diversifier: String,
+    pub path: Vec<Vec<u8>>,
+    pub data: Option<Vec<u8>>,
+}
+
+impl SignBytes {
+    // sha256 of the JSON encoding, signed by either key type
+    pub fn digest(&self) -> StdResult<Vec<u8>> {
+        Ok(Sha256::digest(to_json_vec(self)?).to_vec())
+    }
+}
+
+// path of the header sign bytes, outside of any commitment prefix
+pub fn header_path() -> Vec<Vec<u8>> {
+    vec![b"solomachine".to_vec(), b"header".to_vec()]
+}
+
+// data of the header sign bytes
+#[cw_serde]
+pub struct HeaderData {
+    pub new_public_key: PublicKey,
+    pub new_diversifier: String,
+}
+
+// advances the sequence, rotating the key if `new_public_key` differs
+#[cw_serde]
+pub struct Header {
+    pub timestamp: u64,
+    pub signature: Vec<u8>,
+    pub new_public_key: PublicKey,
+    pub new_diversifier: String,
+}
+
+// membership and non-membership proof, over the current sequence
+// a batch proof holds one per item, over consecutive sequences
+#[cw_serde]
+pub struct TimestampedSignature {
+    pub signature: Vec<u8>,
+    pub timestamp: u64,
+}
+
+#[cw_serde]
+pub struct SignatureAndData {
+    pub signature: Vec<u8>,
+    pub path: Vec<Vec<u8>>,
+    pub data: Option<Vec<u8>>,
+    pub timestamp: u64,
+}
+
+// two different values signed for the same path at the same sequence
+#[cw_serde]
+pub struct Misbehaviour {
+    pub sequence: u64,
+    pub signature_1: SignatureAndData,
+    pub signature_2: SignatureAndData,
+}
+
+// prefixed path and value signed by a proof, a missing value for non-membership
+type Commitment = (Vec<Vec<u8>>, Option<Vec<u8>>);
+
+fn verify_signature(
+    api: &dyn Api,
+    consensus_state: &ConsensusState,
+    sequence: u64,
+    timestamp: u64,
+    path: Vec<Vec<u8>>,
+    data: Option<Vec<u8>>,
+    signature: &[u8],
+) -> Result<(), ContractError> {
+    if timestamp < consensus_state.timestamp {
+        return Err(ContractError::TimestampTooOld {
+            timestamp,
+            consensus_timestamp: consensus_state.timestamp,
+        });
+    }
+
+    let digest = SignBytes {
+        sequence,
+        timestamp,
+        diversifier: consensus_state.diversifier.clone(),
+        path,
+        data,
+    }
+    .digest()?;
+
+    if !consensus_state.public_key.verify(api, &digest, signature) {
+        return Err(ContractError::InvalidSignature);
+    }
+
+    Ok(())
+}
+
+pub struct Contract {
+    pub client_state: Item<ClientState>,
+    // sequence -> consensus timestamp, only the current sequence is kept as proofs are only
+    // valid there
+    pub timestamps: Map<u64, Item<u64>>,
+    // instantiator, the only one consuming proofs: tao when created through its factory
+    pub tao: Item<Addr>,
+}
+
+#[cfg_attr(not(feature = "library"), sylvia::entry_points)]
+#[contract]
+#[sv::error(ContractError)]
+#[sv::messages(eureka_lightclient_interface)]
+impl Contract {
+    pub const fn new() -> Self {
+        Self {
+            client_state: Item::new(b'C'),
+            timestamps: Map::new(b'S'),
+            tao: Item::new(b'T'),
+        }
+    }
+
+    #[sv::msg(instantiate)]
+    fn instantiate(
+        &self,
+        ctx: InstantiateCtx,
+        client_state: ClientState,
+    ) -> Result<Response, ContractError> {
+        if client_state.consensus_state.diversifier.is_empty() {
+            return Err(ContractError::EmptyDiversifier);
+        }
+
+        let mut storage = CwStorage(ctx.deps.storage);
+
+        self.timestamps
+            .access(&mut storage)
+            .entry_mut(&client_state.sequence)
+            .set(&client_state.consensus_state.timestamp)?;
+        self.client_state.access(&mut storage).set(&client_state)?;
+        self.tao.access(&mut storage).set(&ctx.info.sender)?;
+
+        Ok(Response::default())
+    }
+}
+
+impl Contract {
+    // stores `client_state` advanced from `previous_sequence`, whose timestamp is dropped
+    fn advance(
+        &self,
+        storage: &mut dyn Storage,
+        previous_sequence: u64,
+        client_state: &ClientState,
+    ) -> Result<(), ContractError> {
+        let mut storage = CwStorage(storage);
+
+        let mut timestamps = self.timestamps.access(&mut storage);
+        timestamps.entry_mut(&previous_sequence).remove();
+        timestamps
+            .entry_mut(&client_state.sequence)
+            .set(&client_state.consensus_state.timestamp)?;
+
+        self.client_state.access(&mut storage).set(client_state)?;
+
+        Ok(())
+    }
+
+    fn load_client_state(&self, storage: &dyn Storage) -> Result<ClientState, ContractError> {
+        self.client_state
+            .access(&CwStorage(storage))
+            .get()?
+            .ok_or_else(|| StdError::not_found("client state").into())
+    }
+
+    // client state at `height`, which must be the current sequence
+    fn load_current(
+        &self,
+        storage: &dyn Storage,
+        height: Height,
+    ) -> Result<ClientState, ContractError> {
+        let client_state = self.load_client_state(storage)?;
+
+        if client_state.frozen {
+            return Err(ContractError::ClientFrozen);
+        }
+
+        if height != Height::new(0, client_state.sequence) {
+            return Err(ContractError::SequenceMismatch {
+                height,
+                sequence: client_state.sequence,
+            });
+        }
+
+        Ok(client_state)
+    }
+
+    // checks the signatures over the commitments at their paths, the first one at the current
+    // sequence and each following one at the next sequence
+    fn verify_commitments(
+        &self,
+        storage: &dyn Storage,
+        api: &dyn Api,
+        commitments: Vec<Commitment>,
+        height: Height,
+        proofs: Vec<Vec<u8>>,
+    ) -> Result<(), ContractError> {
+        let client_state = self.load_current(storage, height)?;
+
+        if proofs.len() != commitments.len() {
+            return Err(ContractError::ProofCountMismatch {
+                expected: commitments.len(),
+                got: proofs.len(),
+            });
+        }
+
+        for (sequence, ((path, value), proof)) in
+            (client_state.sequence..).zip(commitments.into_iter().zip(proofs))
+        {
+            let TimestampedSignature {
+                signature,
+                timestamp,
+            } = from_json(proof)?;
+
+            verify_signature(
+                api,
+                &client_state.consensus_state,
+                sequence,
+                timestamp,
+                path,
+                value,
+                &signature,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl LightClient for Contract {
+    type Error = ContractError;
+
+    fn update(&self, ctx: ExecCtx, header: Vec<u8>) -> Result<Response, Self::Error> {
+        let mut client_state = self.load_client_state(ctx.deps.storage)?;
+
+        if client_state.frozen {
+            return Err(ContractError::ClientFrozen);
+        }
+
+        let Header {
+            timestamp,
+            signature,
+            new_public_key,
+            new_diversifier,
+        } = from_json(header)?;
+
+        if new_diversifier.is_empty() {
+            return Err(ContractError::EmptyDiversifier);
+        }
+
+        let data = HeaderData {
+            new_public_key,
+            new_diversifier,
+        };
+
+        verify_signature(
+            ctx.deps.api,
+            &client_state.consensus_state,
+            client_state.sequence,
+            timestamp,
+            header_path(),
+            Some(to_json_vec(&data)?),
+            &signature,
+        )?;
+
+        let previous_sequence = client_state.sequence;
+
+        client_state.sequence += 1;
+        client_state.consensus_state = ConsensusState {
+            public_key: data.new_public_key,
+            diversifier: data.new_diversifier,
+            timestamp,
+        };
+
+        self.advance(ctx.deps.storage, previous_sequence, &client_state)?;
+
+        Ok(Response::default())
+    }
+
+    fn update_batch(&self, ctx: ExecCtx, headers: Vec<Vec<u8>>) -> Result<Response, Self::Error> {
+        apply_headers(ctx, headers, |ctx, header| self.update(ctx, header))
+    }
+
+    // headers are verified against the trusted public key
+    fn update_policy(&self, _ctx: QueryCtx) -> Result<UpdatePolicy, Self::Error> {
+        Ok(UpdatePolicy::Permissionless)
+    }
+
+    fn check_for_misbehaviour(
+        &self,
+        ctx: QueryCtx,
+        misbehaviour: Vec<u8>,
+    ) -> Result<bool, Self::Error> {
+        let Misbehaviour {
+            sequence,
+            signature_1,
+            signature_2,
+        } = from_json(misbehaviour)?;
+
+        let client_state = self.load_client_state(ctx.deps.storage)?;
+
+        for signature in [&signature_1, &signature_2] {
+            verify_signature(
+                ctx.deps.api,
+                &client_state.consensus_state,
+                sequence,
+                signature.timestamp,
+                signature.path.clone(),
+                signature.data.clone(),
+                &signature.signature,
+            )?;
+        }
+
+        // signatures for different paths are expected to share a sequence when proofs are not
+        // consumed in between, only conflicting values for the same path are misbehaviour
+        Ok(signature_1.path == signature_2.path && signature_1.data != signature_2.data)
+    }
+
+    fn update_on_misbehaviour(
+        &self,
+        ctx: ExecCtx,
+        misbehaviour: Vec<u8>,
+    ) -> Result<Response, Self::Error> {
+        if !self.check_for_misbehaviour(
+            QueryCtx::from((ctx.deps.as_ref(), ctx.env.clone())),
+            misbehaviour,
+        )? {
+            return Err(ContractError::NoMisbehaviour);
+        }
+
+        let mut client_state = self.load_client_state(ctx.deps.storage)?;
+        client_state.frozen = true;
+
+        self.client_state
+            .access(&mut CwStorage(ctx.deps.storage))
+            .set(&client_state)?;
+
+        Ok(Response::default())
+    }
+
+    // a new key is taken over with `update`
+    fn verify_upgrade_and_update_state(
+        &self,
+        _ctx: ExecCtx,
+        _upgraded_client: Vec<u8>,
+        _upgraded_consensus: Vec<u8>,
+        _proofs: Vec<u8>,
+    ) -> Result<Response, Self::Error> {
+        Err(ContractError::UpgradeNotSupported)
+    }
+
+    // the diversifier stands for the chain id of the solo machine
+    fn client_state(&self, ctx: QueryCtx) -> Result<interface::ClientState, Self::Error> {
+        let client_state = self.load_client_state(ctx.deps.storage)?;
+        let latest_height = Height::new(0, client_state.sequence);
+
+        Ok(interface::ClientState {
+            chain_id: client_state.consensus_state.diversifier.clone(),
+            latest_height,
+            frozen_height: client_state.frozen.then_some(latest_height),
+            data: to_json_vec(&client_state)?,
+        })
+    }
+
+    // only the consensus state at the current sequence is kept, solo machines keep no root
+    fn consensus_state(
+        &self,
+        ctx: QueryCtx,
+        height: Height,
+    ) -> Result<interface::ConsensusState, Self::Error> {
+        let client_state = self.load_client_state(ctx.deps.storage)?;
+
+        if height != Height::new(0, client_state.sequence) {
+            return Err(ContractError::ConsensusStateNotFound { height });
+        }
+
+        Ok(interface::ConsensusState {
+            timestamp: client_state.consensus_state.timestamp,
+            root: vec![],
+            data: to_json_vec(&client_state.consensus_state)?,
+        })
+    }
+
+    fn status(&self, ctx: QueryCtx) -> Result<Status, Self::Error> {
+        if self.load_client_state(ctx.deps.storage)?.frozen {
+            Ok(Status::Frozen)
+        } else {
+            Ok(Status::Active)
+        }
+    }
+
+    fn latest_height(&self, ctx: QueryCtx) -> Result<Height, Self::Error> {
+        Ok(Height::new(
+            0,
+            self.load_client_state(ctx.deps.storage)?.sequence,
+        ))
+    }
+
+    fn timestamp(&self, ctx: QueryCtx, height: Height) -> Result<u64, Self::Error> {
+        self.consensus_timestamp(ctx, height)?
+            .ok_or(ContractError::ConsensusStateNotFound { height })
+    }
+
+    fn consensus_timestamp(
+        &self,
+        ctx: QueryCtx,
+        height: Height,
+    ) -> Result<Option<u64>, Self::Error> {
+        if height.revision_number != 0 {
+            return Ok(None);
+        }
+
+        Ok(self
+            .timestamps
+            .access(&CwStorage(ctx.deps.storage))
+            .entry(&height.revision_height)
+            .get()?)
+    }
+
+    // the solo machine key is trusted until rotated
+    fn trusting_period(&self, _ctx: QueryCtx) -> Result<u64, Self::Error> {
+        Ok(u64::MAX)
+    }
+
+    // a substitute may be signed by another key, only the diversifier has to match
+    fn client_parameters(&self, ctx: QueryCtx) -> Result<Vec<u8>, Self::Error> {
+        Ok(to_json_vec(
+            &self
+                .load_client_state(ctx.deps.storage)?
+                .consensus_state
+                .diversifier,
+        )?)
+    }
+
+    // the timestamp of a sequence is removed as soon as it is passed, nothing to prune
+    fn prune(&self, _ctx: ExecCtx) -> Result<Response, Self::Error> {
+        Ok(Response::default())
+    }
+
+    fn check_membership(
+        &self,
+        ctx: QueryCtx,
+        key: Vec<u8>,
+        value: Vec<u8>,
+        commitment_prefix: Vec<u8>,
+        height: Height,
+        proof: Vec<u8>,
+    ) -> Result<bool, Self::Error> {
+        self.verify_commitments(
+            ctx.deps.storage,
+            ctx.deps.api,
+            vec![(apply_prefix(&commitment_prefix, &key), Some(value))],
+            height,
+            vec![proof],
+        )?;
+
+        Ok(true)
+    }
+
+    // a `batch_proof` of the items signed at consecutive sequences
+    fn verify_batch_membership(
+        &self,
+        ctx: QueryCtx,
+        items: Vec<(Vec<u8>, Vec<u8>)>,
+        commitment_prefix: Vec<u8>,
+        height: Height,
+        proof: Vec<u8>,
+    ) -> Result<bool, Self::Error> {
+        self.verify_commitments(
+            ctx.deps.storage,
+            ctx.deps.api,
+            items
+                .into_iter()
+                .map(|(key, value)| (apply_prefix(&commitment_prefix, &key), Some(value)))
+                .collect(),
+            height,
+            from_json(proof)?,
+        )?;
+
+        Ok(true)
+    }
+
+    fn check_non_membership(
+        &self,
+        ctx: QueryCtx,
+        key: Vec<u8>,
+        commitment_prefix: Vec<u8>,
+        height: Height,
+        proof: Vec<u8>,
+    ) -> Result<bool, Self::Error> {
+        self.verify_commitments(
+            ctx.deps.storage,
+            ctx.deps.api,
+            vec![(apply_prefix(&commitment_prefix, &key), None)],
+            height,
+            vec![proof],
+        )?;
+
+        Ok(true)
+    }
+
+    // the signatures of the accepted proofs cannot be replayed past their sequences
+    fn consume_proofs(
+        &self,
+        ctx: ExecCtx,
+        height: Height,
+        count: u64,
+    ) -> Result<Response, Self::Error> {
+        if self.tao.access(&CwStorage(&*ctx.deps.storage)).get()? != Some(ctx.info.sender) {
+            return Err(ContractError::Unauthorized);
+        }
+
+        let mut client_state = self.load_current(ctx.deps.storage, height)?;
+
+        let previous_sequence = client_state.sequence;
+
+        client_state.sequence += count;
+
+        self.advance(ctx.deps.storage, previous_sequence, &client_state)?;
+
+        Ok(Response::default())
+    }
+}
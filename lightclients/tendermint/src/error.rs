@@ -0,0 +1,102 @@
+use cosmwasm_std::StdError;
+use eureka_ics23_verifier::ProofError;
+use eureka_lightclient_interface::Height;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("invalid proof: {0}")]
+    Proof(#[from] ProofError),
+
+    #[error("invalid trust level: {numerator}/{denominator}")]
+    InvalidTrustLevel { numerator: u64, denominator: u64 },
+
+    #[error("trusting period must be positive")]
+    ZeroTrustingPeriod,
+
+    #[error("trusting period not below unbonding period: trusting period: {trusting_period}, unbonding period: {unbonding_period}")]
+    TrustingPeriodNotBelowUnbonding {
+        trusting_period: u64,
+        unbonding_period: u64,
+    },
+
+    #[error("client is frozen at height: {height}")]
+    ClientFrozen { height: Height },
+
+    #[error("consensus state not found at height: {height}")]
+    ConsensusStateNotFound { height: Height },
+
+    #[error("chain id mismatch: expected: {expected}, got: {got}")]
+    ChainIdMismatch { expected: String, got: String },
+
+    #[error("trusted consensus state expired: timestamp: {timestamp}, trusting period: {trusting_period}, now: {now}")]
+    TrustedConsensusStateExpired {
+        timestamp: u64,
+        trusting_period: u64,
+        now: u64,
+    },
+
+    #[error("header height not above trusted height: trusted: {trusted}, height: {height}")]
+    NonIncreasingHeight { trusted: u64, height: u64 },
+
+    #[error("header time not after trusted time: trusted: {trusted}, time: {time}")]
+    NonIncreasingTime { trusted: u64, time: u64 },
+
+    #[error("header time too far in the future: time: {time}, now: {now}, max clock drift: {max_clock_drift}")]
+    HeaderFromFuture {
+        time: u64,
+        now: u64,
+        max_clock_drift: u64,
+    },
+
+    #[error("validator set does not match header validators hash")]
+    ValidatorSetHashMismatch,
+
+    #[error("validator set does not match trusted next validators hash")]
+    NextValidatorSetHashMismatch,
+
+    #[error("duplicate validator in validator set")]
+    DuplicateValidator,
+
+    #[error("signature count mismatch: validators: {validators}, signatures: {signatures}")]
+    SignatureCountMismatch {
+        validators: usize,
+        signatures: usize,
+    },
+
+    #[error("commit height mismatch: height: {height}, commit height: {commit_height}")]
+    CommitHeightMismatch { height: u64, commit_height: u64 },
+
+    #[error("commit block id does not match header hash")]
+    CommitBlockIdMismatch,
+
+    #[error("invalid timestamp")]
+    InvalidTimestamp,
+
+    #[error("invalid signature of validator: {index}")]
+    InvalidSignature { index: usize },
+
+    #[error("insufficient voting power: signed: {signed}, total: {total}")]
+    InsufficientVotingPower { signed: u128, total: u128 },
+
+    #[error("insufficient trusted voting power: signed: {signed}, total: {total}")]
+    InsufficientTrustedVotingPower { signed: u128, total: u128 },
+
+    #[error("misbehaviour header_1 is lower than header_2")]
+    MisbehaviourHeightOrder,
+
+    #[error("upgraded height not above latest height: latest: {latest_height}, upgraded: {upgraded_height}")]
+    UpgradeHeightNotIncreasing {
+        latest_height: Height,
+        upgraded_height: Height,
+    },
+
+    #[error("invalid upgraded state: {type_url}")]
+    InvalidUpgradedState { type_url: String },
+
+    #[error("no misbehaviour")]
+    NoMisbehaviour,
+}
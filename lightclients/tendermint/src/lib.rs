@@ -0,0 +1,940 @@
+// Tendermint light client
+// headers are JSON encoded `Header`s, block hashes, validator set hashes and vote sign
+// bytes follow the CometBFT protobuf encodings
+
+pub mod error;
+pub mod proto;
+pub mod verify;
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{from_json, to_json_vec, Api, StdError, Storage};
+use cw_storey::containers::{Item, Map};
+use cw_storey::CwStorage;
+use eureka_ics23_verifier::{apply_prefix, sdk_specs};
+use eureka_lightclient_interface::{
+    self as interface, apply_headers, is_expired, Height, LightClient, Status, UpdatePolicy,
+    PRUNE_LIMIT,
+};
+use prost::Message;
+use storey::containers::{BoundedIterableAccessor, IterableAccessor};
+use sylvia::contract;
+use sylvia::cw_std::Response;
+use sylvia::types::{ExecCtx, InstantiateCtx, QueryCtx};
+
+pub use crate::error::ContractError;
+use crate::verify::verify_header;
+
+#[cw_serde]
+pub struct Fraction {
+    pub numerator: u64,
+    pub denominator: u64,
+}
+
+#[cw_serde]
+pub struct ClientState {
+    pub chain_id: String,
+    // minimum fraction of trusted voting power to skip to a non-adjacent header
+    pub trust_level: Fraction,
+    // in seconds
+    pub trusting_period: u64,
+    // in seconds
+    pub max_clock_drift: u64,
+    // in seconds, the trusting period must be below it
+    pub unbonding_period: u64,
+    pub latest_height: Height,
+    pub frozen_height: Option<Height>,
+}
+
+#[cw_serde]
+pub struct ConsensusState {
+    // block time in unix nanoseconds
+    pub timestamp: u64,
+    // app hash, the root of the multistore
+    pub root: Vec<u8>,
+    pub next_validators_hash: Vec<u8>,
+}
+
+#[cw_serde]
+pub struct Validator {
+    // ed25519 public key
+    pub pub_key: Vec<u8>,
+    pub voting_power: u64,
+}
+
+#[cw_serde]
+pub struct ValidatorSet {
+    pub validators: Vec<Validator>,
+}
+
+// protobuf timestamp, seconds and nanoseconds since the unix epoch
+#[cw_serde]
+pub struct Timestamp {
+    pub seconds: i64,
+    pub nanos: i32,
+}
+
+#[cw_serde]
+pub struct Version {
+    pub block: u64,
+    pub app: u64,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct PartSetHeader {
+    pub total: u32,
+    pub hash: Vec<u8>,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct BlockId {
+    pub hash: Vec<u8>,
+    pub part_set_header: PartSetHeader,
+}
+
+#[cw_serde]
+pub struct BlockHeader {
+    pub version: Version,
+    pub chain_id: String,
+    pub height: u64,
+    pub time: Timestamp,
+    pub last_block_id: BlockId,
+    pub last_commit_hash: Vec<u8>,
+    pub data_hash: Vec<u8>,
+    pub validators_hash: Vec<u8>,
+    pub next_validators_hash: Vec<u8>,
+    pub consensus_hash: Vec<u8>,
+    pub app_hash: Vec<u8>,
+    pub last_results_hash: Vec<u8>,
+    pub evidence_hash: Vec<u8>,
+    pub proposer_address: Vec<u8>,
+}
+
+// precommit of a validator for the committed block
+#[cw_serde]
+pub struct CommitSig {
+    pub timestamp: Timestamp,
+    pub signature: Vec<u8>,
+}
+
+#[cw_serde]
+pub struct Commit {
+    pub height: u64,
+    pub round: u32,
+    pub block_id: BlockId,
+    // in validator set order, `None` for absent validators and nil votes
+    pub signatures: Vec<Option<CommitSig>>,
+}
+
+#[cw_serde]
+pub struct SignedHeader {
+    pub header: BlockHeader,
+    pub commit: Commit,
+}
+
+#[cw_serde]
+pub struct Header {
+    pub signed_header: SignedHeader,
+    pub validator_set: ValidatorSet,
+    pub trusted_height: Height,
+    // next validator set of the trusted consensus state
+    pub trusted_validators: ValidatorSet,
+}
+
+// two valid headers that cannot both be part of the chain
+#[cw_serde]
+pub struct Misbehaviour {
+    pub header_1: Header,
+    pub header_2: Header,
+}
+
+// store of the upgraded client and consensus states committed by the counterparty
+pub const UPGRADE_STORE: &[u8] = b"upgrade";
+
+// `height` is the last height of the counterparty before the upgrade
+pub fn upgraded_client_key(height: u64) -> Vec<u8> {
+    format!("upgradedIBCState/{height}/upgradedClient").into_bytes()
+}
+
+pub fn upgraded_consensus_state_key(height: u64) -> Vec<u8> {
+    format!("upgradedIBCState/{height}/upgradedConsState").into_bytes()
+}
+
+// ICS23 proofs of the upgraded states, against the root at the client latest height
+#[cw_serde]
+pub struct UpgradeProofs {
+    pub client: Vec<u8>,
+    pub consensus_state: Vec<u8>,
+}
+
+// root of the upgraded consensus state, unusable until the first header of the upgraded chain
+pub const SENTINEL_ROOT: &[u8] = b"sentinel_root";
+
+// protobuf `Any` of `type_url`, as committed by ibc-go
+fn decode_any<M: Message + Default>(bytes: &[u8], type_url: &str) -> Result<M, ContractError> {
+    let invalid = || ContractError::InvalidUpgradedState {
+        type_url: type_url.to_string(),
+    };
+
+    let any = proto::Any::decode(bytes).map_err(|_| invalid())?;
+
+    if any.type_url != type_url {
+        return Err(invalid());
+    }
+
+    M::decode(any.value.as_slice()).map_err(|_| invalid())
+}
+
+pub const NANOS_PER_SECOND: u64 = 1_000_000_000;
+
+// whole seconds of a unix nanoseconds timestamp
+pub fn seconds(nanos: u64) -> u64 {
+    nanos / NANOS_PER_SECOND
+}
+
+impl Timestamp {
+    // `None` before the unix epoch or past `u64` nanoseconds
+    pub fn unix_nanos(&self) -> Option<u64> {
+        let seconds = u64::try_from(self.seconds).ok()?;
+        let nanos = u64::try_from(self.nanos)
+            .ok()
+            .filter(|nanos| *nanos < NANOS_PER_SECOND)?;
+
+        seconds.checked_mul(NANOS_PER_SECOND)?.checked_add(nanos)
+    }
+}
+
+impl From<&Timestamp> for proto::Timestamp {
+    fn from(timestamp: &Timestamp) -> Self {
+        Self {
+            seconds: timestamp.seconds,
+            nanos: timestamp.nanos,
+        }
+    }
+}
+
+impl From<&BlockId> for proto::BlockId {
+    fn from(block_id: &BlockId) -> Self {
+        Self {
+            hash: block_id.hash.clone(),
+            part_set_header: Some(proto::PartSetHeader {
+                total: block_id.part_set_header.total,
+                hash: block_id.part_set_header.hash.clone(),
+            }),
+        }
+    }
+}
+
+impl ValidatorSet {
+    // merkle root of the `SimpleValidator` encodings
+    pub fn hash(&self) -> Vec<u8> {
+        let leaves = self
+            .validators
+            .iter()
+            .map(|validator| {
+                proto::SimpleValidator {
+                    pub_key: Some(proto::PublicKey {
+                        ed25519: validator.pub_key.clone(),
+                    }),
+                    voting_power: validator.voting_power as i64,
+                }
+                .encode_to_vec()
+            })
+            .collect::<Vec<_>>();
+
+        proto::merkle_root(&leaves)
+    }
+}
+
+impl BlockHeader {
+    // merkle root of the encoded fields, in protobuf order
+    pub fn hash(&self) -> Vec<u8> {
+        let bytes = |value: &[u8]| {
+            proto::BytesValue {
+                value: value.to_vec(),
+            }
+            .encode_to_vec()
+        };
+
+        proto::merkle_root(&[
+            proto::Consensus {
+                block: self.version.block,
+                app: self.version.app,
+            }
+            .encode_to_vec(),
+            proto::StringValue {
+                value: self.chain_id.clone(),
+            }
+            .encode_to_vec(),
+            proto::Int64Value {
+                value: self.height as i64,
+            }
+            .encode_to_vec(),
+            proto::Timestamp::from(&self.time).encode_to_vec(),
+            proto::BlockId::from(&self.last_block_id).encode_to_vec(),
+            bytes(&self.last_commit_hash),
+            bytes(&self.data_hash),
+            bytes(&self.validators_hash),
+            bytes(&self.next_validators_hash),
+            bytes(&self.consensus_hash),
+            bytes(&self.app_hash),
+            bytes(&self.last_results_hash),
+            bytes(&self.evidence_hash),
+            bytes(&self.proposer_address),
+        ])
+    }
+}
+
+impl Commit {
+    // length-delimited `CanonicalVote` signed by the validator of `signature`
+    pub fn vote_sign_bytes(&self, chain_id: &str, signature: &CommitSig) -> Vec<u8> {
+        proto::CanonicalVote {
+            r#type: proto::PRECOMMIT_TYPE,
+            height: self.height as i64,
+            round: i64::from(self.round),
+            // the zero block id of nil votes is left out
+            block_id: (self.block_id != BlockId::default()).then(|| (&self.block_id).into()),
+            timestamp: Some(proto::Timestamp::from(&signature.timestamp)),
+            chain_id: chain_id.to_string(),
+        }
+        .encode_length_delimited_to_vec()
+    }
+}
+
+impl Header {
+    pub fn height(&self) -> Height {
+        Height::new(
+            self.trusted_height.revision_number,
+            self.signed_header.header.height,
+        )
+    }
+
+    pub fn consensus_state(&self) -> Result<ConsensusState, ContractError> {
+        let header = &self.signed_header.header;
+
+        Ok(ConsensusState {
+            timestamp: header
+                .time
+                .unix_nanos()
+                .ok_or(ContractError::InvalidTimestamp)?,
+            root: header.app_hash.clone(),
+            next_validators_hash: header.next_validators_hash.clone(),
+        })
+    }
+}
+
+pub struct Contract {
+    pub client_state: Item<ClientState>,
+    // revision number -> revision height -> consensus state
+    pub consensus_states: Map<u64, Map<u64, Item<ConsensusState>>>,
+}
+
+#[cfg_attr(not(feature = "library"), sylvia::entry_points)]
+#[contract]
+#[sv::error(ContractError)]
+#[sv::messages(eureka_lightclient_interface)]
+impl Contract {
+    pub const fn new() -> Self {
+        Self {
+            client_state: Item::new(b'C'),
+            consensus_states: Map::new(b'S'),
+        }
+    }
+
+    #[sv::msg(instantiate)]
+    fn instantiate(
+        &self,
+        ctx: InstantiateCtx,
+        client_state: ClientState,
+        consensus_state: ConsensusState,
+    ) -> Result<Response, ContractError> {
+        let Fraction {
+            numerator,
+            denominator,
+        } = client_state.trust_level;
+
+        // trust level must be within [1/3, 1]
+        if denominator == 0
+            || numerator > denominator
+            || u128::from(numerator) * 3 < u128::from(denominator)
+        {
+            return Err(ContractError::InvalidTrustLevel {
+                numerator,
+                denominator,
+            });
+        }
+
+        if client_state.trusting_period == 0 {
+            return Err(ContractError::ZeroTrustingPeriod);
+        }
+
+        if client_state.trusting_period >= client_state.unbonding_period {
+            return Err(ContractError::TrustingPeriodNotBelowUnbonding {
+                trusting_period: client_state.trusting_period,
+                unbonding_period: client_state.unbonding_period,
+            });
+        }
+
+        let mut storage = CwStorage(ctx.deps.storage);
+
+        self.consensus_states
+            .access(&mut storage)
+            .entry_mut(&client_state.latest_height.revision_number)
+            .entry_mut(&client_state.latest_height.revision_height)
+            .set(&consensus_state)?;
+        self.client_state.access(&mut storage).set(&client_state)?;
+
+        Ok(Response::default())
+    }
+}
+
+impl Contract {
+    fn load_client_state(&self, storage: &dyn Storage) -> Result<ClientState, ContractError> {
+        self.client_state
+            .access(&CwStorage(storage))
+            .get()?
+            .ok_or_else(|| StdError::not_found("client state").into())
+    }
+
+    fn find_consensus_state(
+        &self,
+        storage: &dyn Storage,
+        height: Height,
+    ) -> Result<Option<ConsensusState>, ContractError> {
+        Ok(self
+            .consensus_states
+            .access(&CwStorage(storage))
+            .entry(&height.revision_number)
+            .entry(&height.revision_height)
+            .get()?)
+    }
+
+    fn load_consensus_state(
+        &self,
+        storage: &dyn Storage,
+        height: Height,
+    ) -> Result<ConsensusState, ContractError> {
+        self.find_consensus_state(storage, height)?
+            .ok_or(ContractError::ConsensusStateNotFound { height })
+    }
+
+    // whether the block time at `height` is between the ones of the closest stored heights of
+    // the revision, the lower one being at least `trusted_height`
+    fn is_monotonic(
+        &self,
+        storage: &dyn Storage,
+        trusted_height: Height,
+        height: Height,
+        consensus_state: &ConsensusState,
+    ) -> Result<bool, ContractError> {
+        let storage = CwStorage(storage);
+        let consensus_states = self.consensus_states.access(&storage);
+        let revision = consensus_states.entry(&height.revision_number);
+
+        let invalid = |_| StdError::generic_err("invalid consensus state");
+
+        let previous = revision
+            .bounded_values(
+                Some(&trusted_height.revision_height),
+                Some(&height.revision_height),
+            )
+            .last()
+            .transpose()
+            .map_err(invalid)?;
+
+        let next = revision
+            .bounded_values(
+                Some(&height.revision_height.saturating_add(1)),
+                None::<&u64>,
+            )
+            .next()
+            .transpose()
+            .map_err(invalid)?;
+
+        Ok(previous.map_or(true, |previous| {
+            previous.timestamp < consensus_state.timestamp
+        }) && next.map_or(true, |next| consensus_state.timestamp < next.timestamp))
+    }
+
+    // height of the conflict between the verified headers, if any
+    fn detect_misbehaviour(
+        &self,
+        storage: &dyn Storage,
+        api: &dyn Api,
+        now: u64,
+        misbehaviour: &Misbehaviour,
+    ) -> Result<Option<Height>, ContractError> {
+        let client_state = self.load_client_state(storage)?;
+
+        for header in [&misbehaviour.header_1, &misbehaviour.header_2] {
+            let trusted = self.load_consensus_state(storage, header.trusted_height)?;
+            verify_header(api, &client_state, &trusted, header, now)?;
+        }
+
+        let header_1 = &misbehaviour.header_1.signed_header.header;
+        let header_2 = &misbehaviour.header_2.signed_header.header;
+
+        if header_1.height < header_2.height {
+            return Err(ContractError::MisbehaviourHeightOrder);
+        }
+
+        // two blocks at the same height, or block time going backwards
+        let conflict = if header_1.height == header_2.height {
+            header_1.hash() != header_2.hash()
+        } else {
+            misbehaviour.header_1.consensus_state()?.timestamp
+                <= misbehaviour.header_2.consensus_state()?.timestamp
+        };
+
+        Ok(conflict.then(|| misbehaviour.header_1.height()))
+    }
+}
+
+impl LightClient for Contract {
+    type Error = ContractError;
+
+    fn update(&self, ctx: ExecCtx, header: Vec<u8>) -> Result<Response, Self::Error> {
+        let header: Header = from_json(header)?;
+
+        let mut client_state = self.load_client_state(ctx.deps.storage)?;
+
+        if let Some(height) = client_state.frozen_height {
+            return Err(ContractError::ClientFrozen { height });
+        }
+
+        let trusted = self.load_consensus_state(ctx.deps.storage, header.trusted_height)?;
+
+        verify_header(
+            ctx.deps.api,
+            &client_state,
+            &trusted,
+            &header,
+            ctx.env.block.time.nanos(),
+        )?;
+
+        let height = header.height();
+        let consensus_state = header.consensus_state()?;
+
+        let monotonic = self.is_monotonic(
+            ctx.deps.storage,
+            header.trusted_height,
+            height,
+            &consensus_state,
+        )?;
+
+        let mut storage = CwStorage(ctx.deps.storage);
+
+        let existing = self
+            .consensus_states
+            .access(&storage)
+            .entry(&height.revision_number)
+            .entry(&height.revision_height)
+            .get()?;
+
+        match existing {
+            Some(existing) if existing == consensus_state => {}
+            None if monotonic => {
+                self.consensus_states
+                    .access(&mut storage)
+                    .entry_mut(&height.revision_number)
+                    .entry_mut(&height.revision_height)
+                    .set(&consensus_state)?;
+
+                if height > client_state.latest_height {
+                    client_state.latest_height = height;
+                    self.client_state.access(&mut storage).set(&client_state)?;
+                }
+            }
+            // a different verified header at a known height is misbehaviour, as is block time
+            // not increasing with the height
+            _ => {
+                client_state.frozen_height = Some(height);
+                self.client_state.access(&mut storage).set(&client_state)?;
+            }
+        }
+
+        Ok(Response::default())
+    }
+
+    fn update_batch(&self, ctx: ExecCtx, headers: Vec<Vec<u8>>) -> Result<Response, Self::Error> {
+        apply_headers(ctx, headers, |ctx, header| self.update(ctx, header))
+    }
+
+    // headers are verified against the trusted validator set
+    fn update_policy(&self, _ctx: QueryCtx) -> Result<UpdatePolicy, Self::Error> {
+        Ok(UpdatePolicy::Permissionless)
+    }
+
+    fn check_for_misbehaviour(
+        &self,
+        ctx: QueryCtx,
+        misbehaviour: Vec<u8>,
+    ) -> Result<bool, Self::Error> {
+        let misbehaviour: Misbehaviour = from_json(misbehaviour)?;
+
+        Ok(self
+            .detect_misbehaviour(
+                ctx.deps.storage,
+                ctx.deps.api,
+                ctx.env.block.time.nanos(),
+                &misbehaviour,
+            )?
+            .is_some())
+    }
+
+    fn update_on_misbehaviour(
+        &self,
+        ctx: ExecCtx,
+        misbehaviour: Vec<u8>,
+    ) -> Result<Response, Self::Error> {
+        let misbehaviour: Misbehaviour = from_json(misbehaviour)?;
+
+        let height = self
+            .detect_misbehaviour(
+                ctx.deps.storage,
+                ctx.deps.api,
+                ctx.env.block.time.nanos(),
+                &misbehaviour,
+            )?
+            .ok_or(ContractError::NoMisbehaviour)?;
+
+        let mut client_state = self.load_client_state(ctx.deps.storage)?;
+        client_state.frozen_height.get_or_insert(height);
+
+        self.client_state
+            .access(&mut CwStorage(ctx.deps.storage))
+            .set(&client_state)?;
+
+        Ok(Response::default())
+    }
+
+    fn verify_upgrade_and_update_state(
+        &self,
+        ctx: ExecCtx,
+        upgraded_client: Vec<u8>,
+        upgraded_consensus: Vec<u8>,
+        proofs: Vec<u8>,
+    ) -> Result<Response, Self::Error> {
+        let client_state = self.load_client_state(ctx.deps.storage)?;
+
+        if let Some(height) = client_state.frozen_height {
+            return Err(ContractError::ClientFrozen { height });
+        }
+
+        // only the counterparty chosen fields of the upgraded client are used
+        let upgraded: proto::ClientState =
+            decode_any(&upgraded_client, proto::CLIENT_STATE_TYPE_URL)?;
+        let upgraded_consensus_state: proto::ConsensusState =
+            decode_any(&upgraded_consensus, proto::CONSENSUS_STATE_TYPE_URL)?;
+
+        let invalid_client = || ContractError::InvalidUpgradedState {
+            type_url: proto::CLIENT_STATE_TYPE_URL.to_string(),
+        };
+
+        let upgraded_height = upgraded
+            .latest_height
+            .map(|height| Height::new(height.revision_number, height.revision_height))
+            .ok_or_else(invalid_client)?;
+        let unbonding_period = upgraded
+            .unbonding_period
+            .and_then(|period| u64::try_from(period.seconds).ok())
+            .filter(|period| *period > 0)
+            .ok_or_else(invalid_client)?;
+
+        let timestamp = upgraded_consensus_state
+            .timestamp
+            .and_then(|timestamp| {
+                Timestamp {
+                    seconds: timestamp.seconds,
+                    nanos: timestamp.nanos,
+                }
+                .unix_nanos()
+            })
+            .ok_or(ContractError::InvalidTimestamp)?;
+
+        // the root is only known once the upgraded chain commits its first block
+        let consensus_state = ConsensusState {
+            timestamp,
+            root: SENTINEL_ROOT.to_vec(),
+            next_validators_hash: upgraded_consensus_state.next_validators_hash,
+        };
+
+        if upgraded_height <= client_state.latest_height {
+            return Err(ContractError::UpgradeHeightNotIncreasing {
+                latest_height: client_state.latest_height,
+                upgraded_height,
+            });
+        }
+
+        let trusted = self.load_consensus_state(ctx.deps.storage, client_state.latest_height)?;
+
+        let now = ctx.env.block.time.seconds();
+
+        if is_expired(
+            seconds(trusted.timestamp),
+            client_state.trusting_period,
+            now,
+        ) {
+            return Err(ContractError::TrustedConsensusStateExpired {
+                timestamp: seconds(trusted.timestamp),
+                trusting_period: client_state.trusting_period,
+                now,
+            });
+        }
+
+        let UpgradeProofs {
+            client,
+            consensus_state: consensus_state_proof,
+        } = from_json(proofs)?;
+
+        let height = client_state.latest_height.revision_height;
+
+        eureka_ics23_verifier::verify_membership(
+            &sdk_specs(),
+            &trusted.root,
+            &apply_prefix(UPGRADE_STORE, &upgraded_client_key(height)),
+            &client,
+            upgraded_client,
+        )?;
+
+        eureka_ics23_verifier::verify_membership(
+            &sdk_specs(),
+            &trusted.root,
+            &apply_prefix(UPGRADE_STORE, &upgraded_consensus_state_key(height)),
+            &consensus_state_proof,
+            upgraded_consensus,
+        )?;
+
+        // the trusting period keeps its ratio to the unbonding period, below one
+        let trusting_period = u128::from(client_state.trusting_period)
+            * u128::from(unbonding_period)
+            / u128::from(client_state.unbonding_period);
+
+        let client_state = ClientState {
+            chain_id: upgraded.chain_id,
+            trust_level: client_state.trust_level,
+            trusting_period: trusting_period as u64,
+            max_clock_drift: client_state.max_clock_drift,
+            unbonding_period,
+            latest_height: upgraded_height,
+            frozen_height: None,
+        };
+
+        if client_state.trusting_period == 0 {
+            return Err(ContractError::ZeroTrustingPeriod);
+        }
+
+        let mut storage = CwStorage(ctx.deps.storage);
+
+        self.consensus_states
+            .access(&mut storage)
+            .entry_mut(&client_state.latest_height.revision_number)
+            .entry_mut(&client_state.latest_height.revision_height)
+            .set(&consensus_state)?;
+        self.client_state.access(&mut storage).set(&client_state)?;
+
+        Ok(Response::default())
+    }
+
+    fn client_state(&self, ctx: QueryCtx) -> Result<interface::ClientState, Self::Error> {
+        let client_state = self.load_client_state(ctx.deps.storage)?;
+
+        Ok(interface::ClientState {
+            chain_id: client_state.chain_id.clone(),
+            latest_height: client_state.latest_height,
+            frozen_height: client_state.frozen_height,
+            data: to_json_vec(&client_state)?,
+        })
+    }
+
+    fn consensus_state(
+        &self,
+        ctx: QueryCtx,
+        height: Height,
+    ) -> Result<interface::ConsensusState, Self::Error> {
+        let consensus_state = self.load_consensus_state(ctx.deps.storage, height)?;
+
+        Ok(interface::ConsensusState {
+            timestamp: seconds(consensus_state.timestamp),
+            root: consensus_state.root.clone(),
+            data: to_json_vec(&consensus_state)?,
+        })
+    }
+
+    fn status(&self, ctx: QueryCtx) -> Result<Status, Self::Error> {
+        let client_state = self.load_client_state(ctx.deps.storage)?;
+
+        if client_state.frozen_height.is_some() {
+            return Ok(Status::Frozen);
+        }
+
+        let Ok(latest) = self.load_consensus_state(ctx.deps.storage, client_state.latest_height)
+        else {
+            return Ok(Status::Unknown);
+        };
+
+        if is_expired(
+            seconds(latest.timestamp),
+            client_state.trusting_period,
+            ctx.env.block.time.seconds(),
+        ) {
+            return Ok(Status::Expired);
+        }
+
+        Ok(Status::Active)
+    }
+
+    fn latest_height(&self, ctx: QueryCtx) -> Result<Height, Self::Error> {
+        Ok(self.load_client_state(ctx.deps.storage)?.latest_height)
+    }
+
+    fn timestamp(&self, ctx: QueryCtx, height: Height) -> Result<u64, Self::Error> {
+        Ok(seconds(
+            self.load_consensus_state(ctx.deps.storage, height)?
+                .timestamp,
+        ))
+    }
+
+    fn consensus_timestamp(
+        &self,
+        ctx: QueryCtx,
+        height: Height,
+    ) -> Result<Option<u64>, Self::Error> {
+        Ok(self
+            .find_consensus_state(ctx.deps.storage, height)?
+            .map(|consensus_state| seconds(consensus_state.timestamp)))
+    }
+
+    fn trusting_period(&self, ctx: QueryCtx) -> Result<u64, Self::Error> {
+        Ok(self.load_client_state(ctx.deps.storage)?.trusting_period)
+    }
+
+    fn client_parameters(&self, ctx: QueryCtx) -> Result<Vec<u8>, Self::Error> {
+        Ok(to_json_vec(&ClientState {
+            trusting_period: 0,
+            latest_height: Height::default(),
+            frozen_height: None,
+            ..self.load_client_state(ctx.deps.storage)?
+        })?)
+    }
+
+    // oldest heights first, the latest consensus state is always kept
+    fn prune(&self, ctx: ExecCtx) -> Result<Response, Self::Error> {
+        let client_state = self.load_client_state(ctx.deps.storage)?;
+
+        let now = ctx.env.block.time.seconds();
+
+        let mut storage = CwStorage(ctx.deps.storage);
+
+        let mut expired = vec![];
+
+        for pair in self.consensus_states.access(&storage).pairs() {
+            let ((revision_number, (revision_height, ())), consensus_state) =
+                pair.map_err(|_| StdError::generic_err("invalid consensus state"))?;
+
+            let height = Height::new(revision_number, revision_height);
+
+            if expired.len() == PRUNE_LIMIT
+                || height >= client_state.latest_height
+                || !is_expired(
+                    seconds(consensus_state.timestamp),
+                    client_state.trusting_period,
+                    now,
+                )
+            {
+                break;
+            }
+
+            expired.push(height);
+        }
+
+        for height in expired {
+            self.consensus_states
+                .access(&mut storage)
+                .entry_mut(&height.revision_number)
+                .entry_mut(&height.revision_height)
+                .remove();
+        }
+
+        Ok(Response::default())
+    }
+
+    fn check_membership(
+        &self,
+        ctx: QueryCtx,
+        key: Vec<u8>,
+        value: Vec<u8>,
+        commitment_prefix: Vec<u8>,
+        height: Height,
+        proof: Vec<u8>,
+    ) -> Result<bool, Self::Error> {
+        let consensus_state = self.load_consensus_state(ctx.deps.storage, height)?;
+
+        eureka_ics23_verifier::verify_membership(
+            &sdk_specs(),
+            &consensus_state.root,
+            &apply_prefix(&commitment_prefix, &key),
+            &proof,
+            value,
+        )?;
+
+        Ok(true)
+    }
+
+    // the proof is a `MerkleProof` whose module store proof is a batch of every key
+    fn verify_batch_membership(
+        &self,
+        ctx: QueryCtx,
+        items: Vec<(Vec<u8>, Vec<u8>)>,
+        commitment_prefix: Vec<u8>,
+        height: Height,
+        proof: Vec<u8>,
+    ) -> Result<bool, Self::Error> {
+        let consensus_state = self.load_consensus_state(ctx.deps.storage, height)?;
+
+        let items = items
+            .into_iter()
+            .map(|(key, value)| (apply_prefix(&commitment_prefix, &key), value))
+            .collect::<Vec<_>>();
+
+        eureka_ics23_verifier::verify_batch_membership(
+            &sdk_specs(),
+            &consensus_state.root,
+            &items,
+            &proof,
+        )?;
+
+        Ok(true)
+    }
+
+    fn check_non_membership(
+        &self,
+        ctx: QueryCtx,
+        key: Vec<u8>,
+        commitment_prefix: Vec<u8>,
+        height: Height,
+        proof: Vec<u8>,
+    ) -> Result<bool, Self::Error> {
+        let consensus_state = self.load_consensus_state(ctx.deps.storage, height)?;
+
+        eureka_ics23_verifier::verify_non_membership(
+            &sdk_specs(),
+            &consensus_state.root,
+            &apply_prefix(&commitment_prefix, &key),
+            &proof,
+        )?;
+
+        Ok(true)
+    }
+
+    // proofs are checked against the stored app hashes, they stay valid
+    fn consume_proofs(
+        &self,
+        _ctx: ExecCtx,
+        _height: Height,
+        _count: u64,
+    ) -> Result<Response, Self::Error> {
+        Ok(Response::default())
+    }
+}
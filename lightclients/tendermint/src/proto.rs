@@ -0,0 +1,187 @@
+// CometBFT protobuf messages hashed and signed by the counterparty validators
+
+use sha2::{Digest, Sha256};
+
+// `SignedMsgType` of the commit votes
+pub const PRECOMMIT_TYPE: i32 = 2;
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Timestamp {
+    #[prost(int64, tag = "1")]
+    pub seconds: i64,
+    #[prost(int32, tag = "2")]
+    pub nanos: i32,
+}
+
+// block and app protocol versions
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Consensus {
+    #[prost(uint64, tag = "1")]
+    pub block: u64,
+    #[prost(uint64, tag = "2")]
+    pub app: u64,
+}
+
+// also the encoding of `CanonicalPartSetHeader`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct PartSetHeader {
+    #[prost(uint32, tag = "1")]
+    pub total: u32,
+    #[prost(bytes = "vec", tag = "2")]
+    pub hash: Vec<u8>,
+}
+
+// also the encoding of `CanonicalBlockID`
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct BlockId {
+    #[prost(bytes = "vec", tag = "1")]
+    pub hash: Vec<u8>,
+    #[prost(message, optional, tag = "2")]
+    pub part_set_header: Option<PartSetHeader>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct CanonicalVote {
+    #[prost(int32, tag = "1")]
+    pub r#type: i32,
+    #[prost(sfixed64, tag = "2")]
+    pub height: i64,
+    #[prost(sfixed64, tag = "3")]
+    pub round: i64,
+    #[prost(message, optional, tag = "4")]
+    pub block_id: Option<BlockId>,
+    #[prost(message, optional, tag = "5")]
+    pub timestamp: Option<Timestamp>,
+    #[prost(string, tag = "6")]
+    pub chain_id: String,
+}
+
+// ed25519 variant of the `PublicKey` oneof
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct PublicKey {
+    #[prost(bytes = "vec", tag = "1")]
+    pub ed25519: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct SimpleValidator {
+    #[prost(message, optional, tag = "1")]
+    pub pub_key: Option<PublicKey>,
+    #[prost(int64, tag = "2")]
+    pub voting_power: i64,
+}
+
+// wrappers of the scalar header fields before hashing
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct StringValue {
+    #[prost(string, tag = "1")]
+    pub value: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Int64Value {
+    #[prost(int64, tag = "1")]
+    pub value: i64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct BytesValue {
+    #[prost(bytes = "vec", tag = "1")]
+    pub value: Vec<u8>,
+}
+
+// type urls of the upgraded states committed by ibc-go under the upgrade store
+pub const CLIENT_STATE_TYPE_URL: &str = "/ibc.lightclients.tendermint.v1.ClientState";
+pub const CONSENSUS_STATE_TYPE_URL: &str = "/ibc.lightclients.tendermint.v1.ConsensusState";
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Any {
+    #[prost(string, tag = "1")]
+    pub type_url: String,
+    #[prost(bytes = "vec", tag = "2")]
+    pub value: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Duration {
+    #[prost(int64, tag = "1")]
+    pub seconds: i64,
+    #[prost(int32, tag = "2")]
+    pub nanos: i32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Fraction {
+    #[prost(uint64, tag = "1")]
+    pub numerator: u64,
+    #[prost(uint64, tag = "2")]
+    pub denominator: u64,
+}
+
+// ibc.core.client.v1.Height
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Height {
+    #[prost(uint64, tag = "1")]
+    pub revision_number: u64,
+    #[prost(uint64, tag = "2")]
+    pub revision_height: u64,
+}
+
+// ibc.lightclients.tendermint.v1.ClientState without the proof specs, upgrade path and
+// deprecated flags, skipped on decoding
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ClientState {
+    #[prost(string, tag = "1")]
+    pub chain_id: String,
+    #[prost(message, optional, tag = "2")]
+    pub trust_level: Option<Fraction>,
+    #[prost(message, optional, tag = "3")]
+    pub trusting_period: Option<Duration>,
+    #[prost(message, optional, tag = "4")]
+    pub unbonding_period: Option<Duration>,
+    #[prost(message, optional, tag = "5")]
+    pub max_clock_drift: Option<Duration>,
+    #[prost(message, optional, tag = "6")]
+    pub frozen_height: Option<Height>,
+    #[prost(message, optional, tag = "7")]
+    pub latest_height: Option<Height>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct MerkleRoot {
+    #[prost(bytes = "vec", tag = "1")]
+    pub hash: Vec<u8>,
+}
+
+// ibc.lightclients.tendermint.v1.ConsensusState
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ConsensusState {
+    #[prost(message, optional, tag = "1")]
+    pub timestamp: Option<Timestamp>,
+    #[prost(message, optional, tag = "2")]
+    pub root: Option<MerkleRoot>,
+    #[prost(bytes = "vec", tag = "3")]
+    pub next_validators_hash: Vec<u8>,
+}
+
+// RFC 6962 root of `leaves`, splitting at the largest power of two below their count
+pub fn merkle_root(leaves: &[Vec<u8>]) -> Vec<u8> {
+    match leaves {
+        [] => Sha256::digest([]).to_vec(),
+        [leaf] => Sha256::new()
+            .chain_update([0])
+            .chain_update(leaf)
+            .finalize()
+            .to_vec(),
+        _ => {
+            let split = leaves.len().next_power_of_two() / 2;
+
+            Sha256::new()
+                .chain_update([1])
+                .chain_update(merkle_root(&leaves[..split]))
+                .chain_update(merkle_root(&leaves[split..]))
+                .finalize()
+                .to_vec()
+        }
+    }
+}
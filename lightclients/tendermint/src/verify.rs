@@ -0,0 +1,187 @@
+use std::collections::BTreeSet;
+
+use cosmwasm_std::Api;
+use eureka_lightclient_interface::is_expired;
+
+use crate::error::ContractError;
+use crate::{
+    seconds, ClientState, ConsensusState, Header, SignedHeader, ValidatorSet, NANOS_PER_SECOND,
+};
+
+// public keys of the validators with a valid commit signature
+fn verify_signatures(
+    api: &dyn Api,
+    signed_header: &SignedHeader,
+    validator_set: &ValidatorSet,
+) -> Result<BTreeSet<Vec<u8>>, ContractError> {
+    let SignedHeader { header, commit } = signed_header;
+
+    if commit.height != header.height {
+        return Err(ContractError::CommitHeightMismatch {
+            height: header.height,
+            commit_height: commit.height,
+        });
+    }
+
+    if commit.block_id.hash != header.hash() {
+        return Err(ContractError::CommitBlockIdMismatch);
+    }
+
+    if commit.signatures.len() != validator_set.validators.len() {
+        return Err(ContractError::SignatureCountMismatch {
+            validators: validator_set.validators.len(),
+            signatures: commit.signatures.len(),
+        });
+    }
+
+    let mut signers = BTreeSet::new();
+
+    for (index, (validator, signature)) in validator_set
+        .validators
+        .iter()
+        .zip(&commit.signatures)
+        .enumerate()
+    {
+        let Some(signature) = signature else {
+            continue;
+        };
+
+        // each precommit carries its own timestamp
+        let sign_bytes = commit.vote_sign_bytes(&header.chain_id, signature);
+
+        if !matches!(
+            api.ed25519_verify(&sign_bytes, &signature.signature, &validator.pub_key),
+            Ok(true)
+        ) {
+            return Err(ContractError::InvalidSignature { index });
+        }
+
+        if !signers.insert(validator.pub_key.clone()) {
+            return Err(ContractError::DuplicateValidator);
+        }
+    }
+
+    Ok(signers)
+}
+
+// voting power of `validator_set` held by `signers`, and the total voting power
+fn signed_voting_power(
+    validator_set: &ValidatorSet,
+    signers: &BTreeSet<Vec<u8>>,
+) -> Result<(u128, u128), ContractError> {
+    let mut seen = BTreeSet::new();
+    let mut signed = 0;
+    let mut total = 0;
+
+    for validator in &validator_set.validators {
+        if !seen.insert(&validator.pub_key) {
+            return Err(ContractError::DuplicateValidator);
+        }
+
+        total += u128::from(validator.voting_power);
+
+        if signers.contains(&validator.pub_key) {
+            signed += u128::from(validator.voting_power);
+        }
+    }
+
+    Ok((signed, total))
+}
+
+// skipping verification of `header` against the trusted consensus state, `now` in unix
+// nanoseconds
+pub fn verify_header(
+    api: &dyn Api,
+    client_state: &ClientState,
+    trusted: &ConsensusState,
+    header: &Header,
+    now: u64,
+) -> Result<(), ContractError> {
+    let block = &header.signed_header.header;
+
+    let time = block
+        .time
+        .unix_nanos()
+        .ok_or(ContractError::InvalidTimestamp)?;
+
+    if block.chain_id != client_state.chain_id {
+        return Err(ContractError::ChainIdMismatch {
+            expected: client_state.chain_id.clone(),
+            got: block.chain_id.clone(),
+        });
+    }
+
+    if is_expired(
+        seconds(trusted.timestamp),
+        client_state.trusting_period,
+        seconds(now),
+    ) {
+        return Err(ContractError::TrustedConsensusStateExpired {
+            timestamp: seconds(trusted.timestamp),
+            trusting_period: client_state.trusting_period,
+            now: seconds(now),
+        });
+    }
+
+    if block.height <= header.trusted_height.revision_height {
+        return Err(ContractError::NonIncreasingHeight {
+            trusted: header.trusted_height.revision_height,
+            height: block.height,
+        });
+    }
+
+    if time <= trusted.timestamp {
+        return Err(ContractError::NonIncreasingTime {
+            trusted: trusted.timestamp,
+            time,
+        });
+    }
+
+    if time
+        >= now.saturating_add(
+            client_state
+                .max_clock_drift
+                .saturating_mul(NANOS_PER_SECOND),
+        )
+    {
+        return Err(ContractError::HeaderFromFuture {
+            time,
+            now,
+            max_clock_drift: client_state.max_clock_drift,
+        });
+    }
+
+    if header.validator_set.hash() != block.validators_hash {
+        return Err(ContractError::ValidatorSetHashMismatch);
+    }
+
+    let signers = verify_signatures(api, &header.signed_header, &header.validator_set)?;
+
+    if block.height == header.trusted_height.revision_height + 1 {
+        // adjacent header, the validator set was committed by the trusted header
+        if block.validators_hash != trusted.next_validators_hash {
+            return Err(ContractError::NextValidatorSetHashMismatch);
+        }
+    } else {
+        // non-adjacent header, enough of the trusted validators must have signed it
+        if header.trusted_validators.hash() != trusted.next_validators_hash {
+            return Err(ContractError::NextValidatorSetHashMismatch);
+        }
+
+        let (signed, total) = signed_voting_power(&header.trusted_validators, &signers)?;
+
+        if signed * u128::from(client_state.trust_level.denominator)
+            <= total * u128::from(client_state.trust_level.numerator)
+        {
+            return Err(ContractError::InsufficientTrustedVotingPower { signed, total });
+        }
+    }
+
+    let (signed, total) = signed_voting_power(&header.validator_set, &signers)?;
+
+    if signed * 3 <= total * 2 {
+        return Err(ContractError::InsufficientVotingPower { signed, total });
+    }
+
+    Ok(())
+}
@@ -0,0 +1,47 @@
+// packet commitments, receipts and acknowledgements are written to raw contract storage
+// under these paths, so that the counterparty light client can prove them
+// with the `wasm_commitment_prefix` of this contract as commitment prefix
+
+use cosmwasm_std::{to_json_vec, Addr, HexBinary, StdResult};
+use sha2::{Digest, Sha256};
+
+use crate::{Acknowledgement, Packet};
+
+// value stored at a receipt path
+pub const RECEIPT: &[u8] = &[1];
+
+// light clients are identified by address, which is kept across counterparty upgrades
+// addresses and commitment prefixes are hex encoded, the key is part of the proven paths
+pub fn connection_key(
+    lightclient_source: &(Addr, Vec<u8>),
+    lightclient_destination: &(Addr, Vec<u8>),
+) -> String {
+    [
+        lightclient_source.0.as_bytes(),
+        &lightclient_source.1,
+        lightclient_destination.0.as_bytes(),
+        &lightclient_destination.1,
+    ]
+    .map(|part| HexBinary::from(part).to_hex())
+    .join("-")
+}
+
+pub fn packet_commitment_path(connection: &str, nonce: u64) -> Vec<u8> {
+    format!("commitments/{connection}/{nonce}").into_bytes()
+}
+
+pub fn packet_receipt_path(connection: &str, nonce: u64) -> Vec<u8> {
+    format!("receipts/{connection}/{nonce}").into_bytes()
+}
+
+pub fn packet_acknowledgement_path(connection: &str, nonce: u64) -> Vec<u8> {
+    format!("acks/{connection}/{nonce}").into_bytes()
+}
+
+pub fn packet_commitment(packet: &Packet) -> StdResult<Vec<u8>> {
+    Ok(Sha256::digest(to_json_vec(packet)?).to_vec())
+}
+
+pub fn acknowledgement_commitment(acknowledgement: &Acknowledgement) -> StdResult<Vec<u8>> {
+    Ok(Sha256::digest(to_json_vec(acknowledgement)?).to_vec())
+}
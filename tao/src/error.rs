@@ -0,0 +1,101 @@
+use cosmwasm_std::{Addr, StdError, Uint128};
+use eureka_lightclient_interface::{Height, Status};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("unauthorized")]
+    Unauthorized,
+
+    #[error("unknown reply id: {0}")]
+    UnknownReplyId(u64),
+
+    #[error("application is not registered for connection: {application}")]
+    ApplicationNotRegistered { application: Addr },
+
+    #[error(
+        "sender is not allowed to use application: sender: {sender}, application: {application}"
+    )]
+    SenderNotAllowed { sender: Addr, application: Addr },
+
+    #[error("funds mismatch: required: {required}{denom}, sent: {sent}{denom}")]
+    FundsMismatch {
+        denom: String,
+        required: Uint128,
+        sent: Uint128,
+    },
+
+    #[error("nonce mismatch: expected: {expected}, got: {got}")]
+    NonceMismatch { expected: u64, got: u64 },
+
+    #[error("timeout is not in future: current time: {current}, timeout: {timeout}")]
+    TimeoutNotInFuture { current: u64, timeout: u64 },
+
+    #[error("timeout is in future for proof height: current time: {current}, timeout: {timeout}")]
+    TimeoutNotElapsed { current: u64, timeout: u64 },
+
+    #[error("packet not sent")]
+    PacketNotSent,
+
+    #[error("packet already received")]
+    PacketAlreadyReceived,
+
+    #[error("packet already timed out")]
+    PacketAlreadyTimedOut,
+
+    #[error("packet already acknowledged")]
+    PacketAlreadyAcknowledged,
+
+    #[error("acknowledgement not found")]
+    AcknowledgementNotFound,
+
+    #[error("acknowledgement results mismatch: payloads: {payloads}, results: {results}")]
+    AcknowledgementMismatch { payloads: usize, results: usize },
+
+    #[error("light client is not active: {lightclient}, status: {status:?}")]
+    LightClientNotActive { lightclient: Addr, status: Status },
+
+    #[error("proof height is above light client latest height: height: {height}, latest height: {latest_height}")]
+    ProofHeightTooHigh {
+        height: Height,
+        latest_height: Height,
+    },
+
+    #[error("consensus state not found at height: {height}")]
+    ConsensusStateNotFound { height: Height },
+
+    #[error("light client code is not approved: {code_id}")]
+    LightClientCodeNotApproved { code_id: u64 },
+
+    #[error("light client was not created by tao: {lightclient}")]
+    LightClientNotRegistered { lightclient: Addr },
+
+    #[error("light client is neither frozen nor expired: {lightclient}, status: {status:?}")]
+    LightClientNotSubstitutable { lightclient: Addr, status: Status },
+
+    #[error(
+        "substitute is not the same light client type: {lightclient}, substitute: {substitute}"
+    )]
+    LightClientTypeMismatch { lightclient: Addr, substitute: Addr },
+
+    #[error("substitute does not track the same chain with the same parameters: {lightclient}, substitute: {substitute}")]
+    LightClientParametersMismatch { lightclient: Addr, substitute: Addr },
+
+    #[error("substitute height must be ahead in the same revision: light client: {lightclient}, substitute: {substitute}")]
+    SubstituteHeightMismatch {
+        lightclient: Height,
+        substitute: Height,
+    },
+
+    #[error("packet source light client mismatch: expected: {expected}, got: {got}")]
+    SourceLightClientMismatch { expected: Addr, got: Addr },
+
+    #[error("commitment prefix is not the one registered with the light client")]
+    CommitmentPrefixMismatch,
+
+    #[error("light client failed to verify the proof")]
+    VerificationFailed,
+}
@@ -1,16 +1,32 @@
-use std::collections::HashMap;
+pub mod commitment;
+pub mod error;
+
+use std::collections::{BTreeMap, HashSet};
 
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Coin, Uint128};
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, BankMsg, Binary, Coin, Coins, Deps, DepsMut, Env,
+    QuerierWrapper, Reply, Storage, SubMsg, Uint128, WasmMsg,
+};
 use cw_storey::containers::{Item, Map};
 use cw_storey::CwStorage;
 use eureka_application_interface::sv::Executor;
-use eureka_application_interface::Application;
-use eureka_lightclient_interface::sv::Querier;
-use eureka_lightclient_interface::{LightClient, Status};
+use eureka_application_interface::{Application, PayloadResult};
+use eureka_lightclient_interface::sv::{Executor as LightClientExecutor, Querier};
+use eureka_lightclient_interface::{Height, LightClient, Status};
 use sylvia::contract;
 use sylvia::cw_std::{Response, StdError, StdResult};
-use sylvia::types::{ExecCtx, InstantiateCtx, Remote};
+use sylvia::types::{ExecCtx, InstantiateCtx, QueryCtx, Remote, ReplyCtx};
+
+use crate::commitment::{
+    acknowledgement_commitment, connection_key, packet_acknowledgement_path, packet_commitment,
+    packet_commitment_path, packet_receipt_path, RECEIPT,
+};
+pub use crate::error::ContractError;
+
+const REPLY_RECEIVE_PAYLOAD_ID: u64 = 0;
+const REPLY_CREATE_LIGHTCLIENT_ID: u64 = 1;
+const REPLY_UPDATE_CLIENT_ID: u64 = 2;
 
 #[cw_serde]
 pub struct PacketHeader {
@@ -31,6 +47,8 @@ pub struct PayloadHeader {
     pub application_source: Addr,
     pub application_destination: Addr,
     pub funds: Vec<Coin>,
+    // gas limit for the destination application callback
+    pub gas_limit: Option<u64>,
 }
 
 #[cw_serde]
@@ -39,15 +57,501 @@ pub struct Payload {
     pub data: Vec<u8>,
 }
 
+#[cw_serde]
+pub enum SenderPolicy {
+    // any sender, the application decides
+    Any,
+    // only the application itself or one of the listed senders
+    AllowList(Vec<Addr>),
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct PacketFee {
+    // paid to the relayer that delivered the packet to the destination
+    pub receive_fee: Vec<Coin>,
+    // paid to the relayer that relays the acknowledgement back to the source
+    pub ack_fee: Vec<Coin>,
+    // paid to the relayer that relays the timeout back to the source
+    pub timeout_fee: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct EscrowedFee {
+    pub payer: Addr,
+    pub fee: PacketFee,
+}
+
+#[cw_serde]
+pub struct Acknowledgement {
+    // source chain payee registered by the relayer that submitted the packet at the
+    // destination, the relayer itself when it did not register one
+    pub relayer: Addr,
+    // result of each payload, in the packet order
+    pub results: Vec<PayloadResult>,
+}
+
+// light client created by the tao factory
+#[cw_serde]
+pub struct LightClientInfo {
+    pub creator: Addr,
+    pub code_id: u64,
+    pub client_type: String,
+    // where the counterparty commits packets, e.g. the `wasm_commitment_prefix` of its tao
+    // packets proven with the light client must name it
+    pub commitment_prefix: Vec<u8>,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct Config {
+    // allow timeouts to be proven against expired, but not frozen, light clients
+    pub allow_timeout_on_expired: bool,
+}
+
 pub struct Contract {
     pub sent_nonce: Map<String, Item<u64>>,
     pub sent_packet: Map<String, Map<u64, Item<Packet>>>,
     pub received_packet: Map<String, Map<u64, Item<()>>>,
     pub timeout_packet: Map<String, Map<u64, Item<()>>>,
+    pub packet_acknowledgement: Map<String, Map<u64, Item<Acknowledgement>>>,
+    pub acknowledged_packet: Map<String, Map<u64, Item<()>>>,
+    pub packet_fee: Map<String, Map<u64, Item<EscrowedFee>>>,
+    pub registered_application: Map<String, Map<String, Item<SenderPolicy>>>,
+    pub governance: Item<Addr>,
+    pub config: Item<Config>,
+    // light client ID -> contract substituted by governance
+    pub substituted_lightclient: Map<String, Item<Addr>>,
+    // code ID -> client type, approved by governance
+    pub approved_lightclient_code: Map<u64, Item<String>>,
+    // light clients created by the factory, the only ones packets can name
+    pub lightclient_info: Map<String, Item<LightClientInfo>>,
+    // connection -> relayer -> source chain address paid the receive fees of its packets
+    pub counterparty_payee: Map<String, Map<String, Item<Addr>>>,
+}
+
+// bank transfer of the non-zero `coins`, if any
+fn transfer(recipient: &Addr, coins: impl IntoIterator<Item = Coin>) -> StdResult<Option<BankMsg>> {
+    let mut amount = Coins::default();
+
+    for coin in coins {
+        amount.add(coin)?;
+    }
+
+    if amount.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(BankMsg::Send {
+        to_address: recipient.to_string(),
+        amount: amount.into_vec(),
+    }))
+}
+
+// only the application itself or its admin can claim the application
+fn ensure_application_owner(
+    querier: &QuerierWrapper,
+    sender: &Addr,
+    application: &Addr,
+) -> Result<(), ContractError> {
+    if sender == application
+        || Some(sender)
+            == querier
+                .query_wasm_contract_info(application)?
+                .admin
+                .as_ref()
+    {
+        Ok(())
+    } else {
+        Err(ContractError::Unauthorized)
+    }
+}
+
+// light client status must be one of `allowed`
+fn ensure_lightclient_status(
+    querier: &QuerierWrapper,
+    lightclient: &Addr,
+    allowed: &[Status],
+) -> Result<(), ContractError> {
+    let status = Remote::<'_, dyn LightClient<Error = StdError>>::new(lightclient.clone())
+        .querier(querier)
+        .status()?;
+
+    if allowed.contains(&status) {
+        Ok(())
+    } else {
+        Err(ContractError::LightClientNotActive {
+            lightclient: lightclient.clone(),
+            status,
+        })
+    }
+}
+
+// light clients may report a failed verification as `false` rather than as an error
+fn ensure_verified(verified: bool) -> Result<(), ContractError> {
+    if verified {
+        Ok(())
+    } else {
+        Err(ContractError::VerificationFailed)
+    }
+}
+
+// tells the light client that `count` of its proofs at `height` were accepted
+fn consume_proofs(lightclient: &Addr, height: Height, count: u64) -> StdResult<WasmMsg> {
+    Ok(
+        Remote::<'_, dyn LightClient<Error = StdError>>::new(lightclient.clone())
+            .executor()
+            .consume_proofs(height, count)?
+            .build(),
+    )
+}
+
+// timestamp of the consensus state at proof height, which must be tracked by the light client
+fn consensus_timestamp(
+    querier: &QuerierWrapper,
+    lightclient: &Addr,
+    height: Height,
+) -> Result<u64, ContractError> {
+    let lightclient = Remote::<'_, dyn LightClient<Error = StdError>>::new(lightclient.clone());
+
+    let latest_height = lightclient.querier(querier).latest_height()?;
+
+    if height > latest_height {
+        return Err(ContractError::ProofHeightTooHigh {
+            height,
+            latest_height,
+        });
+    }
+
+    lightclient
+        .querier(querier)
+        .consensus_timestamp(height)?
+        .ok_or(ContractError::ConsensusStateNotFound { height })
+}
+
+impl Contract {
+    fn load_lightclient_info(
+        &self,
+        storage: &dyn Storage,
+        lightclient: &Addr,
+    ) -> Result<LightClientInfo, ContractError> {
+        self.lightclient_info
+            .access(&CwStorage(storage))
+            .entry(lightclient.as_str())
+            .get()?
+            .ok_or_else(|| ContractError::LightClientNotRegistered {
+                lightclient: lightclient.clone(),
+            })
+    }
+
+    // contract behind a light client ID, the ID itself unless substituted by governance
+    // only light clients created by the factory are resolved
+    fn resolve_lightclient(
+        &self,
+        storage: &dyn Storage,
+        lightclient: &Addr,
+    ) -> Result<Addr, ContractError> {
+        self.load_lightclient_info(storage, lightclient)?;
+
+        Ok(self
+            .substituted_lightclient
+            .access(&CwStorage(storage))
+            .entry(lightclient.as_str())
+            .get()?
+            .unwrap_or_else(|| lightclient.clone()))
+    }
+
+    // same as `resolve_lightclient`, for a light client named with a commitment prefix
+    // a proof under any other prefix could be of another contract than the counterparty tao
+    fn resolve_prefixed_lightclient(
+        &self,
+        storage: &dyn Storage,
+        (lightclient, commitment_prefix): &(Addr, Vec<u8>),
+    ) -> Result<Addr, ContractError> {
+        if self
+            .load_lightclient_info(storage, lightclient)?
+            .commitment_prefix
+            != *commitment_prefix
+        {
+            return Err(ContractError::CommitmentPrefixMismatch);
+        }
+
+        self.resolve_lightclient(storage, lightclient)
+    }
+
+    // `packet` must not be timed out nor received yet
+    fn ensure_receivable(
+        &self,
+        storage: &dyn Storage,
+        env: &Env,
+        packet: &Packet,
+    ) -> Result<(), ContractError> {
+        let PacketHeader {
+            lightclient_source,
+            lightclient_destination,
+            nonce,
+            timeout,
+        } = &packet.header;
+
+        if timeout <= &env.block.time.seconds() {
+            return Err(ContractError::TimeoutNotInFuture {
+                current: env.block.time.seconds(),
+                timeout: *timeout,
+            });
+        }
+
+        let connection_str = connection_key(lightclient_source, lightclient_destination);
+
+        if self
+            .received_packet
+            .access(&CwStorage(storage))
+            .entry(&connection_str)
+            .entry(nonce)
+            .get()?
+            .is_some()
+        {
+            return Err(ContractError::PacketAlreadyReceived);
+        }
+
+        Ok(())
+    }
+
+    // active source light client, tracking a consensus state at proof height
+    fn source_lightclient(
+        &self,
+        deps: Deps,
+        lightclient_source: &(Addr, Vec<u8>),
+        height: Height,
+    ) -> Result<Addr, ContractError> {
+        let lightclient = self.resolve_prefixed_lightclient(deps.storage, lightclient_source)?;
+
+        ensure_lightclient_status(&deps.querier, &lightclient, &[Status::Active])?;
+
+        consensus_timestamp(&deps.querier, &lightclient, height)?;
+
+        Ok(lightclient)
+    }
+
+    // receives `packet` on behalf of `relayer`
+    fn receive(
+        &self,
+        deps: DepsMut,
+        env: &Env,
+        relayer: &Addr,
+        packet: Packet,
+        height: Height,
+        proof: Vec<u8>,
+    ) -> Result<Response, ContractError> {
+        let PacketHeader {
+            lightclient_source,
+            lightclient_destination,
+            nonce,
+            ..
+        } = &packet.header;
+
+        self.ensure_receivable(deps.storage, env, &packet)?;
+
+        let lightclient = self.source_lightclient(deps.as_ref(), lightclient_source, height)?;
+
+        // validate commitment proof
+        ensure_verified(
+            Remote::<'_, dyn LightClient<Error = StdError>>::new(lightclient.clone())
+                .querier(&deps.querier)
+                .check_membership(
+                    packet_commitment_path(
+                        &connection_key(lightclient_source, lightclient_destination),
+                        *nonce,
+                    ),
+                    packet_commitment(&packet)?,
+                    lightclient_source.1.clone(),
+                    height,
+                    proof,
+                )?,
+        )?;
+
+        let consume_msg = consume_proofs(&lightclient, height, 1)?;
+
+        let delivered = self.deliver(deps, relayer, packet)?;
+
+        Ok(Response::new()
+            .add_message(consume_msg)
+            .add_submessages(delivered.messages))
+    }
+
+    // receives `packets` of the same source light client on behalf of `relayer`
+    // their commitments are proven at `height` by a single batch proof
+    fn receive_batch(
+        &self,
+        mut deps: DepsMut,
+        env: &Env,
+        relayer: &Addr,
+        packets: Vec<Packet>,
+        height: Height,
+        proof: Vec<u8>,
+    ) -> Result<Response, ContractError> {
+        let Some(first) = packets.first() else {
+            return Ok(Response::new());
+        };
+
+        let lightclient_source = first.header.lightclient_source.clone();
+
+        let mut items = vec![];
+        let mut received = HashSet::new();
+
+        for packet in &packets {
+            let PacketHeader {
+                lightclient_source: (source, commitment_prefix),
+                lightclient_destination,
+                nonce,
+                ..
+            } = &packet.header;
+
+            if *source != lightclient_source.0 {
+                return Err(ContractError::SourceLightClientMismatch {
+                    expected: lightclient_source.0,
+                    got: source.clone(),
+                });
+            }
+
+            if *commitment_prefix != lightclient_source.1 {
+                return Err(ContractError::CommitmentPrefixMismatch);
+            }
+
+            self.ensure_receivable(deps.storage, env, packet)?;
+
+            let connection_str = connection_key(&lightclient_source, lightclient_destination);
+
+            // the same packet could be delivered twice otherwise
+            if !received.insert((connection_str.clone(), *nonce)) {
+                return Err(ContractError::PacketAlreadyReceived);
+            }
+
+            items.push((
+                packet_commitment_path(&connection_str, *nonce),
+                packet_commitment(packet)?,
+            ));
+        }
+
+        let lightclient = self.source_lightclient(deps.as_ref(), &lightclient_source, height)?;
+
+        // validate commitment proofs
+        let count = items.len() as u64;
+
+        ensure_verified(
+            Remote::<'_, dyn LightClient<Error = StdError>>::new(lightclient.clone())
+                .querier(&deps.querier)
+                .verify_batch_membership(items, lightclient_source.1.clone(), height, proof)?,
+        )?;
+
+        let mut response =
+            Response::new().add_message(consume_proofs(&lightclient, height, count)?);
+
+        for packet in packets {
+            let delivered = self.deliver(deps.branch(), relayer, packet)?;
+            response = response.add_submessages(delivered.messages);
+        }
+
+        Ok(response)
+    }
+
+    // delivers the payloads of `packet`, whose commitment is proven, on behalf of `relayer`
+    fn deliver(
+        &self,
+        deps: DepsMut,
+        relayer: &Addr,
+        packet: Packet,
+    ) -> Result<Response, ContractError> {
+        let Packet {
+            header:
+                PacketHeader {
+                    lightclient_source,
+                    lightclient_destination,
+                    nonce,
+                    ..
+                },
+            payloads,
+        } = &packet;
+
+        let mut storage = CwStorage(deps.storage);
+
+        let connection_str = connection_key(lightclient_source, lightclient_destination);
+
+        let mut msgs = vec![];
+
+        for (index, payload) in payloads.iter().enumerate() {
+            let PayloadHeader {
+                application_source,
+                application_destination,
+                funds,
+                gas_limit,
+            } = &payload.header;
+
+            let msg = Remote::<'_, dyn Application<Error = StdError>>::new(
+                application_destination.clone(),
+            )
+            .executor()
+            .receive(
+                lightclient_destination.clone(),
+                lightclient_source.clone(),
+                application_source.clone(),
+                payload.data.clone(),
+                relayer.clone(),
+                funds.clone(),
+            )?
+            .build();
+
+            // a failing application only fails its own payload
+            let msg = SubMsg::reply_on_error(msg, REPLY_RECEIVE_PAYLOAD_ID)
+                .with_payload(to_json_binary(&(&connection_str, nonce, index))?);
+
+            msgs.push(match gas_limit {
+                Some(gas_limit) => msg.with_gas_limit(*gas_limit),
+                None => msg,
+            });
+        }
+
+        self.received_packet
+            .access(&mut storage)
+            .entry_mut(&connection_str)
+            .entry_mut(nonce)
+            .set(&())?;
+
+        storage
+            .0
+            .set(&packet_receipt_path(&connection_str, *nonce), RECEIPT);
+
+        // the relayer is paid on the source chain, at the address it registered there if any
+        let payee = self
+            .counterparty_payee
+            .access(&storage)
+            .entry(&connection_str)
+            .entry(relayer.as_str())
+            .get()?;
+
+        // payloads are successful unless their submessage replies with an error
+        let acknowledgement = Acknowledgement {
+            relayer: payee.unwrap_or_else(|| relayer.clone()),
+            results: vec![PayloadResult::Success; payloads.len()],
+        };
+
+        self.packet_acknowledgement
+            .access(&mut storage)
+            .entry_mut(&connection_str)
+            .entry_mut(nonce)
+            .set(&acknowledgement)?;
+
+        storage.0.set(
+            &packet_acknowledgement_path(&connection_str, *nonce),
+            &acknowledgement_commitment(&acknowledgement)?,
+        );
+
+        Ok(Response::new().add_submessages(msgs))
+    }
 }
 
 #[cfg_attr(not(feature = "library"), sylvia::entry_points)]
 #[contract]
+#[sv::error(ContractError)]
 impl Contract {
     pub const fn new() -> Self {
         Self {
@@ -55,16 +559,410 @@ impl Contract {
             sent_packet: Map::new(b'B'),
             timeout_packet: Map::new(b'C'),
             received_packet: Map::new(b'D'),
+            packet_acknowledgement: Map::new(b'E'),
+            acknowledged_packet: Map::new(b'F'),
+            packet_fee: Map::new(b'G'),
+            registered_application: Map::new(b'H'),
+            governance: Item::new(b'I'),
+            config: Item::new(b'J'),
+            substituted_lightclient: Map::new(b'K'),
+            approved_lightclient_code: Map::new(b'L'),
+            lightclient_info: Map::new(b'M'),
+            counterparty_payee: Map::new(b'N'),
         }
     }
 
     #[sv::msg(instantiate)]
-    fn instantiate(&self, _ctx: InstantiateCtx) -> StdResult<Response> {
+    fn instantiate(&self, ctx: InstantiateCtx) -> Result<Response, ContractError> {
+        let mut storage = CwStorage(ctx.deps.storage);
+
+        self.governance.access(&mut storage).set(&ctx.info.sender)?;
+        self.config.access(&mut storage).set(&Config::default())?;
+
+        Ok(Response::default())
+    }
+
+    #[sv::msg(query)]
+    fn config(&self, ctx: QueryCtx) -> Result<Config, ContractError> {
+        let storage = CwStorage(ctx.deps.storage);
+
+        Ok(self.config.access(&storage).get()?.unwrap_or_default())
+    }
+
+    #[sv::msg(exec)]
+    fn update_config(&self, ctx: ExecCtx, config: Config) -> Result<Response, ContractError> {
+        let mut storage = CwStorage(ctx.deps.storage);
+
+        if self.governance.access(&storage).get()?.as_ref() != Some(&ctx.info.sender) {
+            return Err(ContractError::Unauthorized);
+        }
+
+        self.config.access(&mut storage).set(&config)?;
+
+        Ok(Response::default())
+    }
+
+    // contract currently behind the light client ID
+    #[sv::msg(query)]
+    fn resolved_lightclient(
+        &self,
+        ctx: QueryCtx,
+        lightclient: Addr,
+    ) -> Result<Addr, ContractError> {
+        self.resolve_lightclient(ctx.deps.storage, &lightclient)
+    }
+
+    // replaces a frozen or expired light client by a healthy one of the same type
+    // the ID is kept, and with it the nonces, commitments and receipts of its connections
+    #[sv::msg(exec)]
+    fn substitute_lightclient(
+        &self,
+        ctx: ExecCtx,
+        lightclient: Addr,
+        substitute: Addr,
+    ) -> Result<Response, ContractError> {
+        let mut storage = CwStorage(ctx.deps.storage);
+
+        if self.governance.access(&storage).get()?.as_ref() != Some(&ctx.info.sender) {
+            return Err(ContractError::Unauthorized);
+        }
+
+        let subject = self.resolve_lightclient(storage.0, &lightclient)?;
+
+        let subject_client = Remote::<'_, dyn LightClient<Error = StdError>>::new(subject.clone());
+        let substitute_client =
+            Remote::<'_, dyn LightClient<Error = StdError>>::new(substitute.clone());
+
+        let status = subject_client.querier(&ctx.deps.querier).status()?;
+
+        if !matches!(status, Status::Frozen | Status::Expired) {
+            return Err(ContractError::LightClientNotSubstitutable {
+                lightclient: subject,
+                status,
+            });
+        }
+
+        ensure_lightclient_status(&ctx.deps.querier, &substitute, &[Status::Active])?;
+
+        if self.load_lightclient_info(storage.0, &subject)?.client_type
+            != self
+                .load_lightclient_info(storage.0, &substitute)?
+                .client_type
+        {
+            return Err(ContractError::LightClientTypeMismatch {
+                lightclient: subject,
+                substitute,
+            });
+        }
+
+        // same counterparty chain and parameters as ibc-go `IsMatchingClientState`
+        let parameters = |lightclient: &Remote<'_, dyn LightClient<Error = StdError>>| {
+            let querier = lightclient.querier(&ctx.deps.querier);
+            StdResult::Ok((
+                querier.client_state()?.chain_id,
+                querier.client_parameters()?,
+            ))
+        };
+
+        if parameters(&subject_client)? != parameters(&substitute_client)? {
+            return Err(ContractError::LightClientParametersMismatch {
+                lightclient: subject,
+                substitute,
+            });
+        }
+
+        // same counterparty revision, ahead of the replaced light client
+        let subject_height = subject_client.querier(&ctx.deps.querier).latest_height()?;
+        let substitute_height = substitute_client
+            .querier(&ctx.deps.querier)
+            .latest_height()?;
+
+        if substitute_height.revision_number != subject_height.revision_number
+            || substitute_height <= subject_height
+        {
+            return Err(ContractError::SubstituteHeightMismatch {
+                lightclient: subject_height,
+                substitute: substitute_height,
+            });
+        }
+
+        self.substituted_lightclient
+            .access(&mut storage)
+            .entry_mut(lightclient.as_str())
+            .set(&substitute)?;
+
+        Ok(Response::default())
+    }
+
+    #[sv::msg(query)]
+    fn approved_lightclient_code(
+        &self,
+        ctx: QueryCtx,
+        code_id: u64,
+    ) -> Result<Option<String>, ContractError> {
+        let storage = CwStorage(ctx.deps.storage);
+
+        Ok(self
+            .approved_lightclient_code
+            .access(&storage)
+            .entry(&code_id)
+            .get()?)
+    }
+
+    #[sv::msg(exec)]
+    fn approve_lightclient_code(
+        &self,
+        ctx: ExecCtx,
+        code_id: u64,
+        client_type: String,
+    ) -> Result<Response, ContractError> {
+        let mut storage = CwStorage(ctx.deps.storage);
+
+        if self.governance.access(&storage).get()?.as_ref() != Some(&ctx.info.sender) {
+            return Err(ContractError::Unauthorized);
+        }
+
+        self.approved_lightclient_code
+            .access(&mut storage)
+            .entry_mut(&code_id)
+            .set(&client_type)?;
+
+        Ok(Response::default())
+    }
+
+    // light clients already created from the code are kept
+    #[sv::msg(exec)]
+    fn revoke_lightclient_code(
+        &self,
+        ctx: ExecCtx,
+        code_id: u64,
+    ) -> Result<Response, ContractError> {
+        let mut storage = CwStorage(ctx.deps.storage);
+
+        if self.governance.access(&storage).get()?.as_ref() != Some(&ctx.info.sender) {
+            return Err(ContractError::Unauthorized);
+        }
+
+        self.approved_lightclient_code
+            .access(&mut storage)
+            .entry_mut(&code_id)
+            .remove();
+
+        Ok(Response::default())
+    }
+
+    #[sv::msg(query)]
+    fn lightclient_info(
+        &self,
+        ctx: QueryCtx,
+        lightclient: Addr,
+    ) -> Result<Option<LightClientInfo>, ContractError> {
+        let storage = CwStorage(ctx.deps.storage);
+
+        Ok(self
+            .lightclient_info
+            .access(&storage)
+            .entry(lightclient.as_str())
+            .get()?)
+    }
+
+    // instantiates a light client from an approved code ID, with tao as its admin
+    // so that the creator cannot migrate it to another code
+    // `commitment_prefix` is the counterparty one, the only prefix packets may name with it
+    #[sv::msg(exec)]
+    fn create_lightclient(
+        &self,
+        ctx: ExecCtx,
+        code_id: u64,
+        msg: Binary,
+        label: String,
+        commitment_prefix: Vec<u8>,
+    ) -> Result<Response, ContractError> {
+        let client_type = self
+            .approved_lightclient_code
+            .access(&CwStorage(ctx.deps.storage))
+            .entry(&code_id)
+            .get()?
+            .ok_or(ContractError::LightClientCodeNotApproved { code_id })?;
+
+        let msg = WasmMsg::Instantiate {
+            admin: Some(ctx.env.contract.address.to_string()),
+            code_id,
+            msg,
+            funds: ctx.info.funds,
+            label,
+        };
+
+        let info = LightClientInfo {
+            creator: ctx.info.sender,
+            code_id,
+            client_type,
+            commitment_prefix,
+        };
+
+        Ok(Response::new().add_submessage(
+            SubMsg::reply_on_success(msg, REPLY_CREATE_LIGHTCLIENT_ID)
+                .with_payload(to_json_binary(&info)?),
+        ))
+    }
+
+    #[sv::msg(query)]
+    fn registered_application(
+        &self,
+        ctx: QueryCtx,
+        application: Addr,
+        lightclient_source: (Addr, Vec<u8>),
+        lightclient_destination: (Addr, Vec<u8>),
+    ) -> Result<Option<SenderPolicy>, ContractError> {
+        let mut storage = CwStorage(ctx.deps.storage);
+
+        let connection_str = connection_key(&lightclient_source, &lightclient_destination);
+
+        Ok(self
+            .registered_application
+            .access(&mut storage)
+            .entry(&connection_str)
+            .entry(application.as_str())
+            .get()?)
+    }
+
+    #[sv::msg(exec)]
+    fn register_application(
+        &self,
+        ctx: ExecCtx,
+        application: Addr,
+        lightclient_source: (Addr, Vec<u8>),
+        lightclient_destination: (Addr, Vec<u8>),
+        sender_policy: SenderPolicy,
+    ) -> Result<Response, ContractError> {
+        ensure_application_owner(&ctx.deps.querier, &ctx.info.sender, &application)?;
+
+        let mut storage = CwStorage(ctx.deps.storage);
+
+        let connection_str = connection_key(&lightclient_source, &lightclient_destination);
+
+        self.registered_application
+            .access(&mut storage)
+            .entry_mut(&connection_str)
+            .entry_mut(application.as_str())
+            .set(&sender_policy)?;
+
         Ok(Response::default())
     }
 
     #[sv::msg(exec)]
-    fn send_packet(&self, ctx: ExecCtx, packet: Packet) -> StdResult<Response> {
+    fn unregister_application(
+        &self,
+        ctx: ExecCtx,
+        application: Addr,
+        lightclient_source: (Addr, Vec<u8>),
+        lightclient_destination: (Addr, Vec<u8>),
+    ) -> Result<Response, ContractError> {
+        ensure_application_owner(&ctx.deps.querier, &ctx.info.sender, &application)?;
+
+        let mut storage = CwStorage(ctx.deps.storage);
+
+        let connection_str = connection_key(&lightclient_source, &lightclient_destination);
+
+        self.registered_application
+            .access(&mut storage)
+            .entry_mut(&connection_str)
+            .entry_mut(application.as_str())
+            .remove();
+
+        Ok(Response::default())
+    }
+
+    #[sv::msg(query)]
+    fn counterparty_payee(
+        &self,
+        ctx: QueryCtx,
+        lightclient_source: (Addr, Vec<u8>),
+        lightclient_destination: (Addr, Vec<u8>),
+        relayer: Addr,
+    ) -> Result<Option<Addr>, ContractError> {
+        let storage = CwStorage(ctx.deps.storage);
+
+        let connection_str = connection_key(&lightclient_source, &lightclient_destination);
+
+        Ok(self
+            .counterparty_payee
+            .access(&storage)
+            .entry(&connection_str)
+            .entry(relayer.as_str())
+            .get()?)
+    }
+
+    // registers the source chain address paid the receive fees of the packets the sender
+    // relays on the connection, as the ICS-29 counterparty payee
+    #[sv::msg(exec)]
+    fn register_counterparty_payee(
+        &self,
+        ctx: ExecCtx,
+        lightclient_source: (Addr, Vec<u8>),
+        lightclient_destination: (Addr, Vec<u8>),
+        counterparty_payee: Addr,
+    ) -> Result<Response, ContractError> {
+        let mut storage = CwStorage(ctx.deps.storage);
+
+        let connection_str = connection_key(&lightclient_source, &lightclient_destination);
+
+        self.counterparty_payee
+            .access(&mut storage)
+            .entry_mut(&connection_str)
+            .entry_mut(ctx.info.sender.as_str())
+            .set(&counterparty_payee)?;
+
+        Ok(Response::default())
+    }
+
+    #[sv::msg(query)]
+    fn packet_fee(
+        &self,
+        ctx: QueryCtx,
+        lightclient_source: (Addr, Vec<u8>),
+        lightclient_destination: (Addr, Vec<u8>),
+        nonce: u64,
+    ) -> Result<Option<EscrowedFee>, ContractError> {
+        let mut storage = CwStorage(ctx.deps.storage);
+
+        let connection_str = connection_key(&lightclient_source, &lightclient_destination);
+
+        Ok(self
+            .packet_fee
+            .access(&mut storage)
+            .entry(&connection_str)
+            .entry(&nonce)
+            .get()?)
+    }
+
+    #[sv::msg(query)]
+    fn packet_acknowledgement(
+        &self,
+        ctx: QueryCtx,
+        lightclient_source: (Addr, Vec<u8>),
+        lightclient_destination: (Addr, Vec<u8>),
+        nonce: u64,
+    ) -> Result<Option<Acknowledgement>, ContractError> {
+        let mut storage = CwStorage(ctx.deps.storage);
+
+        let connection_str = connection_key(&lightclient_source, &lightclient_destination);
+
+        Ok(self
+            .packet_acknowledgement
+            .access(&mut storage)
+            .entry(&connection_str)
+            .entry(&nonce)
+            .get()?)
+    }
+
+    #[sv::msg(exec)]
+    fn send_packet(
+        &self,
+        ctx: ExecCtx,
+        packet: Packet,
+        fee: Option<PacketFee>,
+    ) -> Result<Response, ContractError> {
         let Packet {
             header:
                 PacketHeader {
@@ -77,16 +975,21 @@ impl Contract {
         } = &packet;
 
         if timeout <= &ctx.env.block.time.seconds() {
-            return Err(StdError::generic_err(format!(
-                "timeout is not in future: current time: {}, timeout: {}",
-                ctx.env.block.time.seconds(),
-                timeout
-            )));
+            return Err(ContractError::TimeoutNotInFuture {
+                current: ctx.env.block.time.seconds(),
+                timeout: *timeout,
+            });
         }
 
+        let lightclient =
+            self.resolve_prefixed_lightclient(ctx.deps.storage, lightclient_destination)?;
+
+        // no traffic through a frozen or otherwise untrusted light client
+        ensure_lightclient_status(&ctx.deps.querier, &lightclient, &[Status::Active])?;
+
         let mut storage = CwStorage(ctx.deps.storage);
 
-        let connection_str = format!("{:?}-{:?}", lightclient_source, lightclient_destination);
+        let connection_str = connection_key(lightclient_source, lightclient_destination);
 
         let stored_nonce = self
             .sent_nonce
@@ -96,20 +999,54 @@ impl Contract {
             .unwrap_or_default()
             + 1;
 
-        assert_eq!(nonce, &stored_nonce, "nonce mismatch");
+        if nonce != &stored_nonce {
+            return Err(ContractError::NonceMismatch {
+                expected: stored_nonce,
+                got: *nonce,
+            });
+        }
 
         {
-            // sum of funds should match the funds sent to the contract
-            let mut total_funds: HashMap<String, Uint128> = std::collections::HashMap::new();
+            // sum of funds and fees must match the funds sent to the contract exactly,
+            // tao has no way to refund any excess
+            let mut total_funds: BTreeMap<String, Uint128> = BTreeMap::new();
 
-            for payload in payloads {
-                for fund in &payload.header.funds {
-                    *total_funds.entry(fund.denom.clone()).or_default() += fund.amount;
-                }
+            let fee_funds = fee.iter().flat_map(|fee| {
+                fee.receive_fee
+                    .iter()
+                    .chain(&fee.ack_fee)
+                    .chain(&fee.timeout_fee)
+            });
+
+            for fund in payloads
+                .iter()
+                .flat_map(|payload| &payload.header.funds)
+                .chain(fee_funds)
+            {
+                *total_funds.entry(fund.denom.clone()).or_default() += fund.amount;
             }
 
-            for fund in ctx.info.funds.iter() {
-                assert!(*total_funds.entry(fund.denom.clone()).or_default() <= fund.amount);
+            // denoms sent but not required
+            for fund in &ctx.info.funds {
+                total_funds.entry(fund.denom.clone()).or_default();
+            }
+
+            for (denom, amount) in total_funds {
+                let sent = ctx
+                    .info
+                    .funds
+                    .iter()
+                    .filter(|fund| fund.denom == denom)
+                    .map(|fund| fund.amount)
+                    .sum();
+
+                if sent != amount {
+                    return Err(ContractError::FundsMismatch {
+                        denom,
+                        required: amount,
+                        sent,
+                    });
+                }
             }
         }
 
@@ -120,8 +1057,33 @@ impl Contract {
                 application_source,
                 application_destination,
                 funds,
+                ..
             } = &payload.header;
 
+            match self
+                .registered_application
+                .access(&mut storage)
+                .entry(&connection_str)
+                .entry(application_source.as_str())
+                .get()?
+            {
+                Some(SenderPolicy::Any) => {}
+                Some(SenderPolicy::AllowList(senders))
+                    if ctx.info.sender == application_source
+                        || senders.contains(&ctx.info.sender) => {}
+                Some(SenderPolicy::AllowList(_)) => {
+                    return Err(ContractError::SenderNotAllowed {
+                        sender: ctx.info.sender.clone(),
+                        application: application_source.clone(),
+                    })
+                }
+                None => {
+                    return Err(ContractError::ApplicationNotRegistered {
+                        application: application_source.clone(),
+                    })
+                }
+            }
+
             let msg =
                 Remote::<'_, dyn Application<Error = StdError>>::new(application_source.clone())
                     .executor()
@@ -149,6 +1111,22 @@ impl Contract {
             .entry_mut(&stored_nonce)
             .set(&packet)?;
 
+        storage.0.set(
+            &packet_commitment_path(&connection_str, stored_nonce),
+            &packet_commitment(&packet)?,
+        );
+
+        if let Some(fee) = fee {
+            self.packet_fee
+                .access(&mut storage)
+                .entry_mut(&connection_str)
+                .entry_mut(&stored_nonce)
+                .set(&EscrowedFee {
+                    payer: ctx.info.sender.clone(),
+                    fee,
+                })?;
+        }
+
         Ok(Response::new().add_messages(msgs))
     }
 
@@ -157,89 +1135,287 @@ impl Contract {
         &self,
         ctx: ExecCtx,
         packet: Packet,
-        height: u64,
+        height: Height,
         proof: Vec<u8>,
-    ) -> StdResult<Response> {
+    ) -> Result<Response, ContractError> {
+        self.receive(ctx.deps, &ctx.env, &ctx.info.sender, packet, height, proof)
+    }
+
+    // updates the source light client, then receives packets proven at `height` by a batch proof
+    // the light client sees tao as the submitter, allow-listed clients must list both tao and the
+    // relayer
+    #[sv::msg(exec)]
+    fn update_client_and_receive(
+        &self,
+        ctx: ExecCtx,
+        lightclient: Addr,
+        headers: Vec<Vec<u8>>,
+        packets: Vec<Packet>,
+        height: Height,
+        proof: Vec<u8>,
+    ) -> Result<Response, ContractError> {
+        for packet in &packets {
+            if packet.header.lightclient_source.0 != lightclient {
+                return Err(ContractError::SourceLightClientMismatch {
+                    expected: lightclient,
+                    got: packet.header.lightclient_source.0.clone(),
+                });
+            }
+        }
+
+        let lightclient = Remote::<'_, dyn LightClient<Error = StdError>>::new(
+            self.resolve_lightclient(ctx.deps.storage, &lightclient)?,
+        );
+
+        // tao must not relay headers of a sender the light client does not trust
+        if !lightclient
+            .querier(&ctx.deps.querier)
+            .update_policy()?
+            .is_allowed(&ctx.info.sender)
+        {
+            return Err(ContractError::Unauthorized);
+        }
+
+        let msg = lightclient.executor().update_batch(headers)?.build();
+
+        // packets are received in the reply, once the light client is updated
+        Ok(Response::new().add_submessage(
+            SubMsg::reply_on_success(msg, REPLY_UPDATE_CLIENT_ID).with_payload(to_json_binary(&(
+                &ctx.info.sender,
+                packets,
+                height,
+                proof,
+            ))?),
+        ))
+    }
+
+    #[sv::msg(reply)]
+    fn reply(&self, mut ctx: ReplyCtx, reply: Reply) -> Result<Response, ContractError> {
+        let mut storage = CwStorage(&mut *ctx.deps.storage);
+
+        match reply.id {
+            REPLY_RECEIVE_PAYLOAD_ID => {
+                let error = match reply.result.into_result() {
+                    Ok(_) => return Ok(Response::default()),
+                    Err(error) => error,
+                };
+
+                let (connection_str, nonce, index): (String, u64, usize) =
+                    from_json(&reply.payload)?;
+
+                let mut acknowledgement = self
+                    .packet_acknowledgement
+                    .access(&mut storage)
+                    .entry(&connection_str)
+                    .entry(&nonce)
+                    .get()?
+                    .ok_or(ContractError::AcknowledgementNotFound)?;
+
+                acknowledgement.results[index] = PayloadResult::Error(error);
+
+                self.packet_acknowledgement
+                    .access(&mut storage)
+                    .entry_mut(&connection_str)
+                    .entry_mut(&nonce)
+                    .set(&acknowledgement)?;
+
+                storage.0.set(
+                    &packet_acknowledgement_path(&connection_str, nonce),
+                    &acknowledgement_commitment(&acknowledgement)?,
+                );
+
+                Ok(Response::default())
+            }
+            REPLY_CREATE_LIGHTCLIENT_ID => {
+                let response = reply.result.into_result().map_err(StdError::generic_err)?;
+
+                let lightclient = response
+                    .events
+                    .iter()
+                    .filter(|event| event.ty == "instantiate")
+                    .flat_map(|event| &event.attributes)
+                    .find(|attribute| attribute.key == "_contract_address")
+                    .map(|attribute| attribute.value.clone())
+                    .ok_or_else(|| StdError::generic_err("instantiated light client not found"))?;
+
+                let info: LightClientInfo = from_json(&reply.payload)?;
+
+                self.lightclient_info
+                    .access(&mut storage)
+                    .entry_mut(&lightclient)
+                    .set(&info)?;
+
+                Ok(Response::new().add_attribute("lightclient", lightclient))
+            }
+            REPLY_UPDATE_CLIENT_ID => {
+                let (relayer, packets, height, proof): (Addr, Vec<Packet>, Height, Vec<u8>) =
+                    from_json(&reply.payload)?;
+
+                self.receive_batch(
+                    ctx.deps.branch(),
+                    &ctx.env,
+                    &relayer,
+                    packets,
+                    height,
+                    proof,
+                )
+            }
+            id => Err(ContractError::UnknownReplyId(id)),
+        }
+    }
+
+    #[sv::msg(exec)]
+    fn acknowledge_packet(
+        &self,
+        ctx: ExecCtx,
+        packet: Packet,
+        acknowledgement: Acknowledgement,
+        height: Height,
+        proof: Vec<u8>,
+    ) -> Result<Response, ContractError> {
         let Packet {
             header:
                 PacketHeader {
                     lightclient_source,
                     lightclient_destination,
                     nonce,
-                    timeout,
+                    ..
                 },
             payloads,
         } = &packet;
 
-        if timeout <= &ctx.env.block.time.seconds() {
-            return Err(StdError::generic_err(format!(
-                "timeout is not in future: current time: {}, timeout: {}",
-                ctx.env.block.time.seconds(),
-                timeout
-            )));
-        }
-
         let mut storage = CwStorage(ctx.deps.storage);
 
-        let connection_str = format!("{:?}-{:?}", lightclient_source, lightclient_destination);
+        let connection_str = connection_key(lightclient_source, lightclient_destination);
+
+        match self
+            .sent_packet
+            .access(&mut storage)
+            .entry(&connection_str)
+            .entry(nonce)
+            .get()?
+        {
+            Some(stored_packet) if stored_packet == packet => {}
+            _ => return Err(ContractError::PacketNotSent),
+        }
 
         if self
-            .received_packet
+            .timeout_packet
             .access(&mut storage)
             .entry(&connection_str)
             .entry(nonce)
             .get()?
             .is_some()
         {
-            return Err(StdError::generic_err("packet already received"));
+            return Err(ContractError::PacketAlreadyTimedOut);
         }
 
-        if Remote::<'_, dyn LightClient<Error = StdError>>::new(lightclient_source.0.clone())
-            .querier(&ctx.deps.querier)
-            .status()?
-            != Status::Active
+        if self
+            .acknowledged_packet
+            .access(&mut storage)
+            .entry(&connection_str)
+            .entry(nonce)
+            .get()?
+            .is_some()
         {
-            return Err(StdError::generic_err("light client is inactive"));
+            return Err(ContractError::PacketAlreadyAcknowledged);
         }
 
-        // validate commitment proof
-        Remote::<'_, dyn LightClient<Error = StdError>>::new(lightclient_source.0.clone())
-            .querier(&ctx.deps.querier)
-            .check_membership(vec![], vec![], lightclient_source.1.clone(), height, proof)?;
+        let lightclient = self.resolve_prefixed_lightclient(storage.0, lightclient_destination)?;
 
-        let mut msgs = vec![];
+        ensure_lightclient_status(&ctx.deps.querier, &lightclient, &[Status::Active])?;
 
-        for payload in payloads {
+        consensus_timestamp(&ctx.deps.querier, &lightclient, height)?;
+
+        // validate acknowledgement proof
+        ensure_verified(
+            Remote::<'_, dyn LightClient<Error = StdError>>::new(lightclient.clone())
+                .querier(&ctx.deps.querier)
+                .check_membership(
+                    packet_acknowledgement_path(&connection_str, *nonce),
+                    acknowledgement_commitment(&acknowledgement)?,
+                    lightclient_destination.1.clone(),
+                    height,
+                    proof,
+                )?,
+        )?;
+
+        if acknowledgement.results.len() != payloads.len() {
+            return Err(ContractError::AcknowledgementMismatch {
+                payloads: payloads.len(),
+                results: acknowledgement.results.len(),
+            });
+        }
+
+        let mut msgs = vec![consume_proofs(&lightclient, height, 1)?];
+
+        for (payload, result) in payloads.iter().zip(&acknowledgement.results) {
             let PayloadHeader {
                 application_source,
                 application_destination,
                 funds,
+                ..
             } = &payload.header;
 
-            let msg = Remote::<'_, dyn Application<Error = StdError>>::new(
-                application_destination.clone(),
-            )
-            .executor()
-            .receive(
-                lightclient_destination.clone(),
-                lightclient_source.clone(),
-                application_source.clone(),
-                payload.data.clone(),
-                ctx.info.sender.clone(),
-                funds.clone(),
-            )?
-            .build();
+            let msg =
+                Remote::<'_, dyn Application<Error = StdError>>::new(application_source.clone())
+                    .executor()
+                    .acknowledge(
+                        lightclient_source.clone(),
+                        lightclient_destination.clone(),
+                        application_destination.clone(),
+                        payload.data.clone(),
+                        result.clone(),
+                        ctx.info.sender.clone(),
+                        funds.clone(),
+                    )?
+                    .build();
 
             msgs.push(msg);
         }
 
-        self.received_packet
+        self.acknowledged_packet
             .access(&mut storage)
             .entry_mut(&connection_str)
             .entry_mut(nonce)
             .set(&())?;
 
-        Ok(Response::new().add_messages(msgs))
+        // the commitment is no longer needed once the packet lifecycle is over
+        storage
+            .0
+            .remove(&packet_commitment_path(&connection_str, *nonce));
+
+        let mut fee_msgs = vec![];
+
+        if let Some(EscrowedFee { payer, fee }) = self
+            .packet_fee
+            .access(&mut storage)
+            .entry(&connection_str)
+            .entry(nonce)
+            .get()?
+        {
+            // receive fee goes to the payee recorded in the acknowledgement,
+            // ack fee to the acknowledgement relayer and the rest is refunded
+            // without a registered counterparty payee the recorded relayer is a destination chain
+            // address, the receive fee is then refunded so the payout never reverts the acknowledgement
+            let receive_payee = ctx
+                .deps
+                .api
+                .addr_validate(acknowledgement.relayer.as_str())
+                .unwrap_or_else(|_| payer.clone());
+
+            fee_msgs.extend(transfer(&receive_payee, fee.receive_fee)?);
+            fee_msgs.extend(transfer(&ctx.info.sender, fee.ack_fee)?);
+            fee_msgs.extend(transfer(&payer, fee.timeout_fee)?);
+
+            self.packet_fee
+                .access(&mut storage)
+                .entry_mut(&connection_str)
+                .entry_mut(nonce)
+                .remove();
+        }
+
+        Ok(Response::new().add_messages(msgs).add_messages(fee_msgs))
     }
 
     #[sv::msg(exec)]
@@ -247,9 +1423,9 @@ impl Contract {
         &self,
         ctx: ExecCtx,
         packet: Packet,
-        height: u64,
+        height: Height,
         proof: Vec<u8>,
-    ) -> StdResult<Response> {
+    ) -> Result<Response, ContractError> {
         let Packet {
             header:
                 PacketHeader {
@@ -263,7 +1439,7 @@ impl Contract {
 
         let mut storage = CwStorage(ctx.deps.storage);
 
-        let connection_str = format!("{:?}-{:?}", lightclient_source, lightclient_destination);
+        let connection_str = connection_key(lightclient_source, lightclient_destination);
 
         match self
             .sent_packet
@@ -273,7 +1449,7 @@ impl Contract {
             .get()?
         {
             Some(stored_packet) if stored_packet == packet => {}
-            _ => return Err(StdError::generic_err("packet not sent")),
+            _ => return Err(ContractError::PacketNotSent),
         }
 
         if self
@@ -284,41 +1460,66 @@ impl Contract {
             .get()?
             .is_some()
         {
-            return Err(StdError::generic_err("packet already timed out"));
+            return Err(ContractError::PacketAlreadyTimedOut);
         }
 
-        if Remote::<'_, dyn LightClient<Error = StdError>>::new(lightclient_destination.0.clone())
-            .querier(&ctx.deps.querier)
-            .status()?
-            != Status::Active
+        if self
+            .acknowledged_packet
+            .access(&mut storage)
+            .entry(&connection_str)
+            .entry(nonce)
+            .get()?
+            .is_some()
         {
-            return Err(StdError::generic_err("light client is inactive"));
+            return Err(ContractError::PacketAlreadyAcknowledged);
         }
 
-        let proof_height_timestamp =
-            Remote::<'_, dyn LightClient<Error = StdError>>::new(lightclient_destination.0.clone())
-                .querier(&ctx.deps.querier)
-                .timestamp(height)?;
+        // expired light clients can still prove timeouts, if governance allows it
+        let allowed_status = if self
+            .config
+            .access(&storage)
+            .get()?
+            .unwrap_or_default()
+            .allow_timeout_on_expired
+        {
+            &[Status::Active, Status::Expired][..]
+        } else {
+            &[Status::Active][..]
+        };
+
+        let lightclient = self.resolve_prefixed_lightclient(storage.0, lightclient_destination)?;
+
+        ensure_lightclient_status(&ctx.deps.querier, &lightclient, allowed_status)?;
+
+        let proof_height_timestamp = consensus_timestamp(&ctx.deps.querier, &lightclient, height)?;
 
         if timeout > &proof_height_timestamp {
-            return Err(StdError::generic_err(format!(
-                "timeout is in future for proof height: current time: {}, timeout: {}",
-                proof_height_timestamp, timeout
-            )));
+            return Err(ContractError::TimeoutNotElapsed {
+                current: proof_height_timestamp,
+                timeout: *timeout,
+            });
         }
 
         // validate commitment proof
-        Remote::<'_, dyn LightClient<Error = StdError>>::new(lightclient_destination.0.clone())
-            .querier(&ctx.deps.querier)
-            .check_non_membership(vec![], lightclient_destination.1.clone(), height, proof)?;
+        ensure_verified(
+            Remote::<'_, dyn LightClient<Error = StdError>>::new(lightclient.clone())
+                .querier(&ctx.deps.querier)
+                .check_non_membership(
+                    packet_receipt_path(&connection_str, *nonce),
+                    lightclient_destination.1.clone(),
+                    height,
+                    proof,
+                )?,
+        )?;
 
-        let mut msgs = vec![];
+        let mut msgs = vec![consume_proofs(&lightclient, height, 1)?];
 
         for payload in payloads {
             let PayloadHeader {
                 application_source,
                 application_destination,
                 funds,
+                ..
             } = &payload.header;
 
             let msg =
@@ -343,6 +1544,33 @@ impl Contract {
             .entry_mut(nonce)
             .set(&())?;
 
-        Ok(Response::new().add_messages(msgs))
+        storage
+            .0
+            .remove(&packet_commitment_path(&connection_str, *nonce));
+
+        let mut fee_msgs = vec![];
+
+        if let Some(EscrowedFee { payer, fee }) = self
+            .packet_fee
+            .access(&mut storage)
+            .entry(&connection_str)
+            .entry(nonce)
+            .get()?
+        {
+            // timeout fee goes to the timeout relayer and the rest is refunded
+            fee_msgs.extend(transfer(&ctx.info.sender, fee.timeout_fee)?);
+            fee_msgs.extend(transfer(
+                &payer,
+                fee.receive_fee.into_iter().chain(fee.ack_fee),
+            )?);
+
+            self.packet_fee
+                .access(&mut storage)
+                .entry_mut(&connection_str)
+                .entry_mut(nonce)
+                .remove();
+        }
+
+        Ok(Response::new().add_messages(msgs).add_messages(fee_msgs))
     }
 }
@@ -0,0 +1,745 @@
+// synthetic fixtures of a minimal preset chain for the ethereum light client tests
+// the sync committees sign with known keys, the beacon and execution states are sparse trees
+// of pseudo-random nodes around the proven leaves, written as JSON to the given directory
+// hashing, merkleization, signing domains and generalized indices follow the consensus specs
+// and the yellow paper here, independently of the client, so that the fixtures check it
+
+use std::collections::HashMap;
+
+use ark_bls12_381::{Fr, G1Affine, G2Affine};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use cosmwasm_crypto::HashFunction;
+use cosmwasm_std::HexBinary;
+use eureka_lightclient_ethereum::{
+    BeaconBlockHeader, ClientState, ConsensusState, Fork, ForkName, LightClientHeader,
+    Misbehaviour, StorageProof, SyncAggregate, SyncCommittee, Update,
+};
+use sha2::{Digest, Sha256};
+
+const BLS_DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+const SYNC_COMMITTEE_SIZE: usize = 32;
+
+const DOMAIN_SYNC_COMMITTEE: [u8; 4] = [7, 0, 0, 0];
+
+const IBC_CONTRACT: [u8; 20] = [0x1b; 20];
+const COMMITMENT_PREFIX: &[u8] = b"ibc";
+
+type Root = [u8; 32];
+
+// generalized indices of the finalized checkpoint root and of the next sync committee
+fn state_gindices(fork: ForkName) -> (u64, u64) {
+    match fork {
+        ForkName::Electra => (169, 87),
+        _ => (105, 55),
+    }
+}
+
+// generalized index of the `state_root` of the execution payload header in the beacon block
+// body, whose header grows past 16 fields in deneb
+fn execution_state_root_gindex(fork: ForkName) -> u64 {
+    match fork {
+        ForkName::Deneb | ForkName::Electra => 25 * 32 + 2,
+        _ => 25 * 16 + 2,
+    }
+}
+
+fn hash(left: &[u8], right: &[u8]) -> Root {
+    Sha256::new()
+        .chain_update(left)
+        .chain_update(right)
+        .finalize()
+        .into()
+}
+
+// keccak-256 sponge over the keccak-f[1600] permutation, with the original keccak padding
+fn keccak256(data: &[u8]) -> Root {
+    const RATE: usize = 136;
+
+    let mut padded = data.to_vec();
+    padded.push(0x01);
+    padded.resize(padded.len().div_ceil(RATE) * RATE, 0);
+    *padded.last_mut().unwrap() |= 0x80;
+
+    let mut state = [0u64; 25];
+    for block in padded.chunks(RATE) {
+        for (lane, word) in state.iter_mut().zip(block.chunks(8)) {
+            *lane ^= u64::from_le_bytes(word.try_into().unwrap());
+        }
+        keccak::f1600(&mut state);
+    }
+
+    let mut digest = Root::default();
+    for (bytes, lane) in digest.chunks_mut(8).zip(state) {
+        bytes.copy_from_slice(&lane.to_le_bytes());
+    }
+    digest
+}
+
+// storage slot of the commitment at `path` in the IBC contract
+fn commitment_slot(path: &[u8]) -> Root {
+    keccak256(&[COMMITMENT_PREFIX, path].concat())
+}
+
+// leaf of a basic value or of a byte vector of at most 32 bytes, zero padded
+fn chunk(bytes: &[u8]) -> Root {
+    let mut chunk = Root::default();
+    chunk[..bytes.len()].copy_from_slice(bytes);
+    chunk
+}
+
+// root of a power of two number of leaves
+fn merkle_root(leaves: &[Root]) -> Root {
+    match leaves {
+        [leaf] => *leaf,
+        _ => {
+            let (left, right) = leaves.split_at(leaves.len() / 2);
+            hash(&merkle_root(left), &merkle_root(right))
+        }
+    }
+}
+
+// a 48 bytes BLS public key spans two chunks
+fn pubkey_root(pubkey: &[u8]) -> Root {
+    hash(&chunk(&pubkey[..32]), &chunk(&pubkey[32..]))
+}
+
+fn beacon_block_header_root(header: &BeaconBlockHeader) -> Root {
+    let mut leaves = vec![
+        chunk(&header.slot.to_le_bytes()),
+        chunk(&header.proposer_index.to_le_bytes()),
+        chunk(&header.parent_root),
+        chunk(&header.state_root),
+        chunk(&header.body_root),
+    ];
+    leaves.resize(8, Root::default());
+
+    merkle_root(&leaves)
+}
+
+fn sync_committee_root(sync_committee: &SyncCommittee) -> Root {
+    let pubkeys: Vec<Root> = sync_committee
+        .pubkeys
+        .iter()
+        .map(|pubkey| pubkey_root(pubkey))
+        .collect();
+
+    hash(
+        &merkle_root(&pubkeys),
+        &pubkey_root(&sync_committee.aggregate_pubkey),
+    )
+}
+
+// `compute_signing_root` of `header` under `compute_domain(DOMAIN_SYNC_COMMITTEE, ..)`
+fn signing_root(
+    header: &BeaconBlockHeader,
+    version: &[u8],
+    genesis_validators_root: &[u8],
+) -> Root {
+    let fork_data_root = hash(&chunk(version), genesis_validators_root);
+
+    let mut domain = Root::default();
+    domain[..4].copy_from_slice(&DOMAIN_SYNC_COMMITTEE);
+    domain[4..].copy_from_slice(&fork_data_root[..28]);
+
+    hash(&beacon_block_header_root(header), &domain)
+}
+
+// fork scheduled at the epoch of `slot`
+fn fork(client_state: &ClientState, slot: u64) -> &Fork {
+    let epoch = slot / client_state.slots_per_epoch;
+
+    client_state
+        .forks
+        .iter()
+        .rev()
+        .find(|fork| fork.epoch <= epoch)
+        .unwrap()
+}
+
+fn random(seed: &str, index: u64) -> Root {
+    Sha256::digest([seed.as_bytes(), &index.to_be_bytes()].concat()).into()
+}
+
+fn hex(bytes: &[u8]) -> HexBinary {
+    HexBinary::from(bytes)
+}
+
+struct Committee {
+    secret_keys: Vec<Fr>,
+    sync_committee: SyncCommittee,
+}
+
+fn committee(period: u64) -> Committee {
+    let secret_keys: Vec<Fr> = (0..SYNC_COMMITTEE_SIZE as u64)
+        .map(|index| Fr::from(period * 1000 + index + 7))
+        .collect();
+
+    let pubkeys: Vec<HexBinary> = secret_keys
+        .iter()
+        .map(|secret_key| {
+            let mut pubkey = vec![];
+            (G1Affine::generator() * secret_key)
+                .into_affine()
+                .serialize_compressed(&mut pubkey)
+                .unwrap();
+            hex(&pubkey)
+        })
+        .collect();
+
+    let aggregate_pubkey = cosmwasm_crypto::bls12_381_aggregate_g1(
+        &pubkeys
+            .iter()
+            .flat_map(|pubkey| pubkey.to_vec())
+            .collect::<Vec<_>>(),
+    )
+    .unwrap();
+
+    Committee {
+        secret_keys,
+        sync_committee: SyncCommittee {
+            pubkeys,
+            aggregate_pubkey: hex(&aggregate_pubkey),
+        },
+    }
+}
+
+// aggregate signature of `participants` over `message`, with their participation bits
+fn sign(committee: &Committee, participants: &[usize], message: &[u8]) -> SyncAggregate {
+    let point = cosmwasm_crypto::bls12_381_hash_to_g2(HashFunction::Sha256, message, BLS_DST);
+    let point = G2Affine::deserialize_compressed(&point[..]).unwrap();
+
+    let secret_key: Fr = participants
+        .iter()
+        .map(|index| committee.secret_keys[*index])
+        .sum();
+
+    let mut signature = vec![];
+    (point * secret_key)
+        .into_affine()
+        .serialize_compressed(&mut signature)
+        .unwrap();
+
+    let mut bits = vec![0u8; SYNC_COMMITTEE_SIZE / 8];
+    for index in participants {
+        bits[index / 8] |= 1 << (index % 8);
+    }
+
+    SyncAggregate {
+        sync_committee_bits: hex(&bits),
+        sync_committee_signature: hex(&signature),
+    }
+}
+
+// SSZ merkle tree of the given depth, random but for the fixed nodes
+struct Tree {
+    seed: String,
+    depth: u32,
+    fixed: HashMap<u64, Root>,
+}
+
+impl Tree {
+    fn node(&self, gindex: u64) -> Root {
+        if let Some(node) = self.fixed.get(&gindex) {
+            return *node;
+        }
+
+        if gindex.ilog2() == self.depth {
+            return random(&self.seed, gindex);
+        }
+
+        hash(&self.node(2 * gindex), &self.node(2 * gindex + 1))
+    }
+
+    fn root(&self) -> Root {
+        self.node(1)
+    }
+
+    fn branch(&self, mut gindex: u64) -> Vec<HexBinary> {
+        let mut branch = vec![];
+        while gindex > 1 {
+            branch.push(hex(&self.node(gindex ^ 1)));
+            gindex /= 2;
+        }
+        branch
+    }
+}
+
+fn rlp_bytes(bytes: &[u8]) -> Vec<u8> {
+    if bytes.len() == 1 && bytes[0] < 0x80 {
+        return bytes.to_vec();
+    }
+
+    [rlp_length(0x80, bytes.len()), bytes.to_vec()].concat()
+}
+
+fn rlp_list(items: &[Vec<u8>]) -> Vec<u8> {
+    let payload = items.concat();
+
+    [rlp_length(0xc0, payload.len()), payload].concat()
+}
+
+fn rlp_length(offset: u8, length: usize) -> Vec<u8> {
+    if length < 56 {
+        return vec![offset + length as u8];
+    }
+
+    let length: Vec<u8> = length
+        .to_be_bytes()
+        .into_iter()
+        .skip_while(|byte| *byte == 0)
+        .collect();
+
+    [vec![offset + 55 + length.len() as u8], length].concat()
+}
+
+// hex-prefix encoding of a node path
+fn hex_prefix(nibbles: &[u8], leaf: bool) -> Vec<u8> {
+    let flag = if leaf { 2 } else { 0 };
+
+    let (mut encoded, rest) = if nibbles.len() % 2 == 1 {
+        (vec![(flag + 1) << 4 | nibbles[0]], &nibbles[1..])
+    } else {
+        (vec![flag << 4], nibbles)
+    };
+
+    encoded.extend(rest.chunks(2).map(|pair| pair[0] << 4 | pair[1]));
+    encoded
+}
+
+fn nibbles(key: &[u8]) -> Vec<u8> {
+    keccak256(key)
+        .iter()
+        .flat_map(|byte| [byte >> 4, byte & 0x0f])
+        .collect()
+}
+
+// (nibbles of the hashed key, value)
+type Entries = Vec<(Vec<u8>, Vec<u8>)>;
+
+fn common_prefix(entries: &[(Vec<u8>, Vec<u8>)], depth: usize) -> usize {
+    let first = &entries[0].0;
+
+    (depth..first.len())
+        .take_while(|index| {
+            entries
+                .iter()
+                .all(|(path, _)| path[*index] == first[*index])
+        })
+        .count()
+}
+
+// node of the entries below `depth`, all sharing their path above it
+fn encode(entries: &[(Vec<u8>, Vec<u8>)], depth: usize) -> Vec<u8> {
+    if let [(path, value)] = entries {
+        return rlp_list(&[
+            rlp_bytes(&hex_prefix(&path[depth..], true)),
+            rlp_bytes(value),
+        ]);
+    }
+
+    let shared = common_prefix(entries, depth);
+
+    if shared > 0 {
+        let child = encode(entries, depth + shared);
+        return rlp_list(&[
+            rlp_bytes(&hex_prefix(&entries[0].0[depth..depth + shared], false)),
+            reference(&child),
+        ]);
+    }
+
+    let mut items: Vec<Vec<u8>> = (0..16)
+        .map(|nibble| {
+            let group: Entries = entries
+                .iter()
+                .filter(|(path, _)| path[depth] == nibble)
+                .cloned()
+                .collect();
+
+            if group.is_empty() {
+                rlp_bytes(&[])
+            } else {
+                reference(&encode(&group, depth + 1))
+            }
+        })
+        .collect();
+    items.push(rlp_bytes(&[]));
+
+    rlp_list(&items)
+}
+
+// nodes below 32 bytes are embedded in their parent
+fn reference(node: &[u8]) -> Vec<u8> {
+    if node.len() < 32 {
+        node.to_vec()
+    } else {
+        rlp_bytes(&keccak256(node))
+    }
+}
+
+fn prove(entries: &[(Vec<u8>, Vec<u8>)], depth: usize, path: &[u8], proof: &mut Vec<HexBinary>) {
+    let node = encode(entries, depth);
+
+    if depth == 0 || node.len() >= 32 {
+        proof.push(hex(&node));
+    }
+
+    if entries.len() == 1 {
+        return;
+    }
+
+    let shared = common_prefix(entries, depth);
+
+    if shared > 0 {
+        if path[depth..depth + shared] == entries[0].0[depth..depth + shared] {
+            prove(entries, depth + shared, path, proof);
+        }
+        return;
+    }
+
+    let group: Entries = entries
+        .iter()
+        .filter(|(entry_path, _)| entry_path[depth] == path[depth])
+        .cloned()
+        .collect();
+
+    if !group.is_empty() {
+        prove(&group, depth + 1, path, proof);
+    }
+}
+
+// Merkle-Patricia trie, with `eth_getProof` style proofs
+struct Trie(Entries);
+
+impl Trie {
+    fn new(entries: &[(Vec<u8>, Vec<u8>)]) -> Self {
+        Self(
+            entries
+                .iter()
+                .map(|(key, value)| (nibbles(key), value.clone()))
+                .collect(),
+        )
+    }
+
+    fn root(&self) -> Vec<u8> {
+        keccak256(&encode(&self.0, 0)).to_vec()
+    }
+
+    fn proof(&self, key: &[u8]) -> Vec<HexBinary> {
+        let mut proof = vec![];
+        prove(&self.0, 0, &nibbles(key), &mut proof);
+        proof
+    }
+}
+
+fn storage_value(value: &[u8]) -> Vec<u8> {
+    let trimmed: Vec<u8> = value
+        .iter()
+        .copied()
+        .skip_while(|byte| *byte == 0)
+        .collect();
+
+    rlp_bytes(&trimmed)
+}
+
+// execution state with the IBC contract among other accounts
+struct ExecutionState {
+    state: Trie,
+    storage: Trie,
+}
+
+fn execution_state(commitments: &[(&[u8], Root)], seed: &str) -> ExecutionState {
+    let storage_entries: Entries = commitments
+        .iter()
+        .map(|(path, value)| (commitment_slot(path).to_vec(), storage_value(value)))
+        .chain((0..6).map(|index| {
+            (
+                random(seed, index).to_vec(),
+                storage_value(&random(seed, 100 + index)),
+            )
+        }))
+        .collect();
+    let storage = Trie::new(&storage_entries);
+
+    let account = |storage_root: &[u8], index: u64| {
+        rlp_list(&[
+            rlp_bytes(&[1]),
+            rlp_bytes(&(index * 1_000_000_007).to_be_bytes()[2..]),
+            rlp_bytes(storage_root),
+            rlp_bytes(&random("code", index)),
+        ])
+    };
+    let empty_storage_root = keccak256(&[0x80]);
+
+    let state_entries: Entries = [(IBC_CONTRACT.to_vec(), account(&storage.root(), 1))]
+        .into_iter()
+        .chain((0..12).map(|index| {
+            (
+                random(seed, 200 + index)[..20].to_vec(),
+                account(&empty_storage_root, 2 + index),
+            )
+        }))
+        .collect();
+
+    ExecutionState {
+        state: Trie::new(&state_entries),
+        storage,
+    }
+}
+
+// the block body is laid out as of the fork at `slot`
+fn header(
+    client_state: &ClientState,
+    slot: u64,
+    seed: &str,
+    state_root: Root,
+    execution_state_root: &[u8],
+) -> LightClientHeader {
+    let gindex = execution_state_root_gindex(fork(client_state, slot).name);
+
+    let body = Tree {
+        seed: format!("{seed}-body"),
+        depth: gindex.ilog2(),
+        fixed: HashMap::from([(gindex, Root::try_from(execution_state_root).unwrap())]),
+    };
+
+    LightClientHeader {
+        beacon: BeaconBlockHeader {
+            slot,
+            proposer_index: slot * 3 % 17,
+            parent_root: hex(&random(seed, 1)),
+            state_root: hex(&state_root),
+            body_root: hex(&body.root()),
+        },
+        execution_state_root: hex(execution_state_root),
+        execution_branch: body.branch(gindex),
+    }
+}
+
+// the attested state is laid out as of the fork at the attested slot
+struct Slots {
+    finalized: u64,
+    attested: u64,
+    signature: u64,
+}
+
+fn update(
+    client_state: &ClientState,
+    seed: &str,
+    slots: Slots,
+    execution_state_root: &[u8],
+    next_sync_committee: Option<&SyncCommittee>,
+    signers: (&Committee, &[usize]),
+) -> Update {
+    let finalized = header(
+        client_state,
+        slots.finalized,
+        &format!("{seed}-finalized"),
+        random(seed, 2),
+        execution_state_root,
+    );
+
+    let (finalized_root_gindex, next_sync_committee_gindex) =
+        state_gindices(fork(client_state, slots.attested).name);
+
+    let mut fixed = HashMap::from([(
+        finalized_root_gindex,
+        beacon_block_header_root(&finalized.beacon),
+    )]);
+    if let Some(next_sync_committee) = next_sync_committee {
+        fixed.insert(
+            next_sync_committee_gindex,
+            sync_committee_root(next_sync_committee),
+        );
+    }
+
+    let state = Tree {
+        seed: format!("{seed}-state"),
+        depth: finalized_root_gindex.ilog2(),
+        fixed,
+    };
+
+    let attested = header(
+        client_state,
+        slots.attested,
+        &format!("{seed}-attested"),
+        state.root(),
+        &random(seed, 3),
+    );
+
+    let signing_root = signing_root(
+        &attested.beacon,
+        &fork(client_state, slots.signature - 1).version,
+        &client_state.genesis_validators_root,
+    );
+
+    Update {
+        attested_header: attested,
+        next_sync_committee: next_sync_committee.cloned(),
+        next_sync_committee_branch: match next_sync_committee {
+            Some(_) => state.branch(next_sync_committee_gindex),
+            None => vec![],
+        },
+        finalized_header: finalized,
+        finality_branch: state.branch(finalized_root_gindex),
+        sync_aggregate: sign(signers.0, signers.1, &signing_root),
+        signature_slot: slots.signature,
+    }
+}
+
+fn write(name: &str, value: &impl serde::Serialize) {
+    let directory = std::env::args().nth(1).expect("fixtures directory");
+
+    std::fs::write(
+        format!("{directory}/{name}.json"),
+        serde_json::to_string_pretty(value).unwrap() + "\n",
+    )
+    .unwrap();
+}
+
+fn main() {
+    let committee_0 = committee(0);
+    let committee_1 = committee(1);
+    let committee_2 = committee(2);
+
+    // the default multitest block time is at slot 236
+    let genesis_time = 1_571_797_419 - 236 * 6;
+
+    let path_1: &[u8] = b"commitments/ports/transfer/channels/channel-0/sequences/1";
+    let path_2: &[u8] = b"commitments/ports/transfer/channels/channel-0/sequences/2";
+    let value_1 = random("commitment", 1);
+    let value_2 = random("commitment", 2);
+
+    let execution_40 = execution_state(&[], "exec-40");
+    let execution_48 = execution_state(&[(path_1, value_1)], "exec-48");
+    let execution_72 = execution_state(&[(path_2, value_2)], "exec-72");
+
+    // deneb from epoch 7 and electra from epoch 9, the first update finalizes a capella block
+    // and is attested under deneb, the second one is finalized and attested under electra
+    let client_state = ClientState {
+        chain_id: 1337,
+        genesis_validators_root: hex(&random("genesis", 0)),
+        genesis_time,
+        seconds_per_slot: 6,
+        slots_per_epoch: 8,
+        epochs_per_sync_committee_period: 8,
+        forks: vec![
+            Fork {
+                name: ForkName::Capella,
+                epoch: 0,
+                version: hex(&[3, 0, 0, 1]),
+            },
+            Fork {
+                name: ForkName::Deneb,
+                epoch: 7,
+                version: hex(&[4, 0, 0, 1]),
+            },
+            Fork {
+                name: ForkName::Electra,
+                epoch: 9,
+                version: hex(&[5, 0, 0, 1]),
+            },
+        ],
+        ibc_contract_address: hex(&IBC_CONTRACT),
+        trusting_period: 3 * 3600,
+        latest_slot: 40,
+        frozen_slot: None,
+    };
+
+    let consensus_state = ConsensusState {
+        state_root: hex(&execution_40.state.root()),
+        timestamp: genesis_time + 40 * client_state.seconds_per_slot,
+    };
+
+    write(
+        "instantiate",
+        &serde_json::json!({
+            "client_state": client_state,
+            "consensus_state": consensus_state,
+            "current_sync_committee": committee_0.sync_committee,
+        }),
+    );
+
+    let most: Vec<usize> = (0..SYNC_COMMITTEE_SIZE)
+        .filter(|index| index % 11 != 5)
+        .collect();
+    let all: Vec<usize> = (0..SYNC_COMMITTEE_SIZE).collect();
+
+    // period 0, finalizes slot 48 and carries the committee of period 1
+    let update_1 = update(
+        &client_state,
+        "update-1",
+        Slots {
+            finalized: 48,
+            attested: 58,
+            signature: 59,
+        },
+        &execution_48.state.root(),
+        Some(&committee_1.sync_committee),
+        (&committee_0, &most),
+    );
+    write("update_1", &update_1);
+
+    // period 1, signed by the committee of period 1 and carrying the one of period 2
+    let update_2 = update(
+        &ClientState {
+            latest_slot: 48,
+            ..client_state.clone()
+        },
+        "update-2",
+        Slots {
+            finalized: 72,
+            attested: 80,
+            signature: 81,
+        },
+        &execution_72.state.root(),
+        Some(&committee_2.sync_committee),
+        (&committee_1, &all),
+    );
+    write("update_2", &update_2);
+
+    // conflicting finalized header at slot 48
+    let execution_48_fork = execution_state(&[(path_1, value_2)], "exec-48-fork");
+    let conflicting = update(
+        &client_state,
+        "update-1-fork",
+        Slots {
+            finalized: 48,
+            attested: 60,
+            signature: 61,
+        },
+        &execution_48_fork.state.root(),
+        None,
+        (&committee_0, &all),
+    );
+    write(
+        "misbehaviour",
+        &Misbehaviour {
+            update_1: update_1.clone(),
+            update_2: conflicting,
+        },
+    );
+
+    // storage proofs in the execution state at slot 48
+    let storage_proof = |execution: &ExecutionState, path: &[u8]| StorageProof {
+        account_proof: execution.state.proof(&IBC_CONTRACT),
+        storage_proof: execution.storage.proof(&commitment_slot(path)),
+    };
+
+    write(
+        "proofs",
+        &serde_json::json!({
+            "commitment_prefix": hex(COMMITMENT_PREFIX),
+            "height": 48,
+            "membership": {
+                "key": hex(path_1),
+                "value": hex(&value_1),
+                "proof": storage_proof(&execution_48, path_1),
+            },
+            "non_membership": {
+                "key": hex(path_2),
+                "proof": storage_proof(&execution_48, path_2),
+            },
+        }),
+    );
+}
@@ -0,0 +1,520 @@
+use ed25519_dalek::Signer;
+use eureka_ics23_verifier::{apply_prefix, commitment_proof, IBC_STORE};
+use eureka_lightclient_attestor::sv::mt::CodeId as AttestorCodeId;
+use eureka_lightclient_attestor::{
+    Attestation, AttestorSet, ClientState, ConsensusState, Contract, ContractError, Header,
+    MembershipProof, Misbehaviour, PublicKey, StateAttestation,
+};
+use eureka_lightclient_interface::sv::mt::LightClientProxy;
+use eureka_lightclient_interface::{Height, Status};
+use k256::ecdsa::signature::hazmat::PrehashSigner;
+use rstest::rstest;
+use sylvia::cw_multi_test::BasicApp;
+use sylvia::cw_std::{to_json_vec, Addr};
+use sylvia::multitest::{App, Proxy};
+
+use crate::ics23::{iavl_pair, merkle_proof, multistore_pair};
+
+const CHAIN_ID: &str = "permissioned-1";
+
+enum Key {
+    Secp256k1(k256::ecdsa::SigningKey),
+    Ed25519(ed25519_dalek::SigningKey),
+}
+
+impl Key {
+    fn public_key(&self) -> PublicKey {
+        match self {
+            Self::Secp256k1(key) => PublicKey::Secp256k1(
+                key.verifying_key()
+                    .to_encoded_point(true)
+                    .as_bytes()
+                    .to_vec(),
+            ),
+            Self::Ed25519(key) => PublicKey::Ed25519(key.verifying_key().to_bytes().to_vec()),
+        }
+    }
+
+    fn sign(&self, attestation: &Attestation) -> Vec<u8> {
+        let sign_bytes = attestation.sign_bytes().unwrap();
+
+        match self {
+            Self::Secp256k1(key) => {
+                let signature: k256::ecdsa::Signature = key.sign_prehash(&sign_bytes).unwrap();
+                signature.to_bytes().to_vec()
+            }
+            Self::Ed25519(key) => key.sign(&sign_bytes).to_bytes().to_vec(),
+        }
+    }
+}
+
+// odd seeds give secp256k1 keys, even seeds ed25519 keys
+fn keys(seeds: &[u8]) -> Vec<Key> {
+    seeds
+        .iter()
+        .map(|seed| {
+            if seed % 2 == 1 {
+                Key::Secp256k1(k256::ecdsa::SigningKey::from_bytes(&[*seed; 32].into()).unwrap())
+            } else {
+                Key::Ed25519(ed25519_dalek::SigningKey::from_bytes(&[*seed; 32]))
+            }
+        })
+        .collect()
+}
+
+fn attestor_set(keys: &[Key], threshold: u64) -> AttestorSet {
+    AttestorSet {
+        signers: keys.iter().map(Key::public_key).collect(),
+        threshold,
+    }
+}
+
+// signatures of the keys flagged in `signers`
+fn sign(attestation: &Attestation, keys: &[Key], signers: &[bool]) -> Vec<Option<Vec<u8>>> {
+    keys.iter()
+        .zip(signers)
+        .map(|(key, signed)| signed.then(|| key.sign(attestation)))
+        .collect()
+}
+
+fn state(
+    height: u64,
+    timestamp: u64,
+    root: &[u8],
+    keys: &[Key],
+    signers: &[bool],
+) -> StateAttestation {
+    let height = Height::new(1, height);
+
+    let attestation = Attestation::State {
+        chain_id: CHAIN_ID.to_string(),
+        height,
+        timestamp,
+        root: root.to_vec(),
+    };
+
+    StateAttestation {
+        height,
+        timestamp,
+        root: root.to_vec(),
+        signatures: sign(&attestation, keys, signers),
+    }
+}
+
+fn setup<'a>(chain: &'a App<BasicApp>, keys: &[Key]) -> Proxy<'a, BasicApp, Contract> {
+    AttestorCodeId::store_code(chain)
+        .instantiate(
+            ClientState {
+                chain_id: CHAIN_ID.to_string(),
+                attestor_set: attestor_set(keys, 2),
+                sequence: 0,
+                latest_height: Height::new(1, 10),
+                frozen: false,
+            },
+            ConsensusState {
+                timestamp: 100,
+                root: b"root".to_vec(),
+            },
+        )
+        .call(&Addr::unchecked("dao"))
+        .unwrap()
+}
+
+#[rstest]
+fn test_attestor_set_keys() {
+    let chain = App::default();
+
+    let code_id = AttestorCodeId::store_code(&chain);
+
+    let keys_a = keys(&[1, 2, 3, 4]);
+
+    let instantiate = |signers: Vec<PublicKey>| {
+        code_id
+            .instantiate(
+                ClientState {
+                    chain_id: CHAIN_ID.to_string(),
+                    attestor_set: AttestorSet {
+                        signers,
+                        threshold: 2,
+                    },
+                    sequence: 0,
+                    latest_height: Height::new(1, 10),
+                    frozen: false,
+                },
+                ConsensusState {
+                    timestamp: 100,
+                    root: b"root".to_vec(),
+                },
+            )
+            .call(&Addr::unchecked("dao"))
+    };
+
+    instantiate(attestor_set(&keys_a, 2).signers).unwrap();
+
+    let Key::Secp256k1(secp256k1_key) = &keys_a[0] else {
+        unreachable!()
+    };
+
+    // the same key twice, once uncompressed
+    let mut signers = attestor_set(&keys_a, 2).signers;
+    signers.push(PublicKey::Secp256k1(
+        secp256k1_key
+            .verifying_key()
+            .to_encoded_point(false)
+            .as_bytes()
+            .to_vec(),
+    ));
+
+    assert_eq!(
+        instantiate(signers).unwrap_err(),
+        ContractError::InvalidAttestorSet("invalid public key".to_string())
+    );
+
+    let mut signers = attestor_set(&keys_a, 2).signers;
+    signers.push(keys_a[0].public_key());
+
+    assert_eq!(
+        instantiate(signers).unwrap_err(),
+        ContractError::InvalidAttestorSet("duplicate signer".to_string())
+    );
+
+    let mut signers = attestor_set(&keys_a, 2).signers;
+    signers.push(PublicKey::Ed25519(vec![2; 31]));
+
+    assert_eq!(
+        instantiate(signers).unwrap_err(),
+        ContractError::InvalidAttestorSet("invalid public key".to_string())
+    );
+}
+
+#[rstest]
+fn test_attestor_state_update() {
+    let chain = App::default();
+
+    let relayer = Addr::unchecked("relayer");
+
+    let keys_a = keys(&[1, 2, 3]);
+
+    let lightclient = setup(&chain, &keys_a);
+
+    // two of three signers, mixing key types
+    let header = Header::State(state(11, 110, b"root-11", &keys_a, &[true, true, false]));
+
+    lightclient
+        .update(to_json_vec(&header).unwrap())
+        .call(&relayer)
+        .unwrap();
+
+    assert_eq!(lightclient.latest_height().unwrap(), Height::new(1, 11));
+    assert_eq!(lightclient.timestamp(Height::new(1, 11)).unwrap(), 110);
+    assert_eq!(lightclient.status().unwrap(), Status::Active);
+
+    let header = Header::State(state(12, 120, b"root-12", &keys_a, &[false, false, true]));
+
+    assert_eq!(
+        lightclient
+            .update(to_json_vec(&header).unwrap())
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::ThresholdNotMet {
+            signed: 1,
+            threshold: 2
+        }
+    );
+
+    let mut forged = state(12, 120, b"root-12", &keys_a, &[true, false, true]);
+    forged.root = b"forged".to_vec();
+
+    assert_eq!(
+        lightclient
+            .update(to_json_vec(&Header::State(forged)).unwrap())
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::InvalidSignature { index: 0 }
+    );
+
+    let mut missing = state(12, 120, b"root-12", &keys_a, &[true, false, true]);
+    missing.signatures.pop();
+
+    assert_eq!(
+        lightclient
+            .update(to_json_vec(&Header::State(missing)).unwrap())
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::SignatureCountMismatch {
+            signers: 3,
+            signatures: 2
+        }
+    );
+}
+
+#[rstest]
+fn test_attestor_rotation() {
+    let chain = App::default();
+
+    let relayer = Addr::unchecked("relayer");
+
+    let keys_a = keys(&[1, 2, 3]);
+    let keys_b = keys(&[4, 5, 6, 7]);
+
+    let lightclient = setup(&chain, &keys_a);
+
+    let rotation = Attestation::AttestorSet {
+        chain_id: CHAIN_ID.to_string(),
+        sequence: 0,
+        attestor_set: attestor_set(&keys_b, 3),
+    };
+
+    let header = Header::Rotation {
+        attestor_set: attestor_set(&keys_b, 3),
+        signatures: sign(&rotation, &keys_a, &[true, false, true]),
+    };
+
+    lightclient
+        .update(to_json_vec(&header).unwrap())
+        .call(&relayer)
+        .unwrap();
+
+    // replaying the rotation is signed by the old set over the old sequence
+    lightclient
+        .update(to_json_vec(&header).unwrap())
+        .call(&relayer)
+        .unwrap_err();
+
+    let header = Header::State(state(11, 110, b"root-11", &keys_a, &[true, true, true]));
+
+    lightclient
+        .update(to_json_vec(&header).unwrap())
+        .call(&relayer)
+        .unwrap_err();
+
+    let header = Header::State(state(
+        11,
+        110,
+        b"root-11",
+        &keys_b,
+        &[true, true, false, true],
+    ));
+
+    lightclient
+        .update(to_json_vec(&header).unwrap())
+        .call(&relayer)
+        .unwrap();
+
+    assert_eq!(lightclient.latest_height().unwrap(), Height::new(1, 11));
+
+    // a threshold that cannot be met is rejected
+    let invalid = Attestation::AttestorSet {
+        chain_id: CHAIN_ID.to_string(),
+        sequence: 1,
+        attestor_set: attestor_set(&keys_a, 4),
+    };
+
+    assert!(matches!(
+        lightclient
+            .update(
+                to_json_vec(&Header::Rotation {
+                    attestor_set: attestor_set(&keys_a, 4),
+                    signatures: sign(&invalid, &keys_b, &[true; 4]),
+                })
+                .unwrap()
+            )
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::InvalidAttestorSet(_)
+    ));
+}
+
+#[rstest]
+fn test_attestor_membership() {
+    let chain = App::default();
+
+    let relayer = Addr::unchecked("relayer");
+
+    let keys_a = keys(&[1, 2, 3]);
+
+    let (key_a, value_a) = (b"commitments/a".to_vec(), b"value-a".to_vec());
+    let (key_c, value_c) = (b"commitments/c".to_vec(), b"value-c".to_vec());
+
+    let (ibc_root, proof_a, _) = iavl_pair((&key_a, &value_a), (&key_c, &value_c));
+    let (app_hash, _, ibc_store) = multistore_pair((b"bank", b"bank-root"), (IBC_STORE, &ibc_root));
+
+    let lightclient = setup(&chain, &keys_a);
+
+    let header = Header::State(state(11, 110, &app_hash, &keys_a, &[true, true, true]));
+
+    lightclient
+        .update(to_json_vec(&header).unwrap())
+        .call(&relayer)
+        .unwrap();
+
+    let height = Height::new(1, 11);
+
+    // merkle proof against the attested root
+    let proof = to_json_vec(&MembershipProof::Merkle(merkle_proof(vec![
+        commitment_proof::Proof::Exist(proof_a),
+        commitment_proof::Proof::Exist(ibc_store),
+    ])))
+    .unwrap();
+
+    assert!(lightclient
+        .check_membership(
+            key_a.clone(),
+            value_a.clone(),
+            b"ibc".to_vec(),
+            height,
+            proof.clone(),
+        )
+        .unwrap());
+
+    lightclient
+        .check_membership(
+            key_a.clone(),
+            value_c.clone(),
+            b"ibc".to_vec(),
+            height,
+            proof,
+        )
+        .unwrap_err();
+
+    // commitments attested directly
+    let commitment = |key: &[u8], value: Option<&[u8]>| Attestation::Commitment {
+        chain_id: CHAIN_ID.to_string(),
+        height,
+        path: apply_prefix(b"ibc", key),
+        value: value.map(<[u8]>::to_vec),
+    };
+
+    let attested = |attestation: &Attestation| {
+        to_json_vec(&MembershipProof::Attested(sign(
+            attestation,
+            &keys_a,
+            &[false, true, true],
+        )))
+        .unwrap()
+    };
+
+    let proof = attested(&commitment(b"receipts/b", Some(b"value-b")));
+
+    assert!(lightclient
+        .check_membership(
+            b"receipts/b".to_vec(),
+            b"value-b".to_vec(),
+            b"ibc".to_vec(),
+            height,
+            proof.clone(),
+        )
+        .unwrap());
+
+    lightclient
+        .check_membership(
+            b"receipts/b".to_vec(),
+            b"value-c".to_vec(),
+            b"ibc".to_vec(),
+            height,
+            proof.clone(),
+        )
+        .unwrap_err();
+
+    // a membership attestation does not prove absence
+    lightclient
+        .check_non_membership(
+            b"receipts/b".to_vec(),
+            b"ibc".to_vec(),
+            height,
+            proof.clone(),
+        )
+        .unwrap_err();
+
+    // no attested state at the height
+    lightclient
+        .check_membership(
+            b"receipts/b".to_vec(),
+            b"value-b".to_vec(),
+            b"ibc".to_vec(),
+            Height::new(1, 12),
+            proof,
+        )
+        .unwrap_err();
+
+    assert!(lightclient
+        .check_non_membership(
+            b"receipts/d".to_vec(),
+            b"ibc".to_vec(),
+            height,
+            attested(&commitment(b"receipts/d", None)),
+        )
+        .unwrap());
+}
+
+#[rstest]
+fn test_attestor_misbehaviour() {
+    let chain = App::default();
+
+    let relayer = Addr::unchecked("relayer");
+
+    let keys_a = keys(&[1, 2, 3]);
+
+    let lightclient = setup(&chain, &keys_a);
+
+    let state_11 = state(11, 110, b"root-11", &keys_a, &[true, true, false]);
+    let conflicting = state(11, 110, b"fork-11", &keys_a, &[false, true, true]);
+
+    let no_misbehaviour = to_json_vec(&Misbehaviour {
+        attestation_1: state_11.clone(),
+        attestation_2: state_11.clone(),
+    })
+    .unwrap();
+
+    assert!(!lightclient
+        .check_for_misbehaviour(no_misbehaviour.clone())
+        .unwrap());
+
+    assert_eq!(
+        lightclient
+            .update_on_misbehaviour(no_misbehaviour)
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::NoMisbehaviour
+    );
+
+    let misbehaviour = to_json_vec(&Misbehaviour {
+        attestation_1: state_11.clone(),
+        attestation_2: conflicting.clone(),
+    })
+    .unwrap();
+
+    assert!(lightclient
+        .check_for_misbehaviour(misbehaviour.clone())
+        .unwrap());
+
+    lightclient
+        .update_on_misbehaviour(misbehaviour)
+        .call(&relayer)
+        .unwrap();
+
+    assert_eq!(lightclient.status().unwrap(), Status::Frozen);
+
+    assert_eq!(
+        lightclient
+            .update(to_json_vec(&Header::State(state_11.clone())).unwrap())
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::ClientFrozen
+    );
+
+    // a conflicting update freezes the client as well
+    let lightclient = setup(&chain, &keys_a);
+
+    lightclient
+        .update(to_json_vec(&Header::State(state_11)).unwrap())
+        .call(&relayer)
+        .unwrap();
+
+    lightclient
+        .update(to_json_vec(&Header::State(conflicting)).unwrap())
+        .call(&relayer)
+        .unwrap();
+
+    assert_eq!(lightclient.status().unwrap(), Status::Frozen);
+}
@@ -0,0 +1,251 @@
+use eureka_application_pingpong::sv::mt::{CodeId as AppCodeId, ContractProxy};
+use eureka_lightclient_dummy::sv::mt::CodeId as lightclientCodeId;
+use eureka_tao::sv::mt::{CodeId as TaoCodeId, ContractProxy as TaoContractProxy};
+use eureka_tao::{Packet, PacketHeader, Payload, PayloadHeader, SenderPolicy};
+use rstest::rstest;
+use sylvia::cw_std::Addr;
+use sylvia::multitest::App;
+
+use crate::factory::create_dummy;
+
+use crate::payload::sv::mt::CodeId as FaultyAppCodeId;
+
+#[rstest]
+fn test_unregistered_application_cannot_send() {
+    let chain_1 = App::default();
+
+    let lightclient_code_id = lightclientCodeId::store_code(&chain_1);
+    let tao_code_id = TaoCodeId::store_code(&chain_1);
+    let application_code_id = AppCodeId::store_code(&chain_1);
+
+    let gov = Addr::unchecked("gov-module");
+    let dao = Addr::unchecked("dao");
+    let alice = Addr::unchecked("alice");
+    let hacker = Addr::unchecked("hacker");
+
+    let tao_contract = tao_code_id.instantiate().call(&gov).unwrap();
+
+    tao_contract
+        .approve_lightclient_code(lightclient_code_id.code_id(), "dummy".to_string())
+        .call(&gov)
+        .unwrap();
+
+    let lightclient_1_contract = create_dummy(&tao_contract, &lightclient_code_id, &dao);
+    let lightclient_2_contract = create_dummy(&tao_contract, &lightclient_code_id, &dao);
+
+    let application_1_contract = application_code_id
+        .instantiate(tao_contract.contract_addr.clone())
+        .with_admin(alice.as_str())
+        .call(&alice)
+        .unwrap();
+    let application_2_contract = application_code_id
+        .instantiate(tao_contract.contract_addr.clone())
+        .with_admin(alice.as_str())
+        .call(&alice)
+        .unwrap();
+
+    let lightclient_1 = (lightclient_1_contract.contract_addr.clone(), vec![]);
+    let lightclient_2 = (lightclient_2_contract.contract_addr.clone(), vec![]);
+
+    application_1_contract
+        .set_allowed_channel(
+            lightclient_1.clone(),
+            lightclient_2.clone(),
+            application_2_contract.contract_addr.clone(),
+        )
+        .call(&alice)
+        .unwrap();
+
+    let packet = |nonce| Packet {
+        header: PacketHeader {
+            lightclient_source: lightclient_1.clone(),
+            lightclient_destination: lightclient_2.clone(),
+            nonce,
+            timeout: chain_1.block_info().time.seconds() + 10,
+        },
+        payloads: vec![Payload {
+            header: PayloadHeader {
+                application_source: application_1_contract.contract_addr.clone(),
+                application_destination: application_2_contract.contract_addr.clone(),
+                funds: vec![],
+                gas_limit: None,
+            },
+            data: b"ping".to_vec(),
+        }],
+    };
+
+    // the application accepts alice, but it is not registered at tao
+    tao_contract
+        .send_packet(packet(1), None)
+        .call(&alice)
+        .unwrap_err();
+
+    // only the application or its admin can register it
+    tao_contract
+        .register_application(
+            application_1_contract.contract_addr.clone(),
+            lightclient_1.clone(),
+            lightclient_2.clone(),
+            SenderPolicy::Any,
+        )
+        .call(&hacker)
+        .unwrap_err();
+
+    // registration is per connection
+    tao_contract
+        .register_application(
+            application_1_contract.contract_addr.clone(),
+            lightclient_2.clone(),
+            lightclient_1.clone(),
+            SenderPolicy::Any,
+        )
+        .call(&alice)
+        .unwrap();
+
+    tao_contract
+        .send_packet(packet(1), None)
+        .call(&alice)
+        .unwrap_err();
+
+    tao_contract
+        .register_application(
+            application_1_contract.contract_addr.clone(),
+            lightclient_1.clone(),
+            lightclient_2.clone(),
+            SenderPolicy::Any,
+        )
+        .call(&alice)
+        .unwrap();
+
+    assert_eq!(
+        tao_contract
+            .registered_application(
+                application_1_contract.contract_addr.clone(),
+                lightclient_1.clone(),
+                lightclient_2.clone(),
+            )
+            .unwrap(),
+        Some(SenderPolicy::Any)
+    );
+
+    tao_contract
+        .send_packet(packet(1), None)
+        .call(&alice)
+        .unwrap();
+
+    tao_contract
+        .unregister_application(
+            application_1_contract.contract_addr.clone(),
+            lightclient_1.clone(),
+            lightclient_2.clone(),
+        )
+        .call(&hacker)
+        .unwrap_err();
+
+    tao_contract
+        .unregister_application(
+            application_1_contract.contract_addr.clone(),
+            lightclient_1.clone(),
+            lightclient_2.clone(),
+        )
+        .call(&alice)
+        .unwrap();
+
+    tao_contract
+        .send_packet(packet(2), None)
+        .call(&alice)
+        .unwrap_err();
+}
+
+#[rstest]
+fn test_application_sender_allow_list() {
+    let chain_1 = App::default();
+
+    let lightclient_code_id = lightclientCodeId::store_code(&chain_1);
+    let tao_code_id = TaoCodeId::store_code(&chain_1);
+    let faulty_application_code_id = FaultyAppCodeId::store_code(&chain_1);
+
+    let gov = Addr::unchecked("gov-module");
+    let dao = Addr::unchecked("dao");
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+
+    let tao_contract = tao_code_id.instantiate().call(&gov).unwrap();
+
+    tao_contract
+        .approve_lightclient_code(lightclient_code_id.code_id(), "dummy".to_string())
+        .call(&gov)
+        .unwrap();
+
+    let lightclient_1_contract = create_dummy(&tao_contract, &lightclient_code_id, &dao);
+    let lightclient_2_contract = create_dummy(&tao_contract, &lightclient_code_id, &dao);
+
+    // the application itself accepts packets from anyone
+    let application_contract = faulty_application_code_id
+        .instantiate()
+        .with_admin(alice.as_str())
+        .call(&alice)
+        .unwrap();
+
+    let lightclient_1 = (lightclient_1_contract.contract_addr.clone(), vec![]);
+    let lightclient_2 = (lightclient_2_contract.contract_addr.clone(), vec![]);
+
+    let packet = |nonce| Packet {
+        header: PacketHeader {
+            lightclient_source: lightclient_1.clone(),
+            lightclient_destination: lightclient_2.clone(),
+            nonce,
+            timeout: chain_1.block_info().time.seconds() + 10,
+        },
+        payloads: vec![Payload {
+            header: PayloadHeader {
+                application_source: application_contract.contract_addr.clone(),
+                application_destination: application_contract.contract_addr.clone(),
+                funds: vec![],
+                gas_limit: None,
+            },
+            data: b"ping".to_vec(),
+        }],
+    };
+
+    tao_contract
+        .register_application(
+            application_contract.contract_addr.clone(),
+            lightclient_1.clone(),
+            lightclient_2.clone(),
+            SenderPolicy::AllowList(vec![alice.clone()]),
+        )
+        .call(&alice)
+        .unwrap();
+
+    tao_contract
+        .send_packet(packet(1), None)
+        .call(&bob)
+        .unwrap_err();
+
+    tao_contract
+        .send_packet(packet(1), None)
+        .call(&alice)
+        .unwrap();
+
+    // the application itself is always allowed
+    tao_contract
+        .send_packet(packet(2), None)
+        .call(&application_contract.contract_addr)
+        .unwrap();
+
+    tao_contract
+        .register_application(
+            application_contract.contract_addr.clone(),
+            lightclient_1.clone(),
+            lightclient_2.clone(),
+            SenderPolicy::Any,
+        )
+        .call(&alice)
+        .unwrap();
+
+    tao_contract
+        .send_packet(packet(3), None)
+        .call(&bob)
+        .unwrap();
+}
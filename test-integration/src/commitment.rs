@@ -0,0 +1,320 @@
+use eureka_application_interface::PayloadResult;
+use eureka_application_pingpong::sv::mt::{CodeId as AppCodeId, ContractProxy};
+use eureka_lightclient_interface::sv::mt::LightClientProxy;
+use eureka_lightclient_interface::Height;
+use eureka_lightclient_mock::sv::mt::CodeId as MockCodeId;
+use eureka_lightclient_mock::sv::InstantiateMsg as MockInstantiateMsg;
+use eureka_lightclient_mock::{
+    Commitment, Contract as MockContract, ContractError as MockError, Header,
+};
+use eureka_tao::commitment::{
+    connection_key, packet_acknowledgement_path, packet_commitment, packet_commitment_path,
+    packet_receipt_path, RECEIPT,
+};
+use eureka_tao::sv::mt::{CodeId as TaoCodeId, ContractProxy as TaoContractProxy};
+use eureka_tao::{Acknowledgement, Packet, PacketHeader, Payload, PayloadHeader, SenderPolicy};
+use rstest::rstest;
+use sylvia::cw_std::{to_json_vec, Addr};
+use sylvia::multitest::{App, Proxy};
+
+use crate::factory::create_lightclient;
+
+#[rstest]
+fn test_packet_commitments() {
+    let chain_1 = App::default();
+
+    let lightclient_code_id = MockCodeId::store_code(&chain_1);
+    let tao_code_id = TaoCodeId::store_code(&chain_1);
+    let application_code_id = AppCodeId::store_code(&chain_1);
+
+    let gov = Addr::unchecked("gov-module");
+    let dao = Addr::unchecked("dao");
+    let alice = Addr::unchecked("alice");
+    let relayer = Addr::unchecked("relayer");
+
+    let tao_contract = tao_code_id.instantiate().call(&gov).unwrap();
+
+    tao_contract
+        .approve_lightclient_code(lightclient_code_id.code_id(), "mock".to_string())
+        .call(&gov)
+        .unwrap();
+
+    let create_mock = || -> Proxy<_, MockContract> {
+        create_lightclient(
+            &tao_contract,
+            &dao,
+            lightclient_code_id.code_id(),
+            b"ibc",
+            &MockInstantiateMsg::new(vec![relayer.clone()]),
+        )
+    };
+
+    let lightclient_1_contract = create_mock();
+    let lightclient_2_contract = create_mock();
+
+    let application_1_contract = application_code_id
+        .instantiate(tao_contract.contract_addr.clone())
+        .with_admin(alice.as_str())
+        .call(&alice)
+        .unwrap();
+    let application_2_contract = application_code_id
+        .instantiate(tao_contract.contract_addr.clone())
+        .with_admin(alice.as_str())
+        .call(&alice)
+        .unwrap();
+
+    let lightclient_1 = (
+        lightclient_1_contract.contract_addr.clone(),
+        b"ibc".to_vec(),
+    );
+    let lightclient_2 = (
+        lightclient_2_contract.contract_addr.clone(),
+        b"ibc".to_vec(),
+    );
+
+    application_1_contract
+        .set_allowed_channel(
+            lightclient_1.clone(),
+            lightclient_2.clone(),
+            application_2_contract.contract_addr.clone(),
+        )
+        .call(&alice)
+        .unwrap();
+
+    application_2_contract
+        .set_allowed_channel(
+            lightclient_2.clone(),
+            lightclient_1.clone(),
+            application_1_contract.contract_addr.clone(),
+        )
+        .call(&alice)
+        .unwrap();
+
+    tao_contract
+        .register_application(
+            application_1_contract.contract_addr.clone(),
+            lightclient_1.clone(),
+            lightclient_2.clone(),
+            SenderPolicy::Any,
+        )
+        .call(&alice)
+        .unwrap();
+
+    let connection = connection_key(&lightclient_1, &lightclient_2);
+
+    let now = chain_1.block_info().time.seconds();
+
+    let packet = |nonce, data: &[u8]| Packet {
+        header: PacketHeader {
+            lightclient_source: lightclient_1.clone(),
+            lightclient_destination: lightclient_2.clone(),
+            nonce,
+            timeout: now + 10,
+        },
+        payloads: vec![Payload {
+            header: PayloadHeader {
+                application_source: application_1_contract.contract_addr.clone(),
+                application_destination: application_2_contract.contract_addr.clone(),
+                funds: vec![],
+                gas_limit: None,
+            },
+            data: data.to_vec(),
+        }],
+    };
+
+    // the commitments found in the tao storage, as a relayer would prove them
+    let header = |height, timestamp, paths: &[Vec<u8>]| {
+        to_json_vec(&Header {
+            height: Height::new(0, height),
+            timestamp,
+            commitments: paths
+                .iter()
+                .filter_map(|path| {
+                    chain_1
+                        .querier()
+                        .query_wasm_raw(&tao_contract.contract_addr, path.clone())
+                        .unwrap()
+                        .map(|value| Commitment {
+                            prefix: b"ibc".to_vec(),
+                            key: path.clone(),
+                            value,
+                        })
+                })
+                .collect(),
+        })
+        .unwrap()
+    };
+
+    let packet_1 = packet(1, b"ping");
+
+    tao_contract
+        .send_packet(packet_1.clone(), None)
+        .call(&alice)
+        .unwrap();
+
+    assert_eq!(
+        chain_1
+            .querier()
+            .query_wasm_raw(
+                &tao_contract.contract_addr,
+                packet_commitment_path(&connection, 1)
+            )
+            .unwrap(),
+        Some(packet_commitment(&packet_1).unwrap())
+    );
+
+    lightclient_1_contract
+        .update(header(1, now, &[packet_commitment_path(&connection, 1)]))
+        .call(&relayer)
+        .unwrap();
+
+    // a packet that was not sent as such
+    let error = tao_contract
+        .receive_packet(packet(1, b"forged"), Height::new(0, 1), vec![])
+        .call(&relayer)
+        .unwrap_err();
+
+    assert!(error.to_string().ends_with(
+        &MockError::CommitmentValueMismatch {
+            key: String::from_utf8(packet_commitment_path(&connection, 1)).unwrap()
+        }
+        .to_string()
+    ));
+
+    let error = tao_contract
+        .receive_packet(packet(2, b"ping"), Height::new(0, 1), vec![])
+        .call(&relayer)
+        .unwrap_err();
+
+    assert!(error.to_string().ends_with(
+        &MockError::CommitmentNotFound {
+            key: String::from_utf8(packet_commitment_path(&connection, 2)).unwrap()
+        }
+        .to_string()
+    ));
+
+    tao_contract
+        .receive_packet(packet_1.clone(), Height::new(0, 1), vec![])
+        .call(&relayer)
+        .unwrap();
+
+    lightclient_2_contract
+        .update(header(
+            1,
+            now,
+            &[
+                packet_receipt_path(&connection, 1),
+                packet_acknowledgement_path(&connection, 1),
+            ],
+        ))
+        .call(&relayer)
+        .unwrap();
+
+    let acknowledgement = tao_contract
+        .packet_acknowledgement(lightclient_1.clone(), lightclient_2.clone(), 1)
+        .unwrap()
+        .unwrap();
+
+    let forged = Acknowledgement {
+        relayer: relayer.clone(),
+        results: vec![PayloadResult::Error("forged".to_string())],
+    };
+
+    let error = tao_contract
+        .acknowledge_packet(packet_1.clone(), forged, Height::new(0, 1), vec![])
+        .call(&relayer)
+        .unwrap_err();
+
+    assert!(error.to_string().ends_with(
+        &MockError::CommitmentValueMismatch {
+            key: String::from_utf8(packet_acknowledgement_path(&connection, 1)).unwrap()
+        }
+        .to_string()
+    ));
+
+    tao_contract
+        .acknowledge_packet(packet_1, acknowledgement, Height::new(0, 1), vec![])
+        .call(&relayer)
+        .unwrap();
+
+    // the commitment is cleared once acknowledged
+    assert_eq!(
+        chain_1
+            .querier()
+            .query_wasm_raw(
+                &tao_contract.contract_addr,
+                packet_commitment_path(&connection, 1)
+            )
+            .unwrap(),
+        None
+    );
+
+    let packet_2 = packet(2, b"ping");
+
+    tao_contract
+        .send_packet(packet_2.clone(), None)
+        .call(&alice)
+        .unwrap();
+
+    // a receipt at the destination prevents the timeout
+    lightclient_2_contract
+        .update(
+            to_json_vec(&Header {
+                height: Height::new(0, 2),
+                timestamp: now + 20,
+                commitments: vec![Commitment {
+                    prefix: b"ibc".to_vec(),
+                    key: packet_receipt_path(&connection, 2),
+                    value: RECEIPT.to_vec(),
+                }],
+            })
+            .unwrap(),
+        )
+        .call(&relayer)
+        .unwrap();
+
+    let error = tao_contract
+        .timeout_packet(packet_2.clone(), Height::new(0, 2), vec![])
+        .call(&relayer)
+        .unwrap_err();
+
+    assert!(error.to_string().ends_with(
+        &MockError::UnexpectedCommitment {
+            key: String::from_utf8(packet_receipt_path(&connection, 2)).unwrap()
+        }
+        .to_string()
+    ));
+
+    lightclient_2_contract
+        .update(header(3, now + 20, &[packet_receipt_path(&connection, 2)]))
+        .call(&relayer)
+        .unwrap();
+
+    tao_contract
+        .timeout_packet(packet_2, Height::new(0, 3), vec![])
+        .call(&relayer)
+        .unwrap();
+}
+
+#[rstest]
+fn test_connection_key() {
+    let connection = connection_key(
+        &(Addr::unchecked("client-1"), b"ibc".to_vec()),
+        &(Addr::unchecked("client-2"), vec![]),
+    );
+
+    // pinned, as any change would break the proofs of the packets in flight
+    assert_eq!(connection, "636c69656e742d31-696263-636c69656e742d32-");
+
+    // separators within the parts are not confused with the ones between them
+    assert_ne!(
+        connection_key(
+            &(Addr::unchecked("client-1"), b"x".to_vec()),
+            &(Addr::unchecked("client-2"), vec![]),
+        ),
+        connection_key(
+            &(Addr::unchecked("client"), b"1-x".to_vec()),
+            &(Addr::unchecked("client-2"), vec![]),
+        )
+    );
+}
@@ -0,0 +1,465 @@
+use eureka_lightclient_ethereum::sv::mt::CodeId as EthereumCodeId;
+use eureka_lightclient_ethereum::sv::InstantiateMsg;
+use eureka_lightclient_ethereum::{
+    ClientState, Contract, ContractError, ForkName, Misbehaviour, StorageProof, Update,
+};
+use eureka_lightclient_interface::sv::mt::LightClientProxy;
+use eureka_lightclient_interface::{Height, Status, UpdatePolicy};
+use rstest::rstest;
+use serde::Deserialize;
+use sylvia::cw_multi_test::BasicApp;
+use sylvia::cw_std::{from_json, to_json_vec, Addr, HexBinary};
+use sylvia::multitest::{App, Proxy};
+
+use crate::factory::query_error;
+
+// synthetic fixtures of a minimal preset chain, with a sync committee of 32 and periods of
+// 64 slots: the client is trusted at slot 40, `update_1` finalizes slot 48 and carries the
+// committee of period 1, which signs `update_2` finalizing slot 72; deneb activates at epoch 7
+// and electra at epoch 9, so `update_1` finalizes a capella block and `update_2` is attested
+// under the electra state layout
+// regenerate them with `cargo run --manifest-path
+// test-integration/fixtures/ethereum/generator/Cargo.toml -- test-integration/fixtures/ethereum`
+const INSTANTIATE: &str = include_str!("../fixtures/ethereum/instantiate.json");
+const UPDATE_1: &str = include_str!("../fixtures/ethereum/update_1.json");
+const UPDATE_2: &str = include_str!("../fixtures/ethereum/update_2.json");
+const MISBEHAVIOUR: &str = include_str!("../fixtures/ethereum/misbehaviour.json");
+const PROOFS: &str = include_str!("../fixtures/ethereum/proofs.json");
+
+#[derive(Deserialize)]
+struct Proofs {
+    commitment_prefix: HexBinary,
+    height: u64,
+    membership: MembershipProof,
+    non_membership: NonMembershipProof,
+}
+
+#[derive(Deserialize)]
+struct MembershipProof {
+    key: HexBinary,
+    value: HexBinary,
+    proof: StorageProof,
+}
+
+#[derive(Deserialize)]
+struct NonMembershipProof {
+    key: HexBinary,
+    proof: StorageProof,
+}
+
+// flips the lowest bit of the first byte
+fn flip(bytes: &mut HexBinary) {
+    let mut flipped = bytes.to_vec();
+    flipped[0] ^= 1;
+    *bytes = HexBinary::from(flipped);
+}
+
+fn update(fixture: &str) -> Update {
+    from_json(fixture).unwrap()
+}
+
+fn setup(chain: &App<BasicApp>) -> Proxy<'_, BasicApp, Contract> {
+    let InstantiateMsg {
+        client_state,
+        consensus_state,
+        current_sync_committee,
+        next_sync_committee,
+    } = from_json(INSTANTIATE).unwrap();
+
+    EthereumCodeId::store_code(chain)
+        .instantiate(
+            client_state,
+            consensus_state,
+            current_sync_committee,
+            next_sync_committee,
+        )
+        .call(&Addr::unchecked("dao"))
+        .unwrap()
+}
+
+#[rstest]
+fn test_ethereum_update() {
+    let chain = App::default();
+
+    let relayer = Addr::unchecked("relayer");
+
+    let lightclient = setup(&chain);
+
+    let client_state: ClientState = from_json(lightclient.client_state().unwrap().data).unwrap();
+    let (update_1, update_2) = (update(UPDATE_1), update(UPDATE_2));
+
+    assert_eq!(lightclient.latest_height().unwrap(), Height::new(0, 40));
+    assert_eq!(lightclient.status().unwrap(), Status::Active);
+    assert_eq!(
+        lightclient.update_policy().unwrap(),
+        UpdatePolicy::Permissionless
+    );
+
+    // the committee of period 1 is not known yet
+    assert_eq!(
+        lightclient
+            .update(to_json_vec(&update_2).unwrap())
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::NextSyncCommitteeUnknown { period: 1 }
+    );
+
+    lightclient
+        .update(to_json_vec(&update_1).unwrap())
+        .call(&relayer)
+        .unwrap();
+
+    // resubmitting the same update is a no-op
+    lightclient
+        .update(to_json_vec(&update_1).unwrap())
+        .call(&relayer)
+        .unwrap();
+
+    assert_eq!(lightclient.latest_height().unwrap(), Height::new(0, 48));
+    assert_eq!(
+        lightclient.timestamp(Height::new(0, 48)).unwrap(),
+        client_state.genesis_time + 48 * client_state.seconds_per_slot
+    );
+    assert_eq!(
+        lightclient
+            .consensus_state(Height::new(0, 48))
+            .unwrap()
+            .root,
+        update_1.finalized_header.execution_state_root.to_vec()
+    );
+
+    // signed by the committee of period 1, under the next fork
+    lightclient
+        .update(to_json_vec(&update_2).unwrap())
+        .call(&relayer)
+        .unwrap();
+
+    assert_eq!(lightclient.latest_height().unwrap(), Height::new(0, 72));
+    assert_eq!(
+        lightclient.client_state().unwrap().latest_height,
+        Height::new(0, 72)
+    );
+}
+
+#[rstest]
+fn test_ethereum_update_batch() {
+    let chain = App::default();
+
+    let relayer = Addr::unchecked("relayer");
+
+    let lightclient = setup(&chain);
+
+    // the committee of period 1 is learnt from the first update of the batch
+    lightclient
+        .update_batch(vec![
+            UPDATE_1.as_bytes().to_vec(),
+            UPDATE_2.as_bytes().to_vec(),
+        ])
+        .call(&relayer)
+        .unwrap();
+
+    assert_eq!(lightclient.latest_height().unwrap(), Height::new(0, 72));
+    assert_eq!(lightclient.timestamp(Height::new(0, 48)).unwrap(), {
+        let client_state: ClientState =
+            from_json(lightclient.client_state().unwrap().data).unwrap();
+        client_state.genesis_time + 48 * client_state.seconds_per_slot
+    });
+}
+
+#[rstest]
+#[case::signature(
+    |update: &mut Update| flip(&mut update.sync_aggregate.sync_committee_bits),
+    ContractError::InvalidSignature
+)]
+#[case::forged_header(
+    |update: &mut Update| update.attested_header.beacon.proposer_index += 1,
+    ContractError::InvalidSignature
+)]
+#[case::participation(
+    |update: &mut Update| update.sync_aggregate.sync_committee_bits = HexBinary::from(vec![0xff, 0xff, 0x0f, 0]),
+    ContractError::InsufficientParticipation { participants: 20, committee: 32 }
+)]
+#[case::bits(
+    |update: &mut Update| update.sync_aggregate.sync_committee_bits = HexBinary::from(vec![0xff; 3]),
+    ContractError::SyncCommitteeBitsMismatch { committee: 32, bits: 24 }
+)]
+#[case::finality_branch(
+    |update: &mut Update| flip(&mut update.finality_branch[0]),
+    ContractError::InvalidMerkleBranch { gindex: 105 }
+)]
+#[case::next_sync_committee_branch(
+    |update: &mut Update| flip(&mut update.next_sync_committee_branch[0]),
+    ContractError::InvalidMerkleBranch { gindex: 55 }
+)]
+#[case::execution_branch(
+    |update: &mut Update| flip(&mut update.finalized_header.execution_state_root),
+    ContractError::InvalidMerkleBranch { gindex: 402 }
+)]
+#[case::slots(
+    |update: &mut Update| update.signature_slot = update.attested_header.beacon.slot,
+    ContractError::InvalidUpdateSlots { signature_slot: 58, attested_slot: 58, finalized_slot: 48 }
+)]
+fn test_ethereum_invalid_update(#[case] tamper: fn(&mut Update), #[case] error: ContractError) {
+    let chain = App::default();
+
+    let lightclient = setup(&chain);
+
+    let mut update_1 = update(UPDATE_1);
+    tamper(&mut update_1);
+
+    assert_eq!(
+        lightclient
+            .update(to_json_vec(&update_1).unwrap())
+            .call(&Addr::unchecked("relayer"))
+            .unwrap_err(),
+        error
+    );
+
+    assert_eq!(lightclient.latest_height().unwrap(), Height::new(0, 40));
+}
+
+#[rstest]
+// a client unaware of capella proves the execution payload of `update_1` against the deneb body
+#[case::capella(0, ContractError::InvalidMerkleBranch { gindex: 802 }, Height::new(0, 40))]
+// a client unaware of electra proves `update_2` against the deneb state layout
+#[case::electra(2, ContractError::InvalidMerkleBranch { gindex: 105 }, Height::new(0, 48))]
+fn test_ethereum_fork_gindices(
+    #[case] fork: usize,
+    #[case] error: ContractError,
+    #[case] latest_height: Height,
+) {
+    let chain = App::default();
+
+    let relayer = Addr::unchecked("relayer");
+
+    let InstantiateMsg {
+        mut client_state,
+        consensus_state,
+        current_sync_committee,
+        next_sync_committee,
+    } = from_json(INSTANTIATE).unwrap();
+    client_state.forks[fork].name = ForkName::Deneb;
+
+    let lightclient = EthereumCodeId::store_code(&chain)
+        .instantiate(
+            client_state,
+            consensus_state,
+            current_sync_committee,
+            next_sync_committee,
+        )
+        .call(&Addr::unchecked("dao"))
+        .unwrap();
+
+    let err = [UPDATE_1, UPDATE_2]
+        .iter()
+        .find_map(|update| {
+            lightclient
+                .update(update.as_bytes().to_vec())
+                .call(&relayer)
+                .err()
+        })
+        .unwrap();
+
+    assert_eq!(err, error);
+    assert_eq!(lightclient.latest_height().unwrap(), latest_height);
+}
+
+#[rstest]
+fn test_ethereum_expiry() {
+    let chain = App::default();
+
+    let lightclient = setup(&chain);
+
+    let trusting_period = lightclient.trusting_period().unwrap();
+
+    chain.update_block(|block| block.time = block.time.plus_seconds(trusting_period));
+
+    assert_eq!(lightclient.status().unwrap(), Status::Expired);
+
+    assert!(matches!(
+        lightclient
+            .update(UPDATE_1.as_bytes().to_vec())
+            .call(&Addr::unchecked("relayer"))
+            .unwrap_err(),
+        ContractError::TrustedConsensusStateExpired { .. }
+    ));
+}
+
+#[rstest]
+fn test_ethereum_misbehaviour() {
+    let chain = App::default();
+
+    let relayer = Addr::unchecked("relayer");
+
+    let lightclient = setup(&chain);
+
+    let misbehaviour: Misbehaviour = from_json(MISBEHAVIOUR).unwrap();
+
+    let no_misbehaviour = to_json_vec(&Misbehaviour {
+        update_1: misbehaviour.update_1.clone(),
+        update_2: misbehaviour.update_1.clone(),
+    })
+    .unwrap();
+
+    assert!(!lightclient
+        .check_for_misbehaviour(no_misbehaviour.clone())
+        .unwrap());
+
+    assert_eq!(
+        lightclient
+            .update_on_misbehaviour(no_misbehaviour)
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::NoMisbehaviour
+    );
+
+    lightclient
+        .update(to_json_vec(&misbehaviour.update_1).unwrap())
+        .call(&relayer)
+        .unwrap();
+
+    // a conflicting finalized header cannot replace the trusted one
+    assert_eq!(
+        lightclient
+            .update(to_json_vec(&misbehaviour.update_2).unwrap())
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::NonIncreasingSlot {
+            latest_slot: 48,
+            finalized_slot: 48
+        }
+    );
+
+    assert!(lightclient
+        .check_for_misbehaviour(MISBEHAVIOUR.as_bytes().to_vec())
+        .unwrap());
+
+    lightclient
+        .update_on_misbehaviour(MISBEHAVIOUR.as_bytes().to_vec())
+        .call(&relayer)
+        .unwrap();
+
+    assert_eq!(lightclient.status().unwrap(), Status::Frozen);
+
+    assert_eq!(
+        lightclient
+            .update(UPDATE_2.as_bytes().to_vec())
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::ClientFrozen { slot: 48 }
+    );
+}
+
+#[rstest]
+fn test_ethereum_membership() {
+    let chain = App::default();
+
+    let lightclient = setup(&chain);
+
+    lightclient
+        .update(UPDATE_1.as_bytes().to_vec())
+        .call(&Addr::unchecked("relayer"))
+        .unwrap();
+
+    let Proofs {
+        commitment_prefix,
+        height,
+        membership,
+        non_membership,
+    } = from_json(PROOFS).unwrap();
+
+    let height = Height::new(0, height);
+    let membership_proof = to_json_vec(&membership.proof).unwrap();
+    let non_membership_proof = to_json_vec(&non_membership.proof).unwrap();
+
+    assert!(lightclient
+        .check_membership(
+            membership.key.to_vec(),
+            membership.value.to_vec(),
+            commitment_prefix.to_vec(),
+            height,
+            membership_proof.clone(),
+        )
+        .unwrap());
+
+    assert!(lightclient
+        .check_non_membership(
+            non_membership.key.to_vec(),
+            commitment_prefix.to_vec(),
+            height,
+            non_membership_proof.clone(),
+        )
+        .unwrap());
+
+    // wrong value
+    assert_eq!(
+        lightclient
+            .check_membership(
+                membership.key.to_vec(),
+                b"value".to_vec(),
+                commitment_prefix.to_vec(),
+                height,
+                membership_proof.clone(),
+            )
+            .unwrap_err()
+            .to_string(),
+        query_error(ContractError::StorageValueMismatch {
+            key: String::from_utf8(membership.key.to_vec()).unwrap()
+        })
+    );
+
+    // existing keys cannot be proven absent
+    assert_eq!(
+        lightclient
+            .check_non_membership(
+                membership.key.to_vec(),
+                commitment_prefix.to_vec(),
+                height,
+                membership_proof.clone(),
+            )
+            .unwrap_err()
+            .to_string(),
+        query_error(ContractError::UnexpectedStorageValue {
+            key: String::from_utf8(membership.key.to_vec()).unwrap()
+        })
+    );
+
+    // absent keys cannot be proven present
+    assert_eq!(
+        lightclient
+            .check_membership(
+                non_membership.key.to_vec(),
+                membership.value.to_vec(),
+                commitment_prefix.to_vec(),
+                height,
+                non_membership_proof,
+            )
+            .unwrap_err()
+            .to_string(),
+        query_error(ContractError::StorageValueNotFound {
+            key: String::from_utf8(non_membership.key.to_vec()).unwrap()
+        })
+    );
+
+    // the storage slot of another prefix is not on the proven path
+    lightclient
+        .check_membership(
+            membership.key.to_vec(),
+            membership.value.to_vec(),
+            b"other".to_vec(),
+            height,
+            membership_proof.clone(),
+        )
+        .unwrap_err();
+
+    // the proof is against another execution state root
+    lightclient
+        .check_membership(
+            membership.key.to_vec(),
+            membership.value.to_vec(),
+            commitment_prefix.to_vec(),
+            Height::new(0, 40),
+            membership_proof,
+        )
+        .unwrap_err();
+}
@@ -0,0 +1,272 @@
+use std::fmt::Display;
+
+use eureka_application_pingpong::sv::mt::{CodeId as AppCodeId, ContractProxy};
+use eureka_lightclient_dummy::sv::mt::CodeId as DummyCodeId;
+use eureka_lightclient_dummy::sv::InstantiateMsg as DummyInstantiateMsg;
+use eureka_lightclient_dummy::Contract as DummyContract;
+use eureka_lightclient_interface::{ClientState, ConsensusState, Height};
+use eureka_tao::sv::mt::{CodeId as TaoCodeId, ContractProxy as TaoContractProxy};
+use eureka_tao::{
+    Contract as TaoContract, ContractError, LightClientInfo, Packet, PacketHeader, Payload,
+    PayloadHeader, SenderPolicy,
+};
+use rstest::rstest;
+use serde::Serialize;
+use sylvia::cw_multi_test::BasicApp;
+use sylvia::cw_std::{to_json_binary, Addr};
+use sylvia::multitest::{App, Proxy};
+
+// light client created by `creator` through the tao factory, its code must be approved
+// packets name it with `commitment_prefix`
+pub fn create_lightclient<'a, C>(
+    tao_contract: &Proxy<'a, BasicApp, TaoContract>,
+    creator: &Addr,
+    code_id: u64,
+    commitment_prefix: &[u8],
+    msg: &impl Serialize,
+) -> Proxy<'a, BasicApp, C> {
+    let response = tao_contract
+        .create_lightclient(
+            code_id,
+            to_json_binary(msg).unwrap(),
+            "lightclient".to_string(),
+            commitment_prefix.to_vec(),
+        )
+        .call(creator)
+        .unwrap();
+
+    let lightclient = response
+        .events
+        .iter()
+        .flat_map(|event| &event.attributes)
+        .find(|attribute| attribute.key == "lightclient")
+        .unwrap()
+        .value
+        .clone();
+
+    Proxy::new(Addr::unchecked(lightclient), tao_contract.app)
+}
+
+// dummy light client states at height 0-0, with a consensus state at the current block time
+pub fn dummy_states(chain: &App<BasicApp>) -> (ClientState, ConsensusState) {
+    (
+        ClientState {
+            chain_id: String::new(),
+            latest_height: Height::default(),
+            frozen_height: None,
+            data: vec![],
+        },
+        ConsensusState {
+            timestamp: chain.block_info().time.seconds(),
+            root: vec![],
+            data: vec![],
+        },
+    )
+}
+
+// error of a failed query, as seen through the querier
+pub fn query_error(error: impl Display) -> String {
+    format!("Generic error: Querier contract error: {error}")
+}
+
+// dummy light client owned by `creator`, with an empty commitment prefix
+pub fn create_dummy<'a>(
+    tao_contract: &Proxy<'a, BasicApp, TaoContract>,
+    code_id: &DummyCodeId<'a, DummyContract, BasicApp>,
+    creator: &Addr,
+) -> Proxy<'a, BasicApp, DummyContract> {
+    create_lightclient(tao_contract, creator, code_id.code_id(), b"", &{
+        let (client_state, consensus_state) = dummy_states(tao_contract.app);
+        DummyInstantiateMsg::new(client_state, consensus_state, creator.clone())
+    })
+}
+
+#[rstest]
+fn test_lightclient_factory() {
+    let chain_1 = App::default();
+
+    let lightclient_code_id = DummyCodeId::store_code(&chain_1);
+    let tao_code_id = TaoCodeId::store_code(&chain_1);
+    let application_code_id = AppCodeId::store_code(&chain_1);
+
+    let gov = Addr::unchecked("gov-module");
+    let dao = Addr::unchecked("dao");
+    let alice = Addr::unchecked("alice");
+    let relayer = Addr::unchecked("relayer");
+    let hacker = Addr::unchecked("hacker");
+
+    let tao_contract = tao_code_id.instantiate().call(&gov).unwrap();
+
+    let code_id = lightclient_code_id.code_id();
+    let (client_state, consensus_state) = dummy_states(&chain_1);
+    let msg = to_json_binary(&DummyInstantiateMsg::new(
+        client_state.clone(),
+        consensus_state.clone(),
+        dao.clone(),
+    ))
+    .unwrap();
+
+    assert_eq!(
+        tao_contract
+            .create_lightclient(code_id, msg.clone(), "lightclient".to_string(), vec![])
+            .call(&dao)
+            .unwrap_err(),
+        ContractError::LightClientCodeNotApproved { code_id }
+    );
+
+    assert_eq!(
+        tao_contract
+            .approve_lightclient_code(code_id, "dummy".to_string())
+            .call(&hacker)
+            .unwrap_err(),
+        ContractError::Unauthorized
+    );
+
+    tao_contract
+        .approve_lightclient_code(code_id, "dummy".to_string())
+        .call(&gov)
+        .unwrap();
+
+    assert_eq!(
+        tao_contract.approved_lightclient_code(code_id).unwrap(),
+        Some("dummy".to_string())
+    );
+
+    let lightclient_1_contract = create_dummy(&tao_contract, &lightclient_code_id, &dao);
+    let lightclient_2_contract = create_dummy(&tao_contract, &lightclient_code_id, &dao);
+
+    assert_eq!(
+        tao_contract
+            .lightclient_info(lightclient_1_contract.contract_addr.clone())
+            .unwrap(),
+        Some(LightClientInfo {
+            creator: dao.clone(),
+            code_id,
+            client_type: "dummy".to_string(),
+            commitment_prefix: vec![],
+        })
+    );
+
+    // only tao can migrate the light clients it created
+    assert_eq!(
+        chain_1
+            .querier()
+            .query_wasm_contract_info(&lightclient_1_contract.contract_addr)
+            .unwrap()
+            .admin,
+        Some(tao_contract.contract_addr.clone())
+    );
+
+    // an always-true light client of the hacker, outside of the factory
+    let hacker_contract = lightclient_code_id
+        .instantiate(client_state, consensus_state, hacker.clone())
+        .call(&hacker)
+        .unwrap();
+
+    let application_1_contract = application_code_id
+        .instantiate(tao_contract.contract_addr.clone())
+        .with_admin(alice.as_str())
+        .call(&alice)
+        .unwrap();
+    let application_2_contract = application_code_id
+        .instantiate(tao_contract.contract_addr.clone())
+        .with_admin(alice.as_str())
+        .call(&alice)
+        .unwrap();
+
+    let lightclient_1 = (lightclient_1_contract.contract_addr.clone(), vec![]);
+    let lightclient_2 = (lightclient_2_contract.contract_addr.clone(), vec![]);
+    let lightclient_hacker = (hacker_contract.contract_addr.clone(), vec![]);
+
+    for lightclient_destination in [&lightclient_2, &lightclient_hacker] {
+        application_1_contract
+            .set_allowed_channel(
+                lightclient_1.clone(),
+                lightclient_destination.clone(),
+                application_2_contract.contract_addr.clone(),
+            )
+            .call(&alice)
+            .unwrap();
+
+        tao_contract
+            .register_application(
+                application_1_contract.contract_addr.clone(),
+                lightclient_1.clone(),
+                lightclient_destination.clone(),
+                SenderPolicy::Any,
+            )
+            .call(&alice)
+            .unwrap();
+    }
+
+    let packet = |lightclient_source: &(Addr, Vec<u8>),
+                  lightclient_destination: &(Addr, Vec<u8>)| Packet {
+        header: PacketHeader {
+            lightclient_source: lightclient_source.clone(),
+            lightclient_destination: lightclient_destination.clone(),
+            nonce: 1,
+            timeout: chain_1.block_info().time.seconds() + 10,
+        },
+        payloads: vec![Payload {
+            header: PayloadHeader {
+                application_source: application_1_contract.contract_addr.clone(),
+                application_destination: application_2_contract.contract_addr.clone(),
+                funds: vec![],
+                gas_limit: None,
+            },
+            data: b"ping".to_vec(),
+        }],
+    };
+
+    let not_registered = ContractError::LightClientNotRegistered {
+        lightclient: hacker_contract.contract_addr.clone(),
+    };
+
+    assert_eq!(
+        tao_contract
+            .send_packet(packet(&lightclient_1, &lightclient_hacker), None)
+            .call(&alice)
+            .unwrap_err(),
+        not_registered
+    );
+
+    assert_eq!(
+        tao_contract
+            .receive_packet(
+                packet(&lightclient_hacker, &lightclient_2),
+                Height::default(),
+                vec![]
+            )
+            .call(&relayer)
+            .unwrap_err(),
+        not_registered
+    );
+
+    // revoking a code only stops new light clients
+    tao_contract
+        .revoke_lightclient_code(code_id)
+        .call(&gov)
+        .unwrap();
+
+    assert_eq!(
+        tao_contract
+            .create_lightclient(code_id, msg, "lightclient".to_string(), vec![])
+            .call(&dao)
+            .unwrap_err(),
+        ContractError::LightClientCodeNotApproved { code_id }
+    );
+
+    application_1_contract
+        .set_allowed_channel(
+            lightclient_1.clone(),
+            lightclient_2.clone(),
+            application_2_contract.contract_addr.clone(),
+        )
+        .call(&alice)
+        .unwrap();
+
+    tao_contract
+        .send_packet(packet(&lightclient_1, &lightclient_2), None)
+        .call(&alice)
+        .unwrap();
+}
@@ -0,0 +1,394 @@
+use eureka_application_interface::PayloadResult;
+use eureka_application_pingpong::sv::mt::{CodeId as AppCodeId, ContractProxy};
+use eureka_lightclient_dummy::sv::mt::CodeId as lightclientCodeId;
+use eureka_lightclient_dummy::Header;
+use eureka_lightclient_interface::sv::mt::LightClientProxy;
+use eureka_lightclient_interface::Height;
+use eureka_tao::sv::mt::{CodeId as TaoCodeId, ContractProxy as TaoContractProxy};
+use eureka_tao::{
+    Acknowledgement, ContractError, EscrowedFee, Packet, PacketFee, PacketHeader, Payload,
+    PayloadHeader, SenderPolicy,
+};
+use rstest::rstest;
+use sylvia::cw_multi_test::BasicApp;
+use sylvia::cw_std::testing::MockApi;
+use sylvia::cw_std::{coin, coins, to_json_vec, Addr, Uint128};
+use sylvia::multitest::App;
+
+use crate::factory::create_dummy;
+
+const DENOM: &str = "ufee";
+const OTHER_DENOM: &str = "uother";
+
+#[rstest]
+fn test_packet_fee_competing_relayers() {
+    let api = MockApi::default();
+
+    let alice = api.addr_make("alice");
+
+    let chain_1: App<BasicApp> = App::custom(|router, _, storage| {
+        router
+            .bank
+            .init_balance(
+                storage,
+                &alice,
+                vec![coin(1000, DENOM), coin(1000, OTHER_DENOM)],
+            )
+            .unwrap();
+    });
+
+    let lightclient_code_id = lightclientCodeId::store_code(&chain_1);
+    let tao_code_id = TaoCodeId::store_code(&chain_1);
+    let application_code_id = AppCodeId::store_code(&chain_1);
+
+    let gov = Addr::unchecked("gov-module");
+    let dao = Addr::unchecked("dao");
+    let relayer_1 = api.addr_make("relayer-1");
+    let relayer_2 = api.addr_make("relayer-2");
+    let payee = api.addr_make("payee");
+
+    let tao_contract = tao_code_id.instantiate().call(&gov).unwrap();
+
+    tao_contract
+        .approve_lightclient_code(lightclient_code_id.code_id(), "dummy".to_string())
+        .call(&gov)
+        .unwrap();
+
+    let lightclient_1_contract = create_dummy(&tao_contract, &lightclient_code_id, &dao);
+    let lightclient_2_contract = create_dummy(&tao_contract, &lightclient_code_id, &dao);
+
+    let application_1_contract = application_code_id
+        .instantiate(tao_contract.contract_addr.clone())
+        .with_admin(alice.as_str())
+        .call(&alice)
+        .unwrap();
+    let application_2_contract = application_code_id
+        .instantiate(tao_contract.contract_addr.clone())
+        .with_admin(alice.as_str())
+        .call(&alice)
+        .unwrap();
+
+    let lightclient_1 = (lightclient_1_contract.contract_addr.clone(), vec![]);
+    let lightclient_2 = (lightclient_2_contract.contract_addr.clone(), vec![]);
+
+    application_1_contract
+        .set_allowed_channel(
+            lightclient_1.clone(),
+            lightclient_2.clone(),
+            application_2_contract.contract_addr.clone(),
+        )
+        .call(&alice)
+        .unwrap();
+
+    application_2_contract
+        .set_allowed_channel(
+            lightclient_2.clone(),
+            lightclient_1.clone(),
+            application_1_contract.contract_addr.clone(),
+        )
+        .call(&alice)
+        .unwrap();
+
+    tao_contract
+        .register_application(
+            application_1_contract.contract_addr.clone(),
+            lightclient_1.clone(),
+            lightclient_2.clone(),
+            SenderPolicy::Any,
+        )
+        .call(&alice)
+        .unwrap();
+
+    let fee = PacketFee {
+        receive_fee: coins(10, DENOM),
+        ack_fee: coins(5, DENOM),
+        timeout_fee: coins(3, DENOM),
+    };
+
+    let packet = |nonce| Packet {
+        header: PacketHeader {
+            lightclient_source: lightclient_1.clone(),
+            lightclient_destination: lightclient_2.clone(),
+            nonce,
+            timeout: chain_1.block_info().time.seconds() + 10,
+        },
+        payloads: vec![Payload {
+            header: PayloadHeader {
+                application_source: application_1_contract.contract_addr.clone(),
+                application_destination: application_2_contract.contract_addr.clone(),
+                funds: vec![],
+                gas_limit: None,
+            },
+            data: b"ping".to_vec(),
+        }],
+    };
+
+    let balance = |addr: &Addr| {
+        chain_1
+            .querier()
+            .query_balance(addr, DENOM)
+            .unwrap()
+            .amount
+            .u128()
+    };
+
+    // fee must be covered by the sent funds
+    assert_eq!(
+        tao_contract
+            .send_packet(packet(1), Some(fee.clone()))
+            .with_funds(&[coin(17, DENOM)])
+            .call(&alice)
+            .unwrap_err(),
+        ContractError::FundsMismatch {
+            denom: DENOM.to_string(),
+            required: Uint128::new(18),
+            sent: Uint128::new(17),
+        }
+    );
+
+    // excess funds would be stuck in tao
+    assert_eq!(
+        tao_contract
+            .send_packet(packet(1), Some(fee.clone()))
+            .with_funds(&[coin(19, DENOM)])
+            .call(&alice)
+            .unwrap_err(),
+        ContractError::FundsMismatch {
+            denom: DENOM.to_string(),
+            required: Uint128::new(18),
+            sent: Uint128::new(19),
+        }
+    );
+
+    assert_eq!(
+        tao_contract
+            .send_packet(packet(1), Some(fee.clone()))
+            .with_funds(&[coin(18, DENOM), coin(1, OTHER_DENOM)])
+            .call(&alice)
+            .unwrap_err(),
+        ContractError::FundsMismatch {
+            denom: OTHER_DENOM.to_string(),
+            required: Uint128::zero(),
+            sent: Uint128::new(1),
+        }
+    );
+
+    // acknowledged packet
+
+    let packet_1 = packet(1);
+
+    tao_contract
+        .send_packet(packet_1.clone(), Some(fee.clone()))
+        .with_funds(&[coin(18, DENOM)])
+        .call(&alice)
+        .unwrap();
+
+    assert_eq!(balance(&alice), 982);
+    assert_eq!(balance(&tao_contract.contract_addr), 18);
+    assert_eq!(
+        tao_contract
+            .packet_fee(lightclient_1.clone(), lightclient_2.clone(), 1)
+            .unwrap(),
+        Some(EscrowedFee {
+            payer: alice.clone(),
+            fee: fee.clone(),
+        })
+    );
+
+    tao_contract
+        .receive_packet(packet_1.clone(), Height::default(), vec![])
+        .call(&relayer_1)
+        .unwrap();
+
+    // the packet can only be received once
+    tao_contract
+        .receive_packet(packet_1.clone(), Height::default(), vec![])
+        .call(&relayer_2)
+        .unwrap_err();
+
+    let acknowledgement = tao_contract
+        .packet_acknowledgement(lightclient_1.clone(), lightclient_2.clone(), 1)
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(
+        acknowledgement,
+        Acknowledgement {
+            relayer: relayer_1.clone(),
+            results: vec![PayloadResult::Success],
+        }
+    );
+
+    tao_contract
+        .acknowledge_packet(
+            packet_1.clone(),
+            acknowledgement.clone(),
+            Height::default(),
+            vec![],
+        )
+        .call(&relayer_2)
+        .unwrap();
+
+    // the packet can only be acknowledged once
+    tao_contract
+        .acknowledge_packet(packet_1.clone(), acknowledgement, Height::default(), vec![])
+        .call(&relayer_1)
+        .unwrap_err();
+
+    // acknowledged packet can not be timed out
+    tao_contract
+        .timeout_packet(packet_1, Height::default(), vec![])
+        .call(&relayer_1)
+        .unwrap_err();
+
+    assert_eq!(balance(&relayer_1), 10);
+    assert_eq!(balance(&relayer_2), 5);
+    assert_eq!(balance(&alice), 985);
+    assert_eq!(balance(&tao_contract.contract_addr), 0);
+    assert_eq!(
+        tao_contract
+            .packet_fee(lightclient_1.clone(), lightclient_2.clone(), 1)
+            .unwrap(),
+        None
+    );
+
+    // timed out packet
+
+    let packet_2 = packet(2);
+
+    tao_contract
+        .send_packet(packet_2.clone(), Some(fee.clone()))
+        .with_funds(&[coin(18, DENOM)])
+        .call(&alice)
+        .unwrap();
+
+    lightclient_2_contract
+        .update(
+            to_json_vec(&Header {
+                height: Height::new(0, 1),
+                timestamp: packet_2.header.timeout,
+            })
+            .unwrap(),
+        )
+        .call(&dao)
+        .unwrap();
+
+    tao_contract
+        .timeout_packet(packet_2.clone(), Height::new(0, 1), vec![])
+        .call(&relayer_2)
+        .unwrap();
+
+    // timed out packet can not be acknowledged
+    tao_contract
+        .acknowledge_packet(
+            packet_2,
+            Acknowledgement {
+                relayer: relayer_1.clone(),
+                results: vec![PayloadResult::Success],
+            },
+            Height::default(),
+            vec![],
+        )
+        .call(&relayer_1)
+        .unwrap_err();
+
+    assert_eq!(balance(&relayer_1), 10);
+    assert_eq!(balance(&relayer_2), 8);
+    assert_eq!(balance(&alice), 982);
+    assert_eq!(balance(&tao_contract.contract_addr), 0);
+    assert_eq!(
+        tao_contract
+            .packet_fee(lightclient_1.clone(), lightclient_2.clone(), 2)
+            .unwrap(),
+        None
+    );
+
+    // packet received by a relayer whose address is not valid on this chain
+
+    let packet_3 = packet(3);
+
+    tao_contract
+        .send_packet(packet_3.clone(), Some(fee.clone()))
+        .with_funds(&[coin(18, DENOM)])
+        .call(&alice)
+        .unwrap();
+
+    // the acknowledgement is not held up by the receive fee payout, which is refunded
+    tao_contract
+        .acknowledge_packet(
+            packet_3,
+            Acknowledgement {
+                relayer: Addr::unchecked("osmo1relayer"),
+                results: vec![PayloadResult::Success],
+            },
+            Height::default(),
+            vec![],
+        )
+        .call(&relayer_2)
+        .unwrap();
+
+    assert_eq!(balance(&relayer_2), 13);
+    assert_eq!(balance(&alice), 977);
+    assert_eq!(balance(&tao_contract.contract_addr), 0);
+    assert_eq!(
+        tao_contract
+            .packet_fee(lightclient_1.clone(), lightclient_2.clone(), 3)
+            .unwrap(),
+        None
+    );
+
+    // packet received by a relayer paid at its registered counterparty payee
+
+    tao_contract
+        .register_counterparty_payee(lightclient_1.clone(), lightclient_2.clone(), payee.clone())
+        .call(&relayer_1)
+        .unwrap();
+
+    assert_eq!(
+        tao_contract
+            .counterparty_payee(
+                lightclient_1.clone(),
+                lightclient_2.clone(),
+                relayer_1.clone()
+            )
+            .unwrap(),
+        Some(payee.clone())
+    );
+
+    let packet_4 = packet(4);
+
+    tao_contract
+        .send_packet(packet_4.clone(), Some(fee))
+        .with_funds(&[coin(18, DENOM)])
+        .call(&alice)
+        .unwrap();
+
+    tao_contract
+        .receive_packet(packet_4.clone(), Height::default(), vec![])
+        .call(&relayer_1)
+        .unwrap();
+
+    let acknowledgement = tao_contract
+        .packet_acknowledgement(lightclient_1.clone(), lightclient_2.clone(), 4)
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(acknowledgement.relayer, payee);
+
+    tao_contract
+        .acknowledge_packet(packet_4, acknowledgement, Height::default(), vec![])
+        .call(&relayer_2)
+        .unwrap();
+
+    assert_eq!(balance(&payee), 10);
+    assert_eq!(balance(&relayer_1), 10);
+    assert_eq!(balance(&relayer_2), 18);
+    assert_eq!(balance(&alice), 962);
+    assert_eq!(balance(&tao_contract.contract_addr), 0);
+    assert_eq!(
+        tao_contract
+            .packet_fee(lightclient_1, lightclient_2, 4)
+            .unwrap(),
+        None
+    );
+}
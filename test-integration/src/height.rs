@@ -0,0 +1,408 @@
+use eureka_application_pingpong::sv::mt::{CodeId as AppCodeId, ContractProxy};
+use eureka_lightclient_dummy::sv::mt::{
+    CodeId as lightclientCodeId, ContractProxy as LightClientContractProxy,
+};
+use eureka_lightclient_dummy::{Contract as DummyContract, Header};
+use eureka_lightclient_interface::sv::mt::LightClientProxy;
+use eureka_lightclient_interface::sv::QueryMsg as LightClientQueryMsg;
+use eureka_lightclient_interface::{ConsensusState, Height, Status};
+use eureka_tao::sv::mt::{CodeId as TaoCodeId, ContractProxy as TaoContractProxy};
+use eureka_tao::{ContractError, Packet, PacketHeader, Payload, PayloadHeader, SenderPolicy};
+use rstest::rstest;
+use sylvia::cw_multi_test::{BasicApp, ContractWrapper};
+use sylvia::cw_std::{
+    to_json_binary, to_json_vec, Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response,
+    StdError, StdResult,
+};
+use sylvia::multitest::{App, Proxy};
+
+use crate::factory::{create_dummy, create_lightclient, dummy_states};
+
+#[rstest]
+fn test_proof_height_above_latest_height() {
+    let chain_1 = App::default();
+
+    let lightclient_code_id = lightclientCodeId::store_code(&chain_1);
+    let tao_code_id = TaoCodeId::store_code(&chain_1);
+    let application_code_id = AppCodeId::store_code(&chain_1);
+
+    let gov = Addr::unchecked("gov-module");
+    let dao = Addr::unchecked("dao");
+    let alice = Addr::unchecked("alice");
+    let relayer = Addr::unchecked("relayer");
+
+    let tao_contract = tao_code_id.instantiate().call(&gov).unwrap();
+
+    tao_contract
+        .approve_lightclient_code(lightclient_code_id.code_id(), "dummy".to_string())
+        .call(&gov)
+        .unwrap();
+
+    let lightclient_1_contract = create_dummy(&tao_contract, &lightclient_code_id, &dao);
+    let lightclient_2_contract = create_dummy(&tao_contract, &lightclient_code_id, &dao);
+
+    let application_1_contract = application_code_id
+        .instantiate(tao_contract.contract_addr.clone())
+        .with_admin(alice.as_str())
+        .call(&alice)
+        .unwrap();
+    let application_2_contract = application_code_id
+        .instantiate(tao_contract.contract_addr.clone())
+        .with_admin(alice.as_str())
+        .call(&alice)
+        .unwrap();
+
+    let lightclient_1 = (lightclient_1_contract.contract_addr.clone(), vec![]);
+    let lightclient_2 = (lightclient_2_contract.contract_addr.clone(), vec![]);
+
+    application_1_contract
+        .set_allowed_channel(
+            lightclient_1.clone(),
+            lightclient_2.clone(),
+            application_2_contract.contract_addr.clone(),
+        )
+        .call(&alice)
+        .unwrap();
+
+    application_2_contract
+        .set_allowed_channel(
+            lightclient_2.clone(),
+            lightclient_1.clone(),
+            application_1_contract.contract_addr.clone(),
+        )
+        .call(&alice)
+        .unwrap();
+
+    tao_contract
+        .register_application(
+            application_1_contract.contract_addr.clone(),
+            lightclient_1.clone(),
+            lightclient_2.clone(),
+            SenderPolicy::Any,
+        )
+        .call(&alice)
+        .unwrap();
+
+    let packet = |nonce| Packet {
+        header: PacketHeader {
+            lightclient_source: lightclient_1.clone(),
+            lightclient_destination: lightclient_2.clone(),
+            nonce,
+            timeout: chain_1.block_info().time.seconds() + 10,
+        },
+        payloads: vec![Payload {
+            header: PayloadHeader {
+                application_source: application_1_contract.contract_addr.clone(),
+                application_destination: application_2_contract.contract_addr.clone(),
+                funds: vec![],
+                gas_limit: None,
+            },
+            data: b"ping".to_vec(),
+        }],
+    };
+
+    assert_eq!(
+        lightclient_1_contract.latest_height().unwrap(),
+        Height::default()
+    );
+    assert_eq!(
+        lightclient_2_contract.latest_height().unwrap(),
+        Height::default()
+    );
+
+    let packet_1 = packet(1);
+
+    tao_contract
+        .send_packet(packet_1.clone(), None)
+        .call(&alice)
+        .unwrap();
+
+    assert_eq!(
+        tao_contract
+            .receive_packet(packet_1.clone(), Height::new(0, 1), vec![])
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::ProofHeightTooHigh {
+            height: Height::new(0, 1),
+            latest_height: Height::default(),
+        }
+    );
+
+    tao_contract
+        .receive_packet(packet_1, Height::default(), vec![])
+        .call(&relayer)
+        .unwrap();
+
+    let packet_2 = packet(2);
+
+    tao_contract
+        .send_packet(packet_2.clone(), None)
+        .call(&alice)
+        .unwrap();
+
+    assert_eq!(
+        tao_contract
+            .timeout_packet(packet_2.clone(), Height::new(1, 0), vec![])
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::ProofHeightTooHigh {
+            height: Height::new(1, 0),
+            latest_height: Height::default(),
+        }
+    );
+
+    // the consensus state at instantiation predates the timeout
+    assert_eq!(
+        tao_contract
+            .timeout_packet(packet_2.clone(), Height::default(), vec![])
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::TimeoutNotElapsed {
+            current: chain_1.block_info().time.seconds(),
+            timeout: packet_2.header.timeout,
+        }
+    );
+
+    lightclient_2_contract
+        .update(
+            to_json_vec(&Header {
+                height: Height::new(1, 0),
+                timestamp: packet_2.header.timeout,
+            })
+            .unwrap(),
+        )
+        .call(&dao)
+        .unwrap();
+
+    // below the latest height, but never stored
+    assert_eq!(
+        tao_contract
+            .timeout_packet(packet_2.clone(), Height::new(0, 5), vec![])
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::ConsensusStateNotFound {
+            height: Height::new(0, 5),
+        }
+    );
+
+    tao_contract
+        .timeout_packet(packet_2, Height::new(1, 0), vec![])
+        .call(&relayer)
+        .unwrap();
+}
+
+#[rstest]
+fn test_dummy_consensus_states() {
+    let chain_1 = App::default();
+
+    let relayer = Addr::unchecked("relayer");
+
+    let dao = Addr::unchecked("dao");
+
+    let (client_state, consensus_state) = dummy_states(&chain_1);
+
+    let lightclient_contract = lightclientCodeId::store_code(&chain_1)
+        .instantiate(client_state, consensus_state, dao.clone())
+        .call(&dao)
+        .unwrap();
+
+    lightclient_contract
+        .set_submitters(vec![relayer.clone()])
+        .call(&dao)
+        .unwrap();
+
+    let now = chain_1.block_info().time.seconds();
+
+    let update = |height, timestamp| {
+        lightclient_contract
+            .update(to_json_vec(&Header { height, timestamp }).unwrap())
+            .call(&relayer)
+            .unwrap();
+    };
+
+    update(Height::new(0, 2), now + 20);
+    update(Height::new(0, 1), now + 10);
+
+    assert_eq!(
+        lightclient_contract.latest_height().unwrap(),
+        Height::new(0, 2)
+    );
+    assert_eq!(
+        lightclient_contract.client_state().unwrap().latest_height,
+        Height::new(0, 2)
+    );
+    assert_eq!(
+        lightclient_contract
+            .consensus_state(Height::new(0, 1))
+            .unwrap(),
+        ConsensusState {
+            timestamp: now + 10,
+            root: vec![],
+            data: to_json_vec(&Header {
+                height: Height::new(0, 1),
+                timestamp: now + 10,
+            })
+            .unwrap(),
+        }
+    );
+    assert_eq!(
+        lightclient_contract.timestamp(Height::default()).unwrap(),
+        now
+    );
+    assert_eq!(
+        lightclient_contract.timestamp(Height::new(0, 1)).unwrap(),
+        now + 10
+    );
+
+    lightclient_contract
+        .timestamp(Height::new(0, 3))
+        .unwrap_err();
+
+    // missing consensus states are told apart from errors
+    assert_eq!(
+        lightclient_contract
+            .consensus_timestamp(Height::new(0, 1))
+            .unwrap(),
+        Some(now + 10)
+    );
+    assert_eq!(
+        lightclient_contract
+            .consensus_timestamp(Height::new(0, 3))
+            .unwrap(),
+        None
+    );
+
+    lightclient_contract
+        .update(b"not a header".to_vec())
+        .call(&relayer)
+        .unwrap_err();
+
+    // the dummy never expires, pruning keeps every consensus state
+    lightclient_contract.prune().call(&relayer).unwrap();
+
+    assert_eq!(
+        lightclient_contract.timestamp(Height::default()).unwrap(),
+        now
+    );
+    assert_eq!(
+        lightclient_contract.timestamp(Height::new(0, 1)).unwrap(),
+        now + 10
+    );
+    assert_eq!(
+        lightclient_contract.timestamp(Height::new(0, 2)).unwrap(),
+        now + 20
+    );
+}
+
+// light client tracking heights up to 0-10 whose consensus states can't be decoded
+fn broken_lightclient_query(_deps: Deps, _env: Env, msg: LightClientQueryMsg) -> StdResult<Binary> {
+    match msg {
+        LightClientQueryMsg::Status {} => to_json_binary(&Status::Active),
+        LightClientQueryMsg::LatestHeight {} => to_json_binary(&Height::new(0, 10)),
+        _ => Err(StdError::generic_err("corrupted consensus state")),
+    }
+}
+
+#[rstest]
+fn test_consensus_state_error_passed_through() {
+    let chain_1 = App::default();
+
+    let lightclient_code_id = lightclientCodeId::store_code(&chain_1);
+    let tao_code_id = TaoCodeId::store_code(&chain_1);
+    let application_code_id = AppCodeId::store_code(&chain_1);
+
+    let broken_code_id = chain_1.app_mut().store_code(Box::new(ContractWrapper::new(
+        |_: DepsMut, _: Env, _: MessageInfo, _: Empty| -> StdResult<Response> {
+            Ok(Response::default())
+        },
+        |_: DepsMut, _: Env, _: MessageInfo, _: Empty| -> StdResult<Response> {
+            Ok(Response::default())
+        },
+        broken_lightclient_query,
+    )));
+
+    let gov = Addr::unchecked("gov-module");
+    let dao = Addr::unchecked("dao");
+    let alice = Addr::unchecked("alice");
+    let relayer = Addr::unchecked("relayer");
+
+    let tao_contract = tao_code_id.instantiate().call(&gov).unwrap();
+
+    tao_contract
+        .approve_lightclient_code(lightclient_code_id.code_id(), "dummy".to_string())
+        .call(&gov)
+        .unwrap();
+    tao_contract
+        .approve_lightclient_code(broken_code_id, "broken".to_string())
+        .call(&gov)
+        .unwrap();
+
+    let lightclient_1_contract = create_dummy(&tao_contract, &lightclient_code_id, &dao);
+    let lightclient_2_contract: Proxy<'_, BasicApp, DummyContract> =
+        create_lightclient(&tao_contract, &dao, broken_code_id, b"", &Empty {});
+
+    let application_1_contract = application_code_id
+        .instantiate(tao_contract.contract_addr.clone())
+        .with_admin(alice.as_str())
+        .call(&alice)
+        .unwrap();
+
+    let lightclient_1 = (lightclient_1_contract.contract_addr.clone(), vec![]);
+    let lightclient_2 = (lightclient_2_contract.contract_addr.clone(), vec![]);
+
+    application_1_contract
+        .set_allowed_channel(
+            lightclient_1.clone(),
+            lightclient_2.clone(),
+            application_1_contract.contract_addr.clone(),
+        )
+        .call(&alice)
+        .unwrap();
+
+    tao_contract
+        .register_application(
+            application_1_contract.contract_addr.clone(),
+            lightclient_1.clone(),
+            lightclient_2.clone(),
+            SenderPolicy::Any,
+        )
+        .call(&alice)
+        .unwrap();
+
+    let packet = Packet {
+        header: PacketHeader {
+            lightclient_source: lightclient_1,
+            lightclient_destination: lightclient_2,
+            nonce: 1,
+            timeout: chain_1.block_info().time.seconds() + 10,
+        },
+        payloads: vec![Payload {
+            header: PayloadHeader {
+                application_source: application_1_contract.contract_addr.clone(),
+                application_destination: application_1_contract.contract_addr.clone(),
+                funds: vec![],
+                gas_limit: None,
+            },
+            data: b"ping".to_vec(),
+        }],
+    };
+
+    tao_contract
+        .send_packet(packet.clone(), None)
+        .call(&alice)
+        .unwrap();
+
+    // the consensus state exists but can't be read, which isn't reported as missing
+    let error = tao_contract
+        .timeout_packet(packet, Height::new(0, 1), vec![])
+        .call(&relayer)
+        .unwrap_err();
+
+    assert_ne!(
+        error,
+        ContractError::ConsensusStateNotFound {
+            height: Height::new(0, 1),
+        }
+    );
+    assert!(error.to_string().contains("corrupted consensus state"));
+}
@@ -0,0 +1,539 @@
+use eureka_ics23_verifier::{
+    apply_prefix, commitment_proof, iavl_spec, sdk_specs, verify_existence, verify_membership,
+    verify_non_membership, wasm_commitment_prefix, CommitmentProof, ExistenceProof, HashOp,
+    InnerOp, LeafOp, LengthOp, MerkleProof, NonExistenceProof, ProofError, IBC_STORE, WASM_STORE,
+};
+use eureka_lightclient_interface::sv::mt::LightClientProxy;
+use eureka_lightclient_interface::Height;
+use eureka_lightclient_mock::sv::mt::CodeId as MockCodeId;
+use eureka_lightclient_mock::sv::InstantiateMsg as MockInstantiateMsg;
+use eureka_lightclient_mock::Contract as MockContract;
+use eureka_lightclient_tendermint::sv::mt::CodeId as TendermintCodeId;
+use eureka_lightclient_tendermint::Contract as TendermintContract;
+use eureka_tao::commitment::{connection_key, packet_commitment, packet_commitment_path};
+use eureka_tao::sv::mt::{CodeId as TaoCodeId, ContractProxy as TaoContractProxy};
+use eureka_tao::{ContractError, Packet, PacketHeader};
+use prost::Message;
+use rstest::rstest;
+use sha2::{Digest, Sha256};
+use sylvia::cw_std::testing::MockApi;
+use sylvia::cw_std::{to_json_vec, Addr, Api};
+use sylvia::multitest::{App, Proxy};
+
+use crate::factory::create_lightclient;
+use crate::tendermint::{header, instantiate_msg, keys};
+
+// protobuf varint length prefix
+fn length_prefixed(data: &[u8]) -> Vec<u8> {
+    let mut buffer = vec![];
+    prost::encoding::encode_varint(data.len() as u64, &mut buffer);
+    [&buffer[..], data].concat()
+}
+
+fn leaf_hash(prefix: &[u8], key: &[u8], value: &[u8]) -> Vec<u8> {
+    Sha256::digest(
+        [
+            prefix,
+            &length_prefixed(key),
+            &length_prefixed(&Sha256::digest(value)),
+        ]
+        .concat(),
+    )
+    .to_vec()
+}
+
+fn exist(key: &[u8], value: &[u8], leaf_prefix: &[u8], path: Vec<InnerOp>) -> ExistenceProof {
+    ExistenceProof {
+        key: key.to_vec(),
+        value: value.to_vec(),
+        leaf: Some(LeafOp {
+            hash: HashOp::Sha256.into(),
+            prehash_key: HashOp::NoHash.into(),
+            prehash_value: HashOp::Sha256.into(),
+            length: LengthOp::VarProto.into(),
+            prefix: leaf_prefix.to_vec(),
+        }),
+        path,
+    }
+}
+
+// tree of two leaves: its root and the existence proofs of both leaves
+// an inner node hashes `inner_prefix | left | separator | right`
+fn pair(
+    leaf_prefix: &[u8],
+    inner_prefix: &[u8],
+    separator: &[u8],
+    left: (&[u8], &[u8]),
+    right: (&[u8], &[u8]),
+) -> (Vec<u8>, ExistenceProof, ExistenceProof) {
+    let left_hash = leaf_hash(leaf_prefix, left.0, left.1);
+    let right_hash = leaf_hash(leaf_prefix, right.0, right.1);
+
+    let root = Sha256::digest([inner_prefix, &left_hash, separator, &right_hash].concat()).to_vec();
+
+    let left_path = vec![InnerOp {
+        hash: HashOp::Sha256.into(),
+        prefix: inner_prefix.to_vec(),
+        suffix: [separator, &right_hash].concat(),
+    }];
+
+    let right_path = vec![InnerOp {
+        hash: HashOp::Sha256.into(),
+        prefix: [inner_prefix, &left_hash, separator].concat(),
+        suffix: vec![],
+    }];
+
+    (
+        root,
+        exist(left.0, left.1, leaf_prefix, left_path),
+        exist(right.0, right.1, leaf_prefix, right_path),
+    )
+}
+
+// IAVL module store: leaves at height 0, size 1, version 1 and the root at height 1, size 2
+pub fn iavl_pair(
+    left: (&[u8], &[u8]),
+    right: (&[u8], &[u8]),
+) -> (Vec<u8>, ExistenceProof, ExistenceProof) {
+    pair(&[0, 2, 2], &[2, 4, 2, 32], &[32], left, right)
+}
+
+// multistore of two module stores, as a tendermint simple merkle tree
+pub fn multistore_pair(
+    left: (&[u8], &[u8]),
+    right: (&[u8], &[u8]),
+) -> (Vec<u8>, ExistenceProof, ExistenceProof) {
+    pair(&[0], &[1], &[], left, right)
+}
+
+pub fn merkle_proof(proofs: Vec<commitment_proof::Proof>) -> Vec<u8> {
+    MerkleProof {
+        proofs: proofs
+            .into_iter()
+            .map(|proof| CommitmentProof { proof: Some(proof) })
+            .collect(),
+    }
+    .encode_to_vec()
+}
+
+pub fn non_existence(
+    key: &[u8],
+    left: Option<ExistenceProof>,
+    right: Option<ExistenceProof>,
+) -> commitment_proof::Proof {
+    commitment_proof::Proof::Nonexist(NonExistenceProof {
+        key: key.to_vec(),
+        left,
+        right,
+    })
+}
+
+#[rstest]
+fn test_multistore_prefixes() {
+    let (ibc_root, ibc_a, _) = iavl_pair((b"a", b"value-a"), (b"c", b"value-c"));
+    let (wasm_root, wasm_k, _) = iavl_pair((b"k", b"value-k"), (b"l", b"value-l"));
+
+    // store names are sorted in the multistore
+    let (app_hash, ibc_store, wasm_store) =
+        multistore_pair((IBC_STORE, &ibc_root), (WASM_STORE, &wasm_root));
+
+    let ibc_proof = merkle_proof(vec![
+        commitment_proof::Proof::Exist(ibc_a),
+        commitment_proof::Proof::Exist(ibc_store.clone()),
+    ]);
+
+    let wasm_proof = merkle_proof(vec![
+        commitment_proof::Proof::Exist(wasm_k),
+        commitment_proof::Proof::Exist(wasm_store),
+    ]);
+
+    verify_membership(
+        &sdk_specs(),
+        &app_hash,
+        &apply_prefix(IBC_STORE, b"a"),
+        &ibc_proof,
+        b"value-a".to_vec(),
+    )
+    .unwrap();
+
+    verify_membership(
+        &sdk_specs(),
+        &app_hash,
+        &apply_prefix(WASM_STORE, b"k"),
+        &wasm_proof,
+        b"value-k".to_vec(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        verify_membership(
+            &sdk_specs(),
+            &app_hash,
+            &apply_prefix(IBC_STORE, b"k"),
+            &wasm_proof,
+            b"value-k".to_vec(),
+        ),
+        Err(ProofError::KeyMismatch)
+    );
+
+    assert_eq!(
+        verify_membership(
+            &sdk_specs(),
+            &app_hash,
+            &apply_prefix(IBC_STORE, b"a"),
+            &ibc_proof,
+            b"value-c".to_vec(),
+        ),
+        Err(ProofError::ValueMismatch)
+    );
+
+    assert_eq!(
+        verify_membership(
+            &sdk_specs(),
+            &ibc_root,
+            &apply_prefix(IBC_STORE, b"a"),
+            &ibc_proof,
+            b"value-a".to_vec(),
+        ),
+        Err(ProofError::RootMismatch)
+    );
+
+    assert_eq!(
+        verify_membership(
+            &sdk_specs(),
+            &app_hash,
+            &apply_prefix(IBC_STORE, b"a"),
+            &merkle_proof(vec![commitment_proof::Proof::Exist(ibc_store)]),
+            b"value-a".to_vec(),
+        ),
+        Err(ProofError::ProofCountMismatch {
+            expected: 2,
+            got: 1
+        })
+    );
+
+    assert!(matches!(
+        verify_membership(
+            &sdk_specs(),
+            &app_hash,
+            &apply_prefix(IBC_STORE, b"a"),
+            b"not a proof",
+            b"value-a".to_vec(),
+        ),
+        Err(ProofError::Decode(_))
+    ));
+}
+
+#[rstest]
+fn test_iavl_ops() {
+    let verify = |leaf_prefix: &[u8], inner_prefix: &[u8]| {
+        let (root, proof_a, proof_c) = pair(
+            leaf_prefix,
+            inner_prefix,
+            &[32],
+            (b"a", b"value-a"),
+            (b"c", b"value-c"),
+        );
+
+        verify_existence(&proof_a, &iavl_spec(), &root, b"a", b"value-a")
+            .and_then(|()| verify_existence(&proof_c, &iavl_spec(), &root, b"c", b"value-c"))
+    };
+
+    verify(&[0, 2, 2], &[2, 4, 2, 32]).unwrap();
+
+    // a taller root, in a later version
+    verify(&[0, 2, 2], &[4, 4, 6, 32]).unwrap();
+
+    for (leaf_prefix, inner_prefix) in [
+        // bytes after the leaf version
+        (&[0, 2, 2, 0][..], &[2, 4, 2, 32][..]),
+        // negative leaf size
+        (&[0, 1, 2], &[2, 4, 2, 32]),
+        // empty leaf
+        (&[0, 0, 2], &[2, 4, 2, 32]),
+        // leaf of size 2
+        (&[0, 4, 2], &[2, 4, 2, 32]),
+        // empty inner node
+        (&[0, 2, 2], &[2, 0, 2, 32]),
+        // leaf above height 0
+        (&[2, 2, 2], &[2, 4, 2, 32]),
+        // negative inner version
+        (&[0, 2, 2], &[2, 4, 1, 32]),
+        // bytes after the child length
+        (&[0, 2, 2], &[2, 4, 2, 32, 0]),
+        // truncated varint
+        (&[0, 2, 2], &[2, 4, 0x80, 0x80]),
+    ] {
+        assert!(matches!(
+            verify(leaf_prefix, inner_prefix),
+            Err(ProofError::SpecMismatch(_))
+        ));
+    }
+}
+
+#[rstest]
+fn test_non_existence_neighbors() {
+    let (ibc_root, proof_a, proof_c) = iavl_pair((b"a", b"value-a"), (b"c", b"value-c"));
+
+    let (app_hash, _, ibc_store) = multistore_pair((b"bank", b"bank-root"), (IBC_STORE, &ibc_root));
+
+    let verify = |key: &[u8], left: Option<&ExistenceProof>, right: Option<&ExistenceProof>| {
+        verify_non_membership(
+            &sdk_specs(),
+            &app_hash,
+            &apply_prefix(IBC_STORE, key),
+            &merkle_proof(vec![
+                non_existence(key, left.cloned(), right.cloned()),
+                commitment_proof::Proof::Exist(ibc_store.clone()),
+            ]),
+        )
+    };
+
+    // between two neighbors
+    verify(b"b", Some(&proof_a), Some(&proof_c)).unwrap();
+
+    // before the leftmost leaf
+    verify(b"0", None, Some(&proof_a)).unwrap();
+
+    // after the rightmost leaf
+    verify(b"d", Some(&proof_c), None).unwrap();
+
+    // a single neighbor is not enough in between
+    assert!(matches!(
+        verify(b"b", None, Some(&proof_c)),
+        Err(ProofError::InvalidNonExistence(_))
+    ));
+
+    assert!(matches!(
+        verify(b"b", Some(&proof_a), None),
+        Err(ProofError::InvalidNonExistence(_))
+    ));
+
+    // existing keys
+    assert!(matches!(
+        verify(b"a", Some(&proof_a), Some(&proof_c)),
+        Err(ProofError::InvalidNonExistence(_))
+    ));
+
+    assert!(matches!(
+        verify(b"c", Some(&proof_a), Some(&proof_c)),
+        Err(ProofError::InvalidNonExistence(_))
+    ));
+}
+
+#[rstest]
+fn test_wasm_commitment_path() {
+    let api = MockApi::default();
+
+    // the counterparty tao, registered with its light client on this chain
+    let counterparty_tao = api.addr_make("counterparty-tao");
+    let counterparty_tao = api.addr_canonicalize(counterparty_tao.as_str()).unwrap();
+
+    let packet = Packet {
+        header: PacketHeader {
+            lightclient_source: (
+                Addr::unchecked("lightclient-source"),
+                wasm_commitment_prefix(&counterparty_tao),
+            ),
+            lightclient_destination: (Addr::unchecked("lightclient-destination"), vec![]),
+            nonce: 1,
+            timeout: 100,
+        },
+        payloads: vec![],
+    };
+
+    let PacketHeader {
+        lightclient_source,
+        lightclient_destination,
+        nonce,
+        ..
+    } = &packet.header;
+
+    let commitment_path = packet_commitment_path(
+        &connection_key(lightclient_source, lightclient_destination),
+        *nonce,
+    );
+
+    let path = apply_prefix(&lightclient_source.1, &commitment_path);
+
+    assert_eq!(
+        path,
+        vec![
+            WASM_STORE.to_vec(),
+            [&[0x03], counterparty_tao.as_slice(), &commitment_path].concat(),
+        ]
+    );
+
+    let value = packet_commitment(&packet).unwrap();
+
+    let (wasm_root, proof, _) = iavl_pair((&path[1], &value), (&[0x04], b"other"));
+    let (app_hash, _, wasm_store) =
+        multistore_pair((IBC_STORE, b"ibc-root"), (WASM_STORE, &wasm_root));
+
+    let proof = merkle_proof(vec![
+        commitment_proof::Proof::Exist(proof),
+        commitment_proof::Proof::Exist(wasm_store),
+    ]);
+
+    verify_membership(&sdk_specs(), &app_hash, &path, &proof, value.clone()).unwrap();
+
+    // the same key outside of the counterparty tao storage
+    assert_eq!(
+        verify_membership(
+            &sdk_specs(),
+            &app_hash,
+            &apply_prefix(WASM_STORE, &commitment_path),
+            &proof,
+            value,
+        ),
+        Err(ProofError::KeyMismatch)
+    );
+}
+
+#[rstest]
+fn test_wasm_commitment_prefix_registered() {
+    let chain = App::default();
+
+    let gov = Addr::unchecked("gov-module");
+    let dao = Addr::unchecked("dao");
+    let relayer = Addr::unchecked("relayer");
+
+    let keys_a = keys(&[1, 2, 3, 4]);
+
+    let now = chain.block_info().time.seconds();
+
+    let tao_contract = TaoCodeId::store_code(&chain)
+        .instantiate()
+        .call(&gov)
+        .unwrap();
+
+    let mock_code_id = MockCodeId::store_code(&chain).code_id();
+    let tendermint_code_id = TendermintCodeId::store_code(&chain).code_id();
+
+    tao_contract
+        .approve_lightclient_code(mock_code_id, "mock".to_string())
+        .call(&gov)
+        .unwrap();
+    tao_contract
+        .approve_lightclient_code(tendermint_code_id, "tendermint".to_string())
+        .call(&gov)
+        .unwrap();
+
+    // the counterparty tao and another contract of the counterparty chain
+    let api = MockApi::default();
+    let counterparty_tao = api.addr_canonicalize(api.addr_make("counterparty-tao").as_str());
+    let other_contract = api.addr_canonicalize(api.addr_make("other-contract").as_str());
+    let tao_prefix = wasm_commitment_prefix(&counterparty_tao.unwrap());
+    let other_prefix = wasm_commitment_prefix(&other_contract.unwrap());
+
+    let lightclient: Proxy<_, TendermintContract> = create_lightclient(
+        &tao_contract,
+        &dao,
+        tendermint_code_id,
+        &tao_prefix,
+        &instantiate_msg(&chain, &keys_a),
+    );
+
+    let counterparty_contract: Proxy<_, MockContract> = create_lightclient(
+        &tao_contract,
+        &dao,
+        mock_code_id,
+        b"ibc",
+        &MockInstantiateMsg::new(vec![]),
+    );
+
+    let lightclient_destination = (counterparty_contract.contract_addr.clone(), b"ibc".to_vec());
+
+    let packet = |commitment_prefix: &[u8]| Packet {
+        header: PacketHeader {
+            lightclient_source: (
+                lightclient.contract_addr.clone(),
+                commitment_prefix.to_vec(),
+            ),
+            lightclient_destination: lightclient_destination.clone(),
+            nonce: 1,
+            timeout: now + 10,
+        },
+        payloads: vec![],
+    };
+
+    // the wasm store key and value of a packet commitment under `commitment_prefix`
+    let commitment = |commitment_prefix: &[u8]| {
+        let packet = packet(commitment_prefix);
+        let path = packet_commitment_path(
+            &connection_key(
+                &packet.header.lightclient_source,
+                &packet.header.lightclient_destination,
+            ),
+            1,
+        );
+
+        (
+            apply_prefix(commitment_prefix, &path).remove(1),
+            packet_commitment(&packet).unwrap(),
+        )
+    };
+
+    // both contracts committed a packet naming the light client
+    let (tao_key, tao_value) = commitment(&tao_prefix);
+    let (other_key, other_value) = commitment(&other_prefix);
+
+    let mut leaves = [(&tao_key, &tao_value), (&other_key, &other_value)];
+    leaves.sort();
+
+    let (wasm_root, proof_1, proof_2) =
+        iavl_pair((leaves[0].0, leaves[0].1), (leaves[1].0, leaves[1].1));
+    let (app_hash, _, wasm_store) =
+        multistore_pair((IBC_STORE, b"ibc-root"), (WASM_STORE, &wasm_root));
+
+    let membership = |key: &[u8]| {
+        let proof = if key == leaves[0].0.as_slice() {
+            &proof_1
+        } else {
+            &proof_2
+        };
+
+        merkle_proof(vec![
+            commitment_proof::Proof::Exist(proof.clone()),
+            commitment_proof::Proof::Exist(wasm_store.clone()),
+        ])
+    };
+
+    lightclient
+        .update(
+            to_json_vec(&header(
+                11,
+                now - 90,
+                &app_hash,
+                &keys_a,
+                &keys_a,
+                10,
+                &keys_a,
+            ))
+            .unwrap(),
+        )
+        .call(&relayer)
+        .unwrap();
+
+    // valid for the light client, but proven in the storage of another contract
+    assert_eq!(
+        tao_contract
+            .receive_packet(
+                packet(&other_prefix),
+                Height::new(1, 11),
+                membership(&other_key),
+            )
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::CommitmentPrefixMismatch
+    );
+
+    tao_contract
+        .receive_packet(
+            packet(&tao_prefix),
+            Height::new(1, 11),
+            membership(&tao_key),
+        )
+        .call(&relayer)
+        .unwrap();
+}
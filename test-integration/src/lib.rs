@@ -1,2 +1,36 @@
 #[cfg(test)]
+pub mod attestor;
+#[cfg(test)]
+pub mod authorization;
+#[cfg(test)]
+pub mod commitment;
+#[cfg(test)]
+pub mod ethereum;
+#[cfg(test)]
+pub mod factory;
+#[cfg(test)]
+pub mod fee;
+#[cfg(test)]
+pub mod height;
+#[cfg(test)]
+pub mod ics23;
+#[cfg(test)]
+pub mod misbehaviour;
+#[cfg(test)]
+pub mod native_proxy;
+#[cfg(test)]
+pub mod payload;
+#[cfg(test)]
+pub mod relay;
+#[cfg(test)]
+pub mod solomachine;
+#[cfg(test)]
+pub mod status;
+#[cfg(test)]
+pub mod substitution;
+#[cfg(test)]
+pub mod tendermint;
+#[cfg(test)]
 pub mod tests;
+#[cfg(test)]
+pub mod verification;
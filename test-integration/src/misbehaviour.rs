@@ -0,0 +1,205 @@
+use eureka_application_pingpong::sv::mt::{CodeId as AppCodeId, ContractProxy};
+use eureka_lightclient_dummy::sv::mt::CodeId as lightclientCodeId;
+use eureka_lightclient_dummy::{Header, Misbehaviour};
+use eureka_lightclient_interface::sv::mt::LightClientProxy;
+use eureka_lightclient_interface::{Height, Status};
+use eureka_tao::sv::mt::{CodeId as TaoCodeId, ContractProxy as TaoContractProxy};
+use eureka_tao::{ContractError, Packet, PacketHeader, Payload, PayloadHeader, SenderPolicy};
+use rstest::rstest;
+use sylvia::cw_std::{to_json_vec, Addr};
+use sylvia::multitest::App;
+
+use crate::factory::create_dummy;
+
+#[rstest]
+fn test_misbehaviour_freezes_lightclient() {
+    let chain_1 = App::default();
+
+    let lightclient_code_id = lightclientCodeId::store_code(&chain_1);
+    let tao_code_id = TaoCodeId::store_code(&chain_1);
+    let application_code_id = AppCodeId::store_code(&chain_1);
+
+    let gov = Addr::unchecked("gov-module");
+    let dao = Addr::unchecked("dao");
+    let alice = Addr::unchecked("alice");
+    let relayer = Addr::unchecked("relayer");
+
+    let tao_contract = tao_code_id.instantiate().call(&gov).unwrap();
+
+    tao_contract
+        .approve_lightclient_code(lightclient_code_id.code_id(), "dummy".to_string())
+        .call(&gov)
+        .unwrap();
+
+    let lightclient_1_contract = create_dummy(&tao_contract, &lightclient_code_id, &dao);
+    let lightclient_2_contract = create_dummy(&tao_contract, &lightclient_code_id, &dao);
+
+    let application_1_contract = application_code_id
+        .instantiate(tao_contract.contract_addr.clone())
+        .with_admin(alice.as_str())
+        .call(&alice)
+        .unwrap();
+    let application_2_contract = application_code_id
+        .instantiate(tao_contract.contract_addr.clone())
+        .with_admin(alice.as_str())
+        .call(&alice)
+        .unwrap();
+
+    let lightclient_1 = (lightclient_1_contract.contract_addr.clone(), vec![]);
+    let lightclient_2 = (lightclient_2_contract.contract_addr.clone(), vec![]);
+
+    application_1_contract
+        .set_allowed_channel(
+            lightclient_1.clone(),
+            lightclient_2.clone(),
+            application_2_contract.contract_addr.clone(),
+        )
+        .call(&alice)
+        .unwrap();
+
+    application_2_contract
+        .set_allowed_channel(
+            lightclient_2.clone(),
+            lightclient_1.clone(),
+            application_1_contract.contract_addr.clone(),
+        )
+        .call(&alice)
+        .unwrap();
+
+    tao_contract
+        .register_application(
+            application_1_contract.contract_addr.clone(),
+            lightclient_1.clone(),
+            lightclient_2.clone(),
+            SenderPolicy::Any,
+        )
+        .call(&alice)
+        .unwrap();
+
+    let packet = |nonce| Packet {
+        header: PacketHeader {
+            lightclient_source: lightclient_1.clone(),
+            lightclient_destination: lightclient_2.clone(),
+            nonce,
+            timeout: chain_1.block_info().time.seconds() + 10,
+        },
+        payloads: vec![Payload {
+            header: PayloadHeader {
+                application_source: application_1_contract.contract_addr.clone(),
+                application_destination: application_2_contract.contract_addr.clone(),
+                funds: vec![],
+                gas_limit: None,
+            },
+            data: b"ping".to_vec(),
+        }],
+    };
+
+    let packet_1 = packet(1);
+
+    tao_contract
+        .send_packet(packet_1.clone(), None)
+        .call(&alice)
+        .unwrap();
+
+    let header = |height, timestamp| to_json_vec(&Header { height, timestamp }).unwrap();
+
+    let no_misbehaviour = to_json_vec(&Misbehaviour {
+        height: Height::new(0, 1),
+        header_1: header(Height::new(0, 1), 10),
+        header_2: header(Height::new(0, 1), 10),
+    })
+    .unwrap();
+
+    assert!(!lightclient_2_contract
+        .check_for_misbehaviour(no_misbehaviour.clone())
+        .unwrap());
+
+    lightclient_2_contract
+        .update_on_misbehaviour(no_misbehaviour)
+        .call(&relayer)
+        .unwrap_err();
+
+    assert_eq!(lightclient_2_contract.status().unwrap(), Status::Active);
+
+    // arbitrary blobs, or headers for other heights, are no evidence
+    for (header_1, header_2) in [
+        (b"header".to_vec(), b"conflicting header".to_vec()),
+        (header(Height::new(0, 1), 10), header(Height::new(0, 2), 11)),
+    ] {
+        let invalid = to_json_vec(&Misbehaviour {
+            height: Height::new(0, 1),
+            header_1,
+            header_2,
+        })
+        .unwrap();
+
+        lightclient_2_contract
+            .check_for_misbehaviour(invalid.clone())
+            .unwrap_err();
+
+        lightclient_2_contract
+            .update_on_misbehaviour(invalid)
+            .call(&relayer)
+            .unwrap_err();
+    }
+
+    assert_eq!(lightclient_2_contract.status().unwrap(), Status::Active);
+
+    // anyone can submit conflicting headers
+    let misbehaviour = to_json_vec(&Misbehaviour {
+        height: Height::new(0, 1),
+        header_1: header(Height::new(0, 1), 10),
+        header_2: header(Height::new(0, 1), 11),
+    })
+    .unwrap();
+
+    assert!(lightclient_2_contract
+        .check_for_misbehaviour(misbehaviour.clone())
+        .unwrap());
+
+    lightclient_2_contract
+        .update_on_misbehaviour(misbehaviour.clone())
+        .call(&relayer)
+        .unwrap();
+
+    assert_eq!(lightclient_2_contract.status().unwrap(), Status::Frozen);
+
+    let frozen = ContractError::LightClientNotActive {
+        lightclient: lightclient_2_contract.contract_addr.clone(),
+        status: Status::Frozen,
+    };
+
+    // the in-flight packet can neither time out nor be followed by new ones
+    assert_eq!(
+        tao_contract
+            .timeout_packet(packet_1.clone(), Height::default(), vec![])
+            .call(&relayer)
+            .unwrap_err(),
+        frozen
+    );
+
+    assert_eq!(
+        tao_contract
+            .send_packet(packet(2), None)
+            .call(&alice)
+            .unwrap_err(),
+        frozen
+    );
+
+    // nor be received through a frozen source light client
+    lightclient_1_contract
+        .update_on_misbehaviour(misbehaviour)
+        .call(&relayer)
+        .unwrap();
+
+    assert_eq!(
+        tao_contract
+            .receive_packet(packet_1, Height::default(), vec![])
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::LightClientNotActive {
+            lightclient: lightclient_1_contract.contract_addr.clone(),
+            status: Status::Frozen,
+        }
+    );
+}
@@ -0,0 +1,439 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+
+use eureka_lightclient_interface::sv::{
+    ExecMsg as LightClientExecMsg, QueryMsg as LightClientQueryMsg,
+};
+use eureka_lightclient_interface::{
+    batch_proof, ClientState, ConsensusState, Height, Status, UpdatePolicy,
+};
+use eureka_lightclient_native_proxy::proto::{
+    self, Any, MerkleRoot, QueryClientStateRequest, QueryClientStateResponse,
+    QueryClientStatusRequest, QueryClientStatusResponse, QueryConsensusStateRequest,
+    QueryConsensusStateResponse, QueryVerifyMembershipRequest, QueryVerifyMembershipResponse,
+    Seconds, TendermintClientState, TendermintConsensusState, CLIENT_STATE_PATH,
+    CLIENT_STATUS_PATH, CONSENSUS_STATE_PATH, TENDERMINT_CLIENT_STATE_TYPE_URL,
+    TENDERMINT_CONSENSUS_STATE_TYPE_URL, VERIFY_MEMBERSHIP_PATH,
+};
+use eureka_lightclient_native_proxy::sv::{InstantiateMsg, QueryMsg};
+use eureka_lightclient_native_proxy::{Contract, ContractError};
+use prost::Message;
+use rstest::rstest;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sylvia::anyhow::{anyhow, bail, Result as AnyResult};
+use sylvia::cw_multi_test::{
+    no_init, BankKeeper, BasicAppBuilder, DistributionKeeper, Executor, FailingModule,
+    GovFailingModule, IbcFailingModule, StakeKeeper, Stargate, WasmKeeper,
+};
+use sylvia::cw_std::testing::{MockApi, MockStorage};
+use sylvia::cw_std::{Addr, Api, Binary, BlockInfo, Empty, GrpcQuery, Querier, StdResult, Storage};
+use sylvia::multitest::App;
+
+const CLIENT_ID: &str = "07-tendermint-0";
+const CHAIN_ID: &str = "counterparty-1";
+// commitment root of every consensus state
+const ROOT: &[u8] = b"app-hash";
+// accepted by the stand-in for every commitment it holds
+const PROOF: &[u8] = b"native-proof";
+
+// native tendermint client of the host chain
+#[derive(Clone)]
+pub struct NativeClient {
+    pub status: String,
+    pub latest_height: Height,
+    // in seconds
+    pub trusting_period: u64,
+    // consensus state timestamps, in seconds
+    pub timestamps: BTreeMap<Height, u64>,
+    // key path -> value, at every height
+    pub commitments: HashMap<Vec<Vec<u8>>, Vec<u8>>,
+}
+
+// stand-in for the ibc-go client queries of the host chain
+#[derive(Clone, Default)]
+pub struct NativeClients(Rc<RefCell<HashMap<String, NativeClient>>>);
+
+impl NativeClients {
+    pub fn insert(&self, client_id: &str, client: NativeClient) {
+        self.0.borrow_mut().insert(client_id.to_string(), client);
+    }
+
+    pub fn set_status(&self, client_id: &str, status: &str) {
+        if let Some(client) = self.0.borrow_mut().get_mut(client_id) {
+            client.status = status.to_string();
+        }
+    }
+
+    fn client(&self, client_id: &str) -> AnyResult<NativeClient> {
+        self.0
+            .borrow()
+            .get(client_id)
+            .cloned()
+            .ok_or_else(|| anyhow!("client not found: {client_id}"))
+    }
+}
+
+fn any(type_url: &str, message: impl Message) -> Option<Any> {
+    Some(Any {
+        type_url: type_url.to_string(),
+        value: message.encode_to_vec(),
+    })
+}
+
+impl Stargate for NativeClients {
+    fn query_grpc(
+        &self,
+        _api: &dyn Api,
+        _storage: &dyn Storage,
+        _querier: &dyn Querier,
+        _block: &BlockInfo,
+        request: GrpcQuery,
+    ) -> AnyResult<Binary> {
+        let data = request.data.as_slice();
+
+        let response = match request.path.as_str() {
+            CLIENT_STATUS_PATH => {
+                let request = QueryClientStatusRequest::decode(data)?;
+                QueryClientStatusResponse {
+                    status: self.client(&request.client_id)?.status,
+                }
+                .encode_to_vec()
+            }
+            CLIENT_STATE_PATH => {
+                let client = self.client(&QueryClientStateRequest::decode(data)?.client_id)?;
+                QueryClientStateResponse {
+                    client_state: any(
+                        TENDERMINT_CLIENT_STATE_TYPE_URL,
+                        TendermintClientState {
+                            chain_id: CHAIN_ID.to_string(),
+                            trusting_period: Some(Seconds {
+                                seconds: client.trusting_period as i64,
+                                nanos: 0,
+                            }),
+                            latest_height: Some(proto::Height {
+                                revision_number: client.latest_height.revision_number,
+                                revision_height: client.latest_height.revision_height,
+                            }),
+                            frozen_height: None,
+                            ..Default::default()
+                        },
+                    ),
+                }
+                .encode_to_vec()
+            }
+            CONSENSUS_STATE_PATH => {
+                let request = QueryConsensusStateRequest::decode(data)?;
+                let height = Height::new(request.revision_number, request.revision_height);
+                let Some(timestamp) = self
+                    .client(&request.client_id)?
+                    .timestamps
+                    .get(&height)
+                    .copied()
+                else {
+                    bail!("consensus state not found: {height}");
+                };
+                QueryConsensusStateResponse {
+                    consensus_state: any(
+                        TENDERMINT_CONSENSUS_STATE_TYPE_URL,
+                        TendermintConsensusState {
+                            timestamp: Some(Seconds {
+                                seconds: timestamp as i64,
+                                nanos: 0,
+                            }),
+                            root: Some(MerkleRoot {
+                                hash: ROOT.to_vec(),
+                            }),
+                        },
+                    ),
+                }
+                .encode_to_vec()
+            }
+            VERIFY_MEMBERSHIP_PATH => {
+                let request = QueryVerifyMembershipRequest::decode(data)?;
+                // as ibc-go, which has no query verifying absence
+                if request.value.is_empty() {
+                    bail!("empty value");
+                }
+                let client = self.client(&request.client_id)?;
+                let height = request.proof_height.unwrap_or_default();
+                if !client
+                    .timestamps
+                    .contains_key(&Height::new(height.revision_number, height.revision_height))
+                {
+                    bail!("consensus state not found");
+                }
+                let stored = client
+                    .commitments
+                    .get(&request.merkle_path.unwrap_or_default().key_path);
+                QueryVerifyMembershipResponse {
+                    success: request.proof == PROOF && stored == Some(&request.value),
+                }
+                .encode_to_vec()
+            }
+            // any path ibc-go does not register
+            path => bail!("unexpected grpc query: {path}"),
+        };
+
+        Ok(Binary::from(response))
+    }
+}
+
+pub type NativeApp = sylvia::cw_multi_test::App<
+    BankKeeper,
+    MockApi,
+    MockStorage,
+    FailingModule<Empty, Empty, Empty>,
+    WasmKeeper<Empty, Empty>,
+    StakeKeeper,
+    DistributionKeeper,
+    IbcFailingModule,
+    GovFailingModule,
+    NativeClients,
+>;
+
+// chain whose ibc-go queries are served by `clients`
+pub fn native_chain(clients: &NativeClients) -> App<NativeApp> {
+    App::new(
+        BasicAppBuilder::<Empty, Empty>::new()
+            .with_stargate(clients.clone())
+            .build(no_init),
+    )
+}
+
+fn native_client(chain: &App<NativeApp>) -> NativeClient {
+    let now = chain.block_info().time.seconds();
+
+    NativeClient {
+        status: "Active".to_string(),
+        latest_height: Height::new(1, 20),
+        trusting_period: 1000,
+        timestamps: BTreeMap::from([
+            (Height::new(1, 10), now - 100),
+            (Height::new(1, 20), now - 50),
+        ]),
+        commitments: HashMap::from([(
+            vec![b"ibc".to_vec(), b"commitments/a".to_vec()],
+            b"value-a".to_vec(),
+        )]),
+    }
+}
+
+// sylvia proxies only run on apps failing gRPC queries, the contract is called through the app
+fn setup(chain: &App<NativeApp>, client_id: &str) -> AnyResult<Addr> {
+    let code_id = chain.app_mut().store_code(Box::new(Contract::new()));
+
+    chain.app_mut().instantiate_contract(
+        code_id,
+        Addr::unchecked("dao"),
+        &InstantiateMsg::new(client_id.to_string()),
+        &[],
+        "native-proxy",
+        None,
+    )
+}
+
+fn query<T: DeserializeOwned>(
+    chain: &App<NativeApp>,
+    lightclient: &Addr,
+    msg: &impl Serialize,
+) -> StdResult<T> {
+    chain.querier().query_wasm_smart(lightclient, msg)
+}
+
+#[rstest]
+fn test_native_proxy_queries() {
+    let clients = NativeClients::default();
+    let chain = native_chain(&clients);
+
+    let now = chain.block_info().time.seconds();
+
+    clients.insert(CLIENT_ID, native_client(&chain));
+
+    // the native client must exist
+    setup(&chain, "07-tendermint-1").unwrap_err();
+
+    let lightclient = setup(&chain, CLIENT_ID).unwrap();
+
+    assert_eq!(
+        query::<String>(&chain, &lightclient, &QueryMsg::client_id()).unwrap(),
+        CLIENT_ID
+    );
+    assert_eq!(
+        query::<Status>(&chain, &lightclient, &LightClientQueryMsg::status()).unwrap(),
+        Status::Active
+    );
+    assert_eq!(
+        query::<Height>(&chain, &lightclient, &LightClientQueryMsg::latest_height()).unwrap(),
+        Height::new(1, 20)
+    );
+    assert_eq!(
+        query::<u64>(
+            &chain,
+            &lightclient,
+            &LightClientQueryMsg::trusting_period()
+        )
+        .unwrap(),
+        1000
+    );
+    assert_eq!(
+        query::<u64>(
+            &chain,
+            &lightclient,
+            &LightClientQueryMsg::timestamp(Height::new(1, 10))
+        )
+        .unwrap(),
+        now - 100
+    );
+    query::<u64>(
+        &chain,
+        &lightclient,
+        &LightClientQueryMsg::timestamp(Height::new(1, 11)),
+    )
+    .unwrap_err();
+
+    let client_state =
+        query::<ClientState>(&chain, &lightclient, &LightClientQueryMsg::client_state()).unwrap();
+    assert_eq!(client_state.chain_id, CHAIN_ID);
+    assert_eq!(client_state.latest_height, Height::new(1, 20));
+    assert_eq!(client_state.frozen_height, None);
+    // the native encoding is passed through
+    assert_eq!(
+        TendermintClientState::decode(client_state.data.as_slice())
+            .unwrap()
+            .chain_id,
+        CHAIN_ID
+    );
+
+    let consensus_state = query::<ConsensusState>(
+        &chain,
+        &lightclient,
+        &LightClientQueryMsg::consensus_state(Height::new(1, 20)),
+    )
+    .unwrap();
+    assert_eq!(consensus_state.timestamp, now - 50);
+    assert_eq!(consensus_state.root, ROOT);
+
+    // the native client is only updated through the host chain
+    assert_eq!(
+        query::<UpdatePolicy>(&chain, &lightclient, &LightClientQueryMsg::update_policy()).unwrap(),
+        UpdatePolicy::AllowList(vec![])
+    );
+    assert_eq!(
+        chain
+            .app_mut()
+            .execute_contract(
+                Addr::unchecked("relayer"),
+                lightclient.clone(),
+                &LightClientExecMsg::update(b"header".to_vec()),
+                &[],
+            )
+            .unwrap_err()
+            .downcast::<ContractError>()
+            .unwrap(),
+        ContractError::UpdateNotSupported
+    );
+
+    clients.set_status(CLIENT_ID, "Frozen");
+    assert_eq!(
+        query::<Status>(&chain, &lightclient, &LightClientQueryMsg::status()).unwrap(),
+        Status::Frozen
+    );
+
+    clients.set_status(CLIENT_ID, "Unauthorized");
+    assert_eq!(
+        query::<Status>(&chain, &lightclient, &LightClientQueryMsg::status()).unwrap(),
+        Status::Inactive
+    );
+}
+
+#[rstest]
+fn test_native_proxy_membership() {
+    let clients = NativeClients::default();
+    let chain = native_chain(&clients);
+
+    clients.insert(CLIENT_ID, native_client(&chain));
+
+    let lightclient = setup(&chain, CLIENT_ID).unwrap();
+
+    let height = Height::new(1, 20);
+
+    let check_membership = |key: &[u8], value: &[u8], height: Height, proof: &[u8]| {
+        query::<bool>(
+            &chain,
+            &lightclient,
+            &LightClientQueryMsg::check_membership(
+                key.to_vec(),
+                value.to_vec(),
+                b"ibc".to_vec(),
+                height,
+                proof.to_vec(),
+            ),
+        )
+    };
+
+    let check_non_membership = |key: &[u8]| {
+        query::<bool>(
+            &chain,
+            &lightclient,
+            &LightClientQueryMsg::check_non_membership(
+                key.to_vec(),
+                b"ibc".to_vec(),
+                height,
+                PROOF.to_vec(),
+            ),
+        )
+    };
+
+    assert!(check_membership(b"commitments/a", b"value-a", height, PROOF).unwrap());
+
+    // wrong value
+    check_membership(b"commitments/a", b"value-b", height, PROOF).unwrap_err();
+
+    // wrong proof
+    check_membership(b"commitments/a", b"value-a", height, b"forged").unwrap_err();
+
+    // unknown height
+    check_membership(b"commitments/a", b"value-a", Height::new(1, 11), PROOF).unwrap_err();
+
+    // empty values are rejected by ibc-go
+    check_membership(b"commitments/b", b"", height, PROOF).unwrap_err();
+
+    // ibc-go cannot prove absence to contracts
+    assert!(check_non_membership(b"commitments/b")
+        .unwrap_err()
+        .to_string()
+        .contains(&ContractError::NonMembershipNotSupported.to_string()));
+
+    // one native proof per item
+    let verify_batch_membership = |items: &[(&[u8], &[u8])], proofs: &[Vec<u8>]| {
+        query::<bool>(
+            &chain,
+            &lightclient,
+            &LightClientQueryMsg::verify_batch_membership(
+                items
+                    .iter()
+                    .map(|(key, value)| (key.to_vec(), value.to_vec()))
+                    .collect(),
+                b"ibc".to_vec(),
+                height,
+                batch_proof(proofs).unwrap(),
+            ),
+        )
+    };
+
+    assert!(verify_batch_membership(&[(b"commitments/a", b"value-a")], &[PROOF.to_vec()]).unwrap());
+
+    verify_batch_membership(&[(b"commitments/a", b"value-a")], &[b"forged".to_vec()]).unwrap_err();
+
+    // missing proof
+    verify_batch_membership(
+        &[
+            (b"commitments/a", b"value-a"),
+            (b"commitments/a", b"value-a"),
+        ],
+        &[PROOF.to_vec()],
+    )
+    .unwrap_err();
+}
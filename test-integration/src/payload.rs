@@ -0,0 +1,244 @@
+use eureka_application_interface::{Application, PayloadResult};
+use eureka_application_pingpong::sv::mt::{CodeId as AppCodeId, ContractProxy};
+use eureka_lightclient_dummy::sv::mt::CodeId as lightclientCodeId;
+use eureka_lightclient_interface::Height;
+use eureka_tao::sv::mt::{CodeId as TaoCodeId, ContractProxy as TaoContractProxy};
+use eureka_tao::{Packet, PacketHeader, Payload, PayloadHeader, SenderPolicy};
+use rstest::rstest;
+use sylvia::contract;
+use sylvia::cw_std::{Addr, Coin, Response, StdError, StdResult};
+use sylvia::multitest::App;
+
+use crate::factory::create_dummy;
+use sylvia::types::{ExecCtx, InstantiateCtx};
+
+use self::sv::mt::CodeId as FaultyAppCodeId;
+
+// application that accepts every packet it sends, but fails to receive any
+pub struct FaultyApplication;
+
+#[contract]
+#[sv::error(StdError)]
+#[sv::messages(eureka_application_interface)]
+impl FaultyApplication {
+    pub const fn new() -> Self {
+        Self
+    }
+
+    #[sv::msg(instantiate)]
+    fn instantiate(&self, _ctx: InstantiateCtx) -> StdResult<Response> {
+        Ok(Response::default())
+    }
+}
+
+impl Application for FaultyApplication {
+    type Error = StdError;
+
+    fn send(
+        &self,
+        _ctx: ExecCtx,
+        _lightclient_local: (Addr, Vec<u8>),
+        _lightclient_remote: (Addr, Vec<u8>),
+        _application_remote: Addr,
+        _packet: Vec<u8>,
+        _packet_sender: Addr,
+    ) -> Result<Response, Self::Error> {
+        Ok(Response::default())
+    }
+
+    fn receive(
+        &self,
+        _ctx: ExecCtx,
+        _lightclient_local: (Addr, Vec<u8>),
+        _lightclient_remote: (Addr, Vec<u8>),
+        _application_remote: Addr,
+        _packet: Vec<u8>,
+        _relayer: Addr,
+        _sent_funds: Vec<Coin>,
+    ) -> Result<Response, Self::Error> {
+        Err(StdError::generic_err("faulty application"))
+    }
+
+    fn timeout(
+        &self,
+        _ctx: ExecCtx,
+        _lightclient_local: (Addr, Vec<u8>),
+        _lightclient_remote: (Addr, Vec<u8>),
+        _application_remote: Addr,
+        _packet: Vec<u8>,
+        _relayer: Addr,
+        _sent_funds: Vec<Coin>,
+    ) -> Result<Response, Self::Error> {
+        Ok(Response::default())
+    }
+
+    fn acknowledge(
+        &self,
+        _ctx: ExecCtx,
+        _lightclient_local: (Addr, Vec<u8>),
+        _lightclient_remote: (Addr, Vec<u8>),
+        _application_remote: Addr,
+        _packet: Vec<u8>,
+        _result: PayloadResult,
+        _relayer: Addr,
+        _sent_funds: Vec<Coin>,
+    ) -> Result<Response, Self::Error> {
+        Ok(Response::default())
+    }
+}
+
+#[rstest]
+fn test_faulty_application_payload() {
+    let chain_1 = App::default();
+
+    let lightclient_code_id = lightclientCodeId::store_code(&chain_1);
+    let tao_code_id = TaoCodeId::store_code(&chain_1);
+    let application_code_id = AppCodeId::store_code(&chain_1);
+    let faulty_application_code_id = FaultyAppCodeId::store_code(&chain_1);
+
+    let gov = Addr::unchecked("gov-module");
+    let dao = Addr::unchecked("dao");
+    let alice = Addr::unchecked("alice");
+    let relayer = Addr::unchecked("relayer");
+
+    let tao_contract = tao_code_id.instantiate().call(&gov).unwrap();
+
+    tao_contract
+        .approve_lightclient_code(lightclient_code_id.code_id(), "dummy".to_string())
+        .call(&gov)
+        .unwrap();
+
+    let lightclient_1_contract = create_dummy(&tao_contract, &lightclient_code_id, &dao);
+    let lightclient_2_contract = create_dummy(&tao_contract, &lightclient_code_id, &dao);
+
+    let application_1_contract = application_code_id
+        .instantiate(tao_contract.contract_addr.clone())
+        .with_admin(alice.as_str())
+        .call(&alice)
+        .unwrap();
+    let application_2_contract = application_code_id
+        .instantiate(tao_contract.contract_addr.clone())
+        .with_admin(alice.as_str())
+        .call(&alice)
+        .unwrap();
+    let faulty_application_contract = faulty_application_code_id
+        .instantiate()
+        .with_admin(alice.as_str())
+        .call(&alice)
+        .unwrap();
+
+    let lightclient_1 = (lightclient_1_contract.contract_addr.clone(), vec![]);
+    let lightclient_2 = (lightclient_2_contract.contract_addr.clone(), vec![]);
+
+    application_1_contract
+        .set_allowed_channel(
+            lightclient_1.clone(),
+            lightclient_2.clone(),
+            application_2_contract.contract_addr.clone(),
+        )
+        .call(&alice)
+        .unwrap();
+
+    application_2_contract
+        .set_allowed_channel(
+            lightclient_2.clone(),
+            lightclient_1.clone(),
+            application_1_contract.contract_addr.clone(),
+        )
+        .call(&alice)
+        .unwrap();
+
+    tao_contract
+        .register_application(
+            application_1_contract.contract_addr.clone(),
+            lightclient_1.clone(),
+            lightclient_2.clone(),
+            SenderPolicy::Any,
+        )
+        .call(&alice)
+        .unwrap();
+
+    tao_contract
+        .register_application(
+            faulty_application_contract.contract_addr.clone(),
+            lightclient_1.clone(),
+            lightclient_2.clone(),
+            SenderPolicy::Any,
+        )
+        .call(&alice)
+        .unwrap();
+
+    let packet = Packet {
+        header: PacketHeader {
+            lightclient_source: lightclient_1.clone(),
+            lightclient_destination: lightclient_2.clone(),
+            nonce: 1,
+            timeout: chain_1.block_info().time.seconds() + 10,
+        },
+        payloads: vec![
+            Payload {
+                header: PayloadHeader {
+                    application_source: application_1_contract.contract_addr.clone(),
+                    application_destination: application_2_contract.contract_addr.clone(),
+                    funds: vec![],
+                    gas_limit: None,
+                },
+                data: b"ping".to_vec(),
+            },
+            Payload {
+                header: PayloadHeader {
+                    application_source: faulty_application_contract.contract_addr.clone(),
+                    application_destination: faulty_application_contract.contract_addr.clone(),
+                    funds: vec![],
+                    gas_limit: Some(1_000_000),
+                },
+                data: b"ping".to_vec(),
+            },
+        ],
+    };
+
+    tao_contract
+        .send_packet(packet.clone(), None)
+        .call(&alice)
+        .unwrap();
+
+    // the faulty payload does not block the packet
+    tao_contract
+        .receive_packet(packet.clone(), Height::default(), vec![])
+        .call(&relayer)
+        .unwrap();
+
+    assert_eq!(
+        application_2_contract.received_value().unwrap(),
+        format!(
+            "{}(via {}) sent ping",
+            application_1_contract.contract_addr, tao_contract.contract_addr
+        )
+    );
+
+    let acknowledgement = tao_contract
+        .packet_acknowledgement(lightclient_1, lightclient_2, 1)
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(acknowledgement.relayer, relayer);
+    assert_eq!(acknowledgement.results.len(), 2);
+    assert_eq!(acknowledgement.results[0], PayloadResult::Success);
+    assert!(matches!(
+        acknowledgement.results[1],
+        PayloadResult::Error(_)
+    ));
+
+    tao_contract
+        .acknowledge_packet(packet, acknowledgement, Height::default(), vec![])
+        .call(&relayer)
+        .unwrap();
+
+    assert_eq!(
+        application_1_contract.acknowledged_value().unwrap(),
+        format!(
+            "{}(via {}) acknowledged ping with Success",
+            application_2_contract.contract_addr, tao_contract.contract_addr
+        )
+    );
+}
@@ -0,0 +1,256 @@
+use eureka_application_interface::PayloadResult;
+use eureka_application_pingpong::sv::mt::{CodeId as AppCodeId, ContractProxy};
+use eureka_lightclient_dummy::sv::mt::{
+    CodeId as lightclientCodeId, ContractProxy as LightClientContractProxy,
+};
+use eureka_lightclient_dummy::Header;
+use eureka_lightclient_interface::sv::mt::LightClientProxy;
+use eureka_lightclient_interface::Height;
+use eureka_tao::sv::mt::{CodeId as TaoCodeId, ContractProxy as TaoContractProxy};
+use eureka_tao::{
+    Acknowledgement, ContractError, Packet, PacketHeader, Payload, PayloadHeader, SenderPolicy,
+};
+use rstest::rstest;
+use sylvia::cw_std::{to_json_vec, Addr};
+use sylvia::multitest::App;
+
+use crate::factory::create_dummy;
+
+#[rstest]
+fn test_update_client_and_receive() {
+    let chain_1 = App::default();
+
+    let lightclient_code_id = lightclientCodeId::store_code(&chain_1);
+    let tao_code_id = TaoCodeId::store_code(&chain_1);
+    let application_code_id = AppCodeId::store_code(&chain_1);
+
+    let gov = Addr::unchecked("gov-module");
+    let dao = Addr::unchecked("dao");
+    let alice = Addr::unchecked("alice");
+    let relayer = Addr::unchecked("relayer");
+
+    let tao_contract = tao_code_id.instantiate().call(&gov).unwrap();
+
+    tao_contract
+        .approve_lightclient_code(lightclient_code_id.code_id(), "dummy".to_string())
+        .call(&gov)
+        .unwrap();
+
+    let lightclient_1_contract = create_dummy(&tao_contract, &lightclient_code_id, &dao);
+    let lightclient_2_contract = create_dummy(&tao_contract, &lightclient_code_id, &dao);
+
+    let application_1_contract = application_code_id
+        .instantiate(tao_contract.contract_addr.clone())
+        .with_admin(alice.as_str())
+        .call(&alice)
+        .unwrap();
+    let application_2_contract = application_code_id
+        .instantiate(tao_contract.contract_addr.clone())
+        .with_admin(alice.as_str())
+        .call(&alice)
+        .unwrap();
+
+    let lightclient_1 = (lightclient_1_contract.contract_addr.clone(), vec![]);
+    let lightclient_2 = (lightclient_2_contract.contract_addr.clone(), vec![]);
+
+    application_1_contract
+        .set_allowed_channel(
+            lightclient_1.clone(),
+            lightclient_2.clone(),
+            application_2_contract.contract_addr.clone(),
+        )
+        .call(&alice)
+        .unwrap();
+
+    application_2_contract
+        .set_allowed_channel(
+            lightclient_2.clone(),
+            lightclient_1.clone(),
+            application_1_contract.contract_addr.clone(),
+        )
+        .call(&alice)
+        .unwrap();
+
+    tao_contract
+        .register_application(
+            application_1_contract.contract_addr.clone(),
+            lightclient_1.clone(),
+            lightclient_2.clone(),
+            SenderPolicy::Any,
+        )
+        .call(&alice)
+        .unwrap();
+
+    let packet = |nonce| Packet {
+        header: PacketHeader {
+            lightclient_source: lightclient_1.clone(),
+            lightclient_destination: lightclient_2.clone(),
+            nonce,
+            timeout: chain_1.block_info().time.seconds() + 10,
+        },
+        payloads: vec![Payload {
+            header: PayloadHeader {
+                application_source: application_1_contract.contract_addr.clone(),
+                application_destination: application_2_contract.contract_addr.clone(),
+                funds: vec![],
+                gas_limit: None,
+            },
+            data: b"ping".to_vec(),
+        }],
+    };
+
+    let packet_1 = packet(1);
+    let packet_2 = packet(2);
+
+    tao_contract
+        .send_packet(packet_1.clone(), None)
+        .call(&alice)
+        .unwrap();
+    tao_contract
+        .send_packet(packet_2.clone(), None)
+        .call(&alice)
+        .unwrap();
+
+    let headers = vec![
+        to_json_vec(&Header {
+            height: Height::new(0, 1),
+            timestamp: chain_1.block_info().time.seconds(),
+        })
+        .unwrap(),
+        to_json_vec(&Header {
+            height: Height::new(0, 2),
+            timestamp: chain_1.block_info().time.seconds(),
+        })
+        .unwrap(),
+    ];
+    let packets = vec![packet_1.clone(), packet_2.clone()];
+
+    assert_eq!(
+        tao_contract
+            .update_client_and_receive(
+                lightclient_2.0.clone(),
+                headers.clone(),
+                packets.clone(),
+                Height::new(0, 2),
+                vec![],
+            )
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::SourceLightClientMismatch {
+            expected: lightclient_2.0.clone(),
+            got: lightclient_1.0.clone(),
+        }
+    );
+
+    // the dummy only trusts headers from its allow-list, tao submits them on behalf of the relayer
+    lightclient_1_contract
+        .set_submitters(vec![relayer.clone()])
+        .call(&dao)
+        .unwrap();
+
+    tao_contract
+        .update_client_and_receive(
+            lightclient_1.0.clone(),
+            headers.clone(),
+            packets.clone(),
+            Height::new(0, 2),
+            vec![],
+        )
+        .call(&relayer)
+        .unwrap_err();
+
+    lightclient_1_contract
+        .set_submitters(vec![tao_contract.contract_addr.clone()])
+        .call(&dao)
+        .unwrap();
+
+    // relayers outside the allow-list are not relayed by tao
+    assert_eq!(
+        tao_contract
+            .update_client_and_receive(
+                lightclient_1.0.clone(),
+                headers.clone(),
+                packets.clone(),
+                Height::new(0, 2),
+                vec![],
+            )
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::Unauthorized
+    );
+
+    lightclient_1_contract
+        .set_submitters(vec![tao_contract.contract_addr.clone(), relayer.clone()])
+        .call(&dao)
+        .unwrap();
+
+    // the same packet twice in a batch
+    assert_eq!(
+        tao_contract
+            .update_client_and_receive(
+                lightclient_1.0.clone(),
+                headers.clone(),
+                vec![packet_1.clone(), packet_1.clone()],
+                Height::new(0, 2),
+                vec![],
+            )
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::PacketAlreadyReceived
+    );
+
+    // packets of a batch are proven under the same commitment prefix
+    let mut prefixed = packet_2.clone();
+    prefixed.header.lightclient_source.1 = b"ibc".to_vec();
+
+    assert_eq!(
+        tao_contract
+            .update_client_and_receive(
+                lightclient_1.0.clone(),
+                headers.clone(),
+                vec![packet_1.clone(), prefixed],
+                Height::new(0, 2),
+                vec![],
+            )
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::CommitmentPrefixMismatch
+    );
+
+    tao_contract
+        .update_client_and_receive(
+            lightclient_1.0.clone(),
+            headers,
+            packets,
+            Height::new(0, 2),
+            vec![],
+        )
+        .call(&relayer)
+        .unwrap();
+
+    assert_eq!(
+        lightclient_1_contract.latest_height().unwrap(),
+        Height::new(0, 2)
+    );
+
+    // the relayer is credited, not tao
+    for nonce in [1, 2] {
+        assert_eq!(
+            tao_contract
+                .packet_acknowledgement(lightclient_1.clone(), lightclient_2.clone(), nonce)
+                .unwrap(),
+            Some(Acknowledgement {
+                relayer: relayer.clone(),
+                results: vec![PayloadResult::Success],
+            })
+        );
+    }
+
+    assert_eq!(
+        tao_contract
+            .receive_packet(packet_1, Height::new(0, 2), vec![])
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::PacketAlreadyReceived
+    );
+}
@@ -0,0 +1,587 @@
+use ed25519_dalek::Signer;
+use eureka_ics23_verifier::apply_prefix;
+use eureka_lightclient_interface::sv::mt::LightClientProxy;
+use eureka_lightclient_interface::{Height, Status};
+use eureka_lightclient_solomachine::sv::mt::CodeId as SoloMachineCodeId;
+use eureka_lightclient_solomachine::sv::InstantiateMsg as SoloMachineInstantiateMsg;
+use eureka_lightclient_solomachine::{
+    header_path, ClientState, ConsensusState, Contract, ContractError, Header, HeaderData,
+    Misbehaviour, PublicKey, SignBytes, SignatureAndData, TimestampedSignature,
+};
+use eureka_tao::commitment::{connection_key, packet_commitment, packet_commitment_path};
+use eureka_tao::sv::mt::{CodeId as TaoCodeId, ContractProxy as TaoContractProxy};
+use eureka_tao::{ContractError as TaoError, Packet, PacketHeader};
+use k256::ecdsa::signature::hazmat::PrehashSigner;
+use rstest::rstest;
+use sylvia::cw_multi_test::BasicApp;
+use sylvia::cw_std::{to_json_vec, Addr};
+use sylvia::multitest::{App, Proxy};
+
+use crate::factory::{create_lightclient, query_error};
+
+const DIVERSIFIER: &str = "solo";
+
+enum Key {
+    Secp256k1(k256::ecdsa::SigningKey),
+    Ed25519(ed25519_dalek::SigningKey),
+}
+
+impl Key {
+    fn public_key(&self) -> PublicKey {
+        match self {
+            Self::Secp256k1(key) => PublicKey::Secp256k1(
+                key.verifying_key()
+                    .to_encoded_point(true)
+                    .as_bytes()
+                    .to_vec(),
+            ),
+            Self::Ed25519(key) => PublicKey::Ed25519(key.verifying_key().to_bytes().to_vec()),
+        }
+    }
+
+    fn sign(
+        &self,
+        sequence: u64,
+        timestamp: u64,
+        path: Vec<Vec<u8>>,
+        data: Option<Vec<u8>>,
+    ) -> Vec<u8> {
+        let digest = SignBytes {
+            sequence,
+            timestamp,
+            diversifier: DIVERSIFIER.to_string(),
+            path,
+            data,
+        }
+        .digest()
+        .unwrap();
+
+        match self {
+            Self::Secp256k1(key) => {
+                let signature: k256::ecdsa::Signature = key.sign_prehash(&digest).unwrap();
+                signature.to_bytes().to_vec()
+            }
+            Self::Ed25519(key) => key.sign(&digest).to_bytes().to_vec(),
+        }
+    }
+
+    fn header(&self, sequence: u64, timestamp: u64, new_key: &Self) -> Vec<u8> {
+        let data = HeaderData {
+            new_public_key: new_key.public_key(),
+            new_diversifier: DIVERSIFIER.to_string(),
+        };
+
+        to_json_vec(&Header {
+            timestamp,
+            signature: self.sign(
+                sequence,
+                timestamp,
+                header_path(),
+                Some(to_json_vec(&data).unwrap()),
+            ),
+            new_public_key: data.new_public_key,
+            new_diversifier: data.new_diversifier,
+        })
+        .unwrap()
+    }
+
+    fn proof(&self, sequence: u64, timestamp: u64, key: &[u8], value: Option<&[u8]>) -> Vec<u8> {
+        to_json_vec(&TimestampedSignature {
+            signature: self.sign(
+                sequence,
+                timestamp,
+                apply_prefix(b"ibc", key),
+                value.map(<[u8]>::to_vec),
+            ),
+            timestamp,
+        })
+        .unwrap()
+    }
+}
+
+fn secp256k1(seed: u8) -> Key {
+    Key::Secp256k1(k256::ecdsa::SigningKey::from_bytes(&[seed; 32].into()).unwrap())
+}
+
+fn ed25519(seed: u8) -> Key {
+    Key::Ed25519(ed25519_dalek::SigningKey::from_bytes(&[seed; 32]))
+}
+
+fn client_state(key: &Key) -> ClientState {
+    ClientState {
+        sequence: 1,
+        frozen: false,
+        consensus_state: ConsensusState {
+            public_key: key.public_key(),
+            diversifier: DIVERSIFIER.to_string(),
+            timestamp: 100,
+        },
+    }
+}
+
+// instantiated by dao, which consumes the proofs in place of tao
+fn setup<'a>(chain: &'a App<BasicApp>, key: &Key) -> Proxy<'a, BasicApp, Contract> {
+    SoloMachineCodeId::store_code(chain)
+        .instantiate(client_state(key))
+        .call(&Addr::unchecked("dao"))
+        .unwrap()
+}
+
+#[rstest]
+fn test_solomachine_update_and_rotation() {
+    let chain = App::default();
+
+    let relayer = Addr::unchecked("relayer");
+
+    let key_a = secp256k1(1);
+    let key_b = ed25519(2);
+
+    let lightclient = setup(&chain, &key_a);
+
+    assert_eq!(lightclient.latest_height().unwrap(), Height::new(0, 1));
+
+    // same key, advances the sequence
+    lightclient
+        .update(key_a.header(1, 110, &key_a))
+        .call(&relayer)
+        .unwrap();
+
+    assert_eq!(lightclient.latest_height().unwrap(), Height::new(0, 2));
+    assert_eq!(lightclient.timestamp(Height::new(0, 2)).unwrap(), 110);
+
+    // replaying a header signed for a consumed sequence
+    assert_eq!(
+        lightclient
+            .update(key_a.header(1, 110, &key_a))
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::InvalidSignature
+    );
+
+    assert_eq!(
+        lightclient
+            .update(key_a.header(2, 90, &key_a))
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::TimestampTooOld {
+            timestamp: 90,
+            consensus_timestamp: 110
+        }
+    );
+
+    // rotation to an ed25519 key
+    lightclient
+        .update(key_a.header(2, 120, &key_b))
+        .call(&relayer)
+        .unwrap();
+
+    assert_eq!(
+        lightclient
+            .update(key_a.header(3, 130, &key_a))
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::InvalidSignature
+    );
+
+    lightclient
+        .update(key_b.header(3, 130, &key_b))
+        .call(&relayer)
+        .unwrap();
+
+    assert_eq!(lightclient.latest_height().unwrap(), Height::new(0, 4));
+    assert_eq!(lightclient.status().unwrap(), Status::Active);
+}
+
+#[rstest]
+fn test_solomachine_membership() {
+    let chain = App::default();
+
+    let relayer = Addr::unchecked("relayer");
+
+    let key_a = secp256k1(1);
+
+    let lightclient = setup(&chain, &key_a);
+
+    let height = Height::new(0, 1);
+
+    assert!(lightclient
+        .check_membership(
+            b"commitments/a".to_vec(),
+            b"value-a".to_vec(),
+            b"ibc".to_vec(),
+            height,
+            key_a.proof(1, 100, b"commitments/a", Some(b"value-a")),
+        )
+        .unwrap());
+
+    assert_eq!(
+        lightclient
+            .check_membership(
+                b"commitments/a".to_vec(),
+                b"value-b".to_vec(),
+                b"ibc".to_vec(),
+                height,
+                key_a.proof(1, 100, b"commitments/a", Some(b"value-a")),
+            )
+            .unwrap_err()
+            .to_string(),
+        query_error(ContractError::InvalidSignature)
+    );
+
+    assert!(lightclient
+        .check_non_membership(
+            b"receipts/b".to_vec(),
+            b"ibc".to_vec(),
+            height,
+            key_a.proof(1, 100, b"receipts/b", None),
+        )
+        .unwrap());
+
+    // a membership signature does not prove absence
+    assert_eq!(
+        lightclient
+            .check_non_membership(
+                b"commitments/a".to_vec(),
+                b"ibc".to_vec(),
+                height,
+                key_a.proof(1, 100, b"commitments/a", Some(b"value-a")),
+            )
+            .unwrap_err()
+            .to_string(),
+        query_error(ContractError::InvalidSignature)
+    );
+
+    lightclient
+        .update(key_a.header(1, 110, &key_a))
+        .call(&relayer)
+        .unwrap();
+
+    // proofs are only valid for the current sequence
+    assert_eq!(
+        lightclient
+            .check_membership(
+                b"commitments/a".to_vec(),
+                b"value-a".to_vec(),
+                b"ibc".to_vec(),
+                height,
+                key_a.proof(1, 100, b"commitments/a", Some(b"value-a")),
+            )
+            .unwrap_err()
+            .to_string(),
+        query_error(ContractError::SequenceMismatch {
+            height,
+            sequence: 2
+        })
+    );
+
+    assert!(lightclient
+        .check_membership(
+            b"commitments/a".to_vec(),
+            b"value-a".to_vec(),
+            b"ibc".to_vec(),
+            Height::new(0, 2),
+            key_a.proof(2, 110, b"commitments/a", Some(b"value-a")),
+        )
+        .unwrap());
+}
+
+#[rstest]
+fn test_solomachine_misbehaviour() {
+    let chain = App::default();
+
+    let relayer = Addr::unchecked("relayer");
+
+    let key_a = secp256k1(1);
+
+    let lightclient = setup(&chain, &key_a);
+
+    let signature = |value: &[u8]| SignatureAndData {
+        signature: key_a.sign(
+            5,
+            100,
+            apply_prefix(b"ibc", b"commitments/a"),
+            Some(value.to_vec()),
+        ),
+        path: apply_prefix(b"ibc", b"commitments/a"),
+        data: Some(value.to_vec()),
+        timestamp: 100,
+    };
+
+    let no_misbehaviour = to_json_vec(&Misbehaviour {
+        sequence: 5,
+        signature_1: signature(b"value-a"),
+        signature_2: signature(b"value-a"),
+    })
+    .unwrap();
+
+    assert!(!lightclient
+        .check_for_misbehaviour(no_misbehaviour.clone())
+        .unwrap());
+
+    assert_eq!(
+        lightclient
+            .update_on_misbehaviour(no_misbehaviour)
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::NoMisbehaviour
+    );
+
+    let misbehaviour = to_json_vec(&Misbehaviour {
+        sequence: 5,
+        signature_1: signature(b"value-a"),
+        signature_2: signature(b"value-b"),
+    })
+    .unwrap();
+
+    lightclient
+        .update_on_misbehaviour(misbehaviour)
+        .call(&relayer)
+        .unwrap();
+
+    assert_eq!(lightclient.status().unwrap(), Status::Frozen);
+
+    assert_eq!(
+        lightclient
+            .update(key_a.header(1, 110, &key_a))
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::ClientFrozen
+    );
+}
+
+#[rstest]
+fn test_solomachine_consume_proofs() {
+    let chain = App::default();
+
+    let dao = Addr::unchecked("dao");
+    let relayer = Addr::unchecked("relayer");
+
+    let key_a = secp256k1(1);
+
+    let lightclient = setup(&chain, &key_a);
+
+    assert_eq!(
+        lightclient
+            .consume_proofs(Height::new(0, 1), 1)
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::Unauthorized
+    );
+
+    assert_eq!(
+        lightclient
+            .consume_proofs(Height::new(0, 2), 1)
+            .call(&dao)
+            .unwrap_err(),
+        ContractError::SequenceMismatch {
+            height: Height::new(0, 2),
+            sequence: 1
+        }
+    );
+
+    lightclient
+        .consume_proofs(Height::new(0, 1), 1)
+        .call(&dao)
+        .unwrap();
+
+    assert_eq!(lightclient.latest_height().unwrap(), Height::new(0, 2));
+    assert_eq!(lightclient.timestamp(Height::new(0, 2)).unwrap(), 100);
+
+    // passed sequences are not kept
+    assert_eq!(
+        lightclient.consensus_timestamp(Height::new(0, 1)).unwrap(),
+        None
+    );
+
+    // a consumed proof cannot be replayed
+    assert_eq!(
+        lightclient
+            .check_membership(
+                b"commitments/a".to_vec(),
+                b"value-a".to_vec(),
+                b"ibc".to_vec(),
+                Height::new(0, 2),
+                key_a.proof(1, 100, b"commitments/a", Some(b"value-a")),
+            )
+            .unwrap_err()
+            .to_string(),
+        query_error(ContractError::InvalidSignature)
+    );
+
+    // batch items are signed at consecutive sequences
+    let items = vec![
+        (b"commitments/a".to_vec(), b"value-a".to_vec()),
+        (b"commitments/b".to_vec(), b"value-b".to_vec()),
+    ];
+
+    let batch_proof = |sequences: [u64; 2]| {
+        to_json_vec(&vec![
+            key_a.proof(sequences[0], 100, b"commitments/a", Some(b"value-a")),
+            key_a.proof(sequences[1], 100, b"commitments/b", Some(b"value-b")),
+        ])
+        .unwrap()
+    };
+
+    assert_eq!(
+        lightclient
+            .verify_batch_membership(
+                items.clone(),
+                b"ibc".to_vec(),
+                Height::new(0, 2),
+                batch_proof([2, 2]),
+            )
+            .unwrap_err()
+            .to_string(),
+        query_error(ContractError::InvalidSignature)
+    );
+
+    assert!(lightclient
+        .verify_batch_membership(
+            items.clone(),
+            b"ibc".to_vec(),
+            Height::new(0, 2),
+            batch_proof([2, 3]),
+        )
+        .unwrap());
+
+    assert_eq!(
+        lightclient
+            .verify_batch_membership(
+                items[..1].to_vec(),
+                b"ibc".to_vec(),
+                Height::new(0, 2),
+                batch_proof([2, 3]),
+            )
+            .unwrap_err()
+            .to_string(),
+        query_error(ContractError::ProofCountMismatch {
+            expected: 1,
+            got: 2
+        })
+    );
+
+    lightclient
+        .consume_proofs(Height::new(0, 2), 2)
+        .call(&dao)
+        .unwrap();
+
+    assert_eq!(lightclient.latest_height().unwrap(), Height::new(0, 4));
+}
+
+#[rstest]
+fn test_solomachine_packets_in_turn() {
+    let chain = App::default();
+
+    let gov = Addr::unchecked("gov-module");
+    let dao = Addr::unchecked("dao");
+    let relayer = Addr::unchecked("relayer");
+
+    let key_a = secp256k1(1);
+
+    let solomachine_code_id = SoloMachineCodeId::store_code(&chain);
+    let tao_contract = TaoCodeId::store_code(&chain)
+        .instantiate()
+        .call(&gov)
+        .unwrap();
+
+    tao_contract
+        .approve_lightclient_code(solomachine_code_id.code_id(), "solomachine".to_string())
+        .call(&gov)
+        .unwrap();
+
+    let lightclient: Proxy<'_, BasicApp, Contract> = create_lightclient(
+        &tao_contract,
+        &dao,
+        solomachine_code_id.code_id(),
+        b"ibc",
+        &SoloMachineInstantiateMsg::new(client_state(&key_a)),
+    );
+
+    let lightclient_source = (lightclient.contract_addr.clone(), b"ibc".to_vec());
+    let lightclient_destination = (Addr::unchecked("counterparty"), b"ibc".to_vec());
+
+    let packet = |nonce| Packet {
+        header: PacketHeader {
+            lightclient_source: lightclient_source.clone(),
+            lightclient_destination: lightclient_destination.clone(),
+            nonce,
+            timeout: chain.block_info().time.seconds() + 10,
+        },
+        payloads: vec![],
+    };
+
+    // the solo machine signs each packet commitment at its current sequence
+    let proof = |packet: &Packet, sequence| {
+        let key = packet_commitment_path(
+            &connection_key(&lightclient_source, &lightclient_destination),
+            packet.header.nonce,
+        );
+        let value = packet_commitment(packet).unwrap();
+
+        let signature = SignatureAndData {
+            signature: key_a.sign(
+                sequence,
+                100,
+                apply_prefix(b"ibc", &key),
+                Some(value.clone()),
+            ),
+            path: apply_prefix(b"ibc", &key),
+            data: Some(value.clone()),
+            timestamp: 100,
+        };
+
+        (key_a.proof(sequence, 100, &key, Some(&value)), signature)
+    };
+
+    let (proof_1, _) = proof(&packet(1), 1);
+    let (proof_2, signature_2) = proof(&packet(2), 2);
+
+    tao_contract
+        .receive_packet(packet(1), Height::new(0, 1), proof_1.clone())
+        .call(&relayer)
+        .unwrap();
+
+    assert_eq!(lightclient.latest_height().unwrap(), Height::new(0, 2));
+
+    // the accepted proof is consumed with its sequence, which is no longer tracked
+    assert_eq!(
+        tao_contract
+            .receive_packet(packet(3), Height::new(0, 1), proof_1)
+            .call(&relayer)
+            .unwrap_err(),
+        TaoError::ConsensusStateNotFound {
+            height: Height::new(0, 1),
+        }
+    );
+
+    tao_contract
+        .receive_packet(packet(2), Height::new(0, 2), proof_2)
+        .call(&relayer)
+        .unwrap();
+
+    assert_eq!(lightclient.latest_height().unwrap(), Height::new(0, 3));
+
+    // different packets signed at the same sequence are not conflicting
+    let (_, signature_3) = proof(&packet(3), 2);
+
+    let misbehaviour = to_json_vec(&Misbehaviour {
+        sequence: 2,
+        signature_1: signature_2,
+        signature_2: signature_3,
+    })
+    .unwrap();
+
+    assert!(!lightclient
+        .check_for_misbehaviour(misbehaviour.clone())
+        .unwrap());
+
+    assert_eq!(
+        lightclient
+            .update_on_misbehaviour(misbehaviour)
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::NoMisbehaviour
+    );
+
+    assert_eq!(lightclient.status().unwrap(), Status::Active);
+}
@@ -0,0 +1,218 @@
+use eureka_application_pingpong::sv::mt::{CodeId as AppCodeId, ContractProxy};
+use eureka_lightclient_dummy::sv::mt::{
+    CodeId as lightclientCodeId, ContractProxy as LightClientContractProxy,
+};
+use eureka_lightclient_dummy::Header;
+use eureka_lightclient_interface::sv::mt::LightClientProxy;
+use eureka_lightclient_interface::{Height, Status};
+use eureka_tao::sv::mt::{CodeId as TaoCodeId, ContractProxy as TaoContractProxy};
+use eureka_tao::{
+    Config, ContractError, Packet, PacketHeader, Payload, PayloadHeader, SenderPolicy,
+};
+use rstest::rstest;
+use sylvia::cw_std::{to_json_vec, Addr};
+use sylvia::multitest::App;
+
+use crate::factory::create_dummy;
+
+#[rstest]
+fn test_lightclient_status() {
+    let chain_1 = App::default();
+
+    let lightclient_code_id = lightclientCodeId::store_code(&chain_1);
+    let tao_code_id = TaoCodeId::store_code(&chain_1);
+    let application_code_id = AppCodeId::store_code(&chain_1);
+
+    let gov = Addr::unchecked("gov-module");
+    let dao = Addr::unchecked("dao");
+    let alice = Addr::unchecked("alice");
+    let relayer = Addr::unchecked("relayer");
+    let hacker = Addr::unchecked("hacker");
+
+    let tao_contract = tao_code_id.instantiate().call(&gov).unwrap();
+
+    tao_contract
+        .approve_lightclient_code(lightclient_code_id.code_id(), "dummy".to_string())
+        .call(&gov)
+        .unwrap();
+
+    let lightclient_1_contract = create_dummy(&tao_contract, &lightclient_code_id, &dao);
+    let lightclient_2_contract = create_dummy(&tao_contract, &lightclient_code_id, &dao);
+
+    let application_1_contract = application_code_id
+        .instantiate(tao_contract.contract_addr.clone())
+        .with_admin(alice.as_str())
+        .call(&alice)
+        .unwrap();
+    let application_2_contract = application_code_id
+        .instantiate(tao_contract.contract_addr.clone())
+        .with_admin(alice.as_str())
+        .call(&alice)
+        .unwrap();
+
+    let lightclient_1 = (lightclient_1_contract.contract_addr.clone(), vec![]);
+    let lightclient_2 = (lightclient_2_contract.contract_addr.clone(), vec![]);
+
+    application_1_contract
+        .set_allowed_channel(
+            lightclient_1.clone(),
+            lightclient_2.clone(),
+            application_2_contract.contract_addr.clone(),
+        )
+        .call(&alice)
+        .unwrap();
+
+    application_2_contract
+        .set_allowed_channel(
+            lightclient_2.clone(),
+            lightclient_1.clone(),
+            application_1_contract.contract_addr.clone(),
+        )
+        .call(&alice)
+        .unwrap();
+
+    tao_contract
+        .register_application(
+            application_1_contract.contract_addr.clone(),
+            lightclient_1.clone(),
+            lightclient_2.clone(),
+            SenderPolicy::Any,
+        )
+        .call(&alice)
+        .unwrap();
+
+    let packet = |nonce| Packet {
+        header: PacketHeader {
+            lightclient_source: lightclient_1.clone(),
+            lightclient_destination: lightclient_2.clone(),
+            nonce,
+            timeout: chain_1.block_info().time.seconds() + 10,
+        },
+        payloads: vec![Payload {
+            header: PayloadHeader {
+                application_source: application_1_contract.contract_addr.clone(),
+                application_destination: application_2_contract.contract_addr.clone(),
+                funds: vec![],
+                gas_limit: None,
+            },
+            data: b"ping".to_vec(),
+        }],
+    };
+
+    let packet_1 = packet(1);
+
+    tao_contract
+        .send_packet(packet_1.clone(), None)
+        .call(&alice)
+        .unwrap();
+
+    // only the instantiator can switch the dummy status
+    lightclient_1_contract
+        .set_status(Status::Frozen)
+        .call(&hacker)
+        .unwrap_err();
+
+    lightclient_1_contract
+        .set_status(Status::Frozen)
+        .call(&dao)
+        .unwrap();
+
+    assert_eq!(
+        tao_contract
+            .receive_packet(packet_1.clone(), Height::default(), vec![])
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::LightClientNotActive {
+            lightclient: lightclient_1_contract.contract_addr.clone(),
+            status: Status::Frozen,
+        }
+    );
+
+    lightclient_1_contract
+        .set_status(Status::Active)
+        .call(&dao)
+        .unwrap();
+
+    tao_contract
+        .receive_packet(packet_1, Height::default(), vec![])
+        .call(&relayer)
+        .unwrap();
+
+    let packet_2 = packet(2);
+
+    tao_contract
+        .send_packet(packet_2.clone(), None)
+        .call(&alice)
+        .unwrap();
+
+    lightclient_2_contract
+        .set_status(Status::Expired)
+        .call(&dao)
+        .unwrap();
+
+    assert_eq!(
+        tao_contract
+            .timeout_packet(packet_2.clone(), Height::default(), vec![])
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::LightClientNotActive {
+            lightclient: lightclient_2_contract.contract_addr.clone(),
+            status: Status::Expired,
+        }
+    );
+
+    // only governance can allow timeouts on expired light clients
+    tao_contract
+        .update_config(Config {
+            allow_timeout_on_expired: true,
+        })
+        .call(&alice)
+        .unwrap_err();
+
+    tao_contract
+        .update_config(Config {
+            allow_timeout_on_expired: true,
+        })
+        .call(&gov)
+        .unwrap();
+
+    assert!(tao_contract.config().unwrap().allow_timeout_on_expired);
+
+    // frozen light clients are never trusted
+    lightclient_2_contract
+        .set_status(Status::Frozen)
+        .call(&dao)
+        .unwrap();
+
+    assert_eq!(
+        tao_contract
+            .timeout_packet(packet_2.clone(), Height::default(), vec![])
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::LightClientNotActive {
+            lightclient: lightclient_2_contract.contract_addr.clone(),
+            status: Status::Frozen,
+        }
+    );
+
+    lightclient_2_contract
+        .set_status(Status::Expired)
+        .call(&dao)
+        .unwrap();
+
+    lightclient_2_contract
+        .update(
+            to_json_vec(&Header {
+                height: Height::new(0, 1),
+                timestamp: packet_2.header.timeout,
+            })
+            .unwrap(),
+        )
+        .call(&dao)
+        .unwrap();
+
+    tao_contract
+        .timeout_packet(packet_2, Height::new(0, 1), vec![])
+        .call(&relayer)
+        .unwrap();
+}
@@ -0,0 +1,293 @@
+use eureka_application_pingpong::sv::mt::{CodeId as AppCodeId, ContractProxy};
+use eureka_ics23_verifier::{commitment_proof, ExistenceProof, IBC_STORE};
+use eureka_lightclient_interface::sv::mt::LightClientProxy;
+use eureka_lightclient_interface::{Height, Status};
+use eureka_lightclient_mock::sv::mt::CodeId as MockCodeId;
+use eureka_lightclient_mock::sv::InstantiateMsg as MockInstantiateMsg;
+use eureka_lightclient_mock::Contract as MockContract;
+use eureka_lightclient_tendermint::sv::mt::CodeId as TendermintCodeId;
+use eureka_lightclient_tendermint::{ClientState, Contract as TendermintContract};
+use eureka_tao::commitment::{connection_key, packet_commitment, packet_commitment_path};
+use eureka_tao::sv::mt::{CodeId as TaoCodeId, ContractProxy as TaoContractProxy};
+use eureka_tao::{ContractError, Packet, PacketHeader, Payload, PayloadHeader};
+use rstest::rstest;
+use sylvia::cw_std::{to_json_vec, Addr};
+use sylvia::multitest::{App, Proxy};
+
+use crate::factory::create_lightclient;
+use crate::ics23::{iavl_pair, merkle_proof, multistore_pair};
+use crate::tendermint::{header, instantiate_msg, keys, TRUSTING_PERIOD};
+
+#[rstest]
+fn test_expired_lightclient_substitution() {
+    let chain = App::default();
+
+    let gov = Addr::unchecked("gov-module");
+    let dao = Addr::unchecked("dao");
+    let alice = Addr::unchecked("alice");
+    let relayer = Addr::unchecked("relayer");
+
+    let keys_a = keys(&[1, 2, 3, 4]);
+
+    let now = chain.block_info().time.seconds();
+
+    let tao_contract = TaoCodeId::store_code(&chain)
+        .instantiate()
+        .call(&gov)
+        .unwrap();
+
+    let mock_code_id = MockCodeId::store_code(&chain).code_id();
+    let tendermint_code_id = TendermintCodeId::store_code(&chain).code_id();
+
+    tao_contract
+        .approve_lightclient_code(mock_code_id, "mock".to_string())
+        .call(&gov)
+        .unwrap();
+    tao_contract
+        .approve_lightclient_code(tendermint_code_id, "tendermint".to_string())
+        .call(&gov)
+        .unwrap();
+
+    let create_tendermint = || -> Proxy<_, TendermintContract> {
+        create_lightclient(
+            &tao_contract,
+            &dao,
+            tendermint_code_id,
+            b"ibc",
+            &instantiate_msg(&chain, &keys_a),
+        )
+    };
+
+    // the counterparty light client tracking this chain
+    let counterparty_contract: Proxy<_, MockContract> = create_lightclient(
+        &tao_contract,
+        &dao,
+        mock_code_id,
+        b"ibc",
+        &MockInstantiateMsg::new(vec![]),
+    );
+
+    let application_contract = AppCodeId::store_code(&chain)
+        .instantiate(tao_contract.contract_addr.clone())
+        .with_admin(alice.as_str())
+        .call(&alice)
+        .unwrap();
+
+    let lightclient = create_tendermint();
+
+    let lightclient_source = (lightclient.contract_addr.clone(), b"ibc".to_vec());
+    let lightclient_destination = (counterparty_contract.contract_addr.clone(), b"ibc".to_vec());
+    let application_source = Addr::unchecked("counterparty-application");
+
+    application_contract
+        .set_allowed_channel(
+            lightclient_destination.clone(),
+            lightclient_source.clone(),
+            application_source.clone(),
+        )
+        .call(&alice)
+        .unwrap();
+
+    let packet = |nonce| Packet {
+        header: PacketHeader {
+            lightclient_source: lightclient_source.clone(),
+            lightclient_destination: lightclient_destination.clone(),
+            nonce,
+            timeout: now + 10 * TRUSTING_PERIOD,
+        },
+        payloads: vec![Payload {
+            header: PayloadHeader {
+                application_source: application_source.clone(),
+                application_destination: application_contract.contract_addr.clone(),
+                funds: vec![],
+                gas_limit: None,
+            },
+            data: b"ping".to_vec(),
+        }],
+    };
+
+    // both packets committed by the counterparty
+    let connection = connection_key(&lightclient_source, &lightclient_destination);
+
+    let commitment_1 = packet_commitment(&packet(1)).unwrap();
+    let commitment_2 = packet_commitment(&packet(2)).unwrap();
+
+    let (ibc_root, proof_1, proof_2) = iavl_pair(
+        (&packet_commitment_path(&connection, 1), &commitment_1),
+        (&packet_commitment_path(&connection, 2), &commitment_2),
+    );
+    let (app_hash, _, ibc_store) = multistore_pair((b"bank", b"bank-root"), (IBC_STORE, &ibc_root));
+
+    let membership = |proof: &ExistenceProof| {
+        merkle_proof(vec![
+            commitment_proof::Proof::Exist(proof.clone()),
+            commitment_proof::Proof::Exist(ibc_store.clone()),
+        ])
+    };
+
+    lightclient
+        .update(
+            to_json_vec(&header(
+                11,
+                now - 90,
+                &app_hash,
+                &keys_a,
+                &keys_a,
+                10,
+                &keys_a,
+            ))
+            .unwrap(),
+        )
+        .call(&relayer)
+        .unwrap();
+
+    tao_contract
+        .receive_packet(packet(1), Height::new(1, 11), membership(&proof_1))
+        .call(&relayer)
+        .unwrap();
+
+    let substitute = create_tendermint();
+
+    // healthy light clients are not substituted
+    assert_eq!(
+        tao_contract
+            .substitute_lightclient(
+                lightclient.contract_addr.clone(),
+                substitute.contract_addr.clone(),
+            )
+            .call(&gov)
+            .unwrap_err(),
+        ContractError::LightClientNotSubstitutable {
+            lightclient: lightclient.contract_addr.clone(),
+            status: Status::Active,
+        }
+    );
+
+    chain.update_block(|block| block.time = block.time.plus_seconds(TRUSTING_PERIOD));
+
+    assert_eq!(
+        tao_contract
+            .receive_packet(packet(2), Height::new(1, 11), membership(&proof_2))
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::LightClientNotActive {
+            lightclient: lightclient.contract_addr.clone(),
+            status: Status::Expired,
+        }
+    );
+
+    let now = chain.block_info().time.seconds();
+
+    let substitute = create_tendermint();
+
+    assert_eq!(
+        tao_contract
+            .substitute_lightclient(
+                lightclient.contract_addr.clone(),
+                substitute.contract_addr.clone(),
+            )
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::Unauthorized
+    );
+
+    // the substitute must be ahead of the expired light client
+    assert_eq!(
+        tao_contract
+            .substitute_lightclient(
+                lightclient.contract_addr.clone(),
+                substitute.contract_addr.clone(),
+            )
+            .call(&gov)
+            .unwrap_err(),
+        ContractError::SubstituteHeightMismatch {
+            lightclient: Height::new(1, 11),
+            substitute: Height::new(1, 10),
+        }
+    );
+
+    assert_eq!(
+        tao_contract
+            .substitute_lightclient(
+                lightclient.contract_addr.clone(),
+                counterparty_contract.contract_addr.clone(),
+            )
+            .call(&gov)
+            .unwrap_err(),
+        ContractError::LightClientTypeMismatch {
+            lightclient: lightclient.contract_addr.clone(),
+            substitute: counterparty_contract.contract_addr.clone(),
+        }
+    );
+
+    // the substitute must track the same chain with the same parameters
+    let create_mismatched = |mismatch: fn(&mut ClientState)| -> Proxy<_, TendermintContract> {
+        let mut msg = instantiate_msg(&chain, &keys_a);
+        mismatch(&mut msg.client_state);
+        create_lightclient(&tao_contract, &dao, tendermint_code_id, b"ibc", &msg)
+    };
+
+    for mismatched in [
+        create_mismatched(|client_state| client_state.chain_id = "other-chain".to_string()),
+        create_mismatched(|client_state| client_state.max_clock_drift = 20),
+    ] {
+        assert_eq!(
+            tao_contract
+                .substitute_lightclient(
+                    lightclient.contract_addr.clone(),
+                    mismatched.contract_addr.clone(),
+                )
+                .call(&gov)
+                .unwrap_err(),
+            ContractError::LightClientParametersMismatch {
+                lightclient: lightclient.contract_addr.clone(),
+                substitute: mismatched.contract_addr.clone(),
+            }
+        );
+    }
+
+    substitute
+        .update(
+            to_json_vec(&header(
+                12,
+                now - 50,
+                &app_hash,
+                &keys_a,
+                &keys_a,
+                10,
+                &keys_a,
+            ))
+            .unwrap(),
+        )
+        .call(&relayer)
+        .unwrap();
+
+    tao_contract
+        .substitute_lightclient(
+            lightclient.contract_addr.clone(),
+            substitute.contract_addr.clone(),
+        )
+        .call(&gov)
+        .unwrap();
+
+    assert_eq!(
+        tao_contract
+            .resolved_lightclient(lightclient.contract_addr.clone())
+            .unwrap(),
+        substitute.contract_addr
+    );
+
+    // receipts are kept under the same light client ID
+    assert_eq!(
+        tao_contract
+            .receive_packet(packet(1), Height::new(1, 12), membership(&proof_1))
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::PacketAlreadyReceived
+    );
+
+    tao_contract
+        .receive_packet(packet(2), Height::new(1, 12), membership(&proof_2))
+        .call(&relayer)
+        .unwrap();
+}
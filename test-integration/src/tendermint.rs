@@ -0,0 +1,1172 @@
+use ed25519_dalek::{Signer, SigningKey};
+use eureka_application_pingpong::sv::mt::{CodeId as AppCodeId, ContractProxy};
+use eureka_ics23_verifier::{
+    batch_entry, commitment_proof, compressed_batch_entry, BatchEntry, BatchProof,
+    CompressedBatchEntry, CompressedBatchProof, CompressedExistenceProof, ExistenceProof,
+    IBC_STORE,
+};
+use eureka_lightclient_interface::sv::mt::LightClientProxy;
+use eureka_lightclient_interface::{Height, Status, UpdatePolicy};
+use eureka_lightclient_mock::sv::mt::CodeId as MockCodeId;
+use eureka_lightclient_mock::sv::InstantiateMsg as MockInstantiateMsg;
+use eureka_lightclient_mock::Contract as MockContract;
+use eureka_lightclient_tendermint::proto;
+use eureka_lightclient_tendermint::sv::mt::CodeId as TendermintCodeId;
+use eureka_lightclient_tendermint::sv::InstantiateMsg;
+use eureka_lightclient_tendermint::{
+    upgraded_client_key, upgraded_consensus_state_key, BlockHeader, BlockId, ClientState, Commit,
+    CommitSig, ConsensusState, Contract, ContractError, Fraction, Header, Misbehaviour,
+    PartSetHeader, SignedHeader, Timestamp, UpgradeProofs, Validator, ValidatorSet, Version,
+    NANOS_PER_SECOND, SENTINEL_ROOT, UPGRADE_STORE,
+};
+use eureka_tao::commitment::{connection_key, packet_receipt_path};
+use eureka_tao::sv::mt::{CodeId as TaoCodeId, ContractProxy as TaoContractProxy};
+use eureka_tao::{Packet, PacketHeader, Payload, PayloadHeader, SenderPolicy};
+use prost::Message;
+use rstest::rstest;
+use sha2::{Digest, Sha256};
+use sylvia::cw_multi_test::BasicApp;
+use sylvia::cw_std::{from_json, to_json_vec, Addr};
+use sylvia::multitest::{App, Proxy};
+
+use crate::factory::create_lightclient;
+use crate::ics23::{iavl_pair, merkle_proof, multistore_pair, non_existence};
+
+const CHAIN_ID: &str = "counterparty-1";
+pub const TRUSTING_PERIOD: u64 = 1000;
+const UNBONDING_PERIOD: u64 = 1500;
+
+pub fn keys(seeds: &[u8]) -> Vec<SigningKey> {
+    seeds
+        .iter()
+        .map(|seed| SigningKey::from_bytes(&[*seed; 32]))
+        .collect()
+}
+
+fn validator_set(keys: &[SigningKey]) -> ValidatorSet {
+    ValidatorSet {
+        validators: keys
+            .iter()
+            .map(|key| Validator {
+                pub_key: key.verifying_key().to_bytes().to_vec(),
+                voting_power: 10,
+            })
+            .collect(),
+    }
+}
+
+// commit of the header by the validators in `keys` flagged in `signers`, voting at block time
+fn sign(header: &mut Header, keys: &[SigningKey], signers: &[bool]) {
+    let block = &header.signed_header.header;
+
+    let mut commit = Commit {
+        height: block.height,
+        round: 0,
+        block_id: BlockId {
+            hash: block.hash(),
+            part_set_header: PartSetHeader {
+                total: 1,
+                hash: vec![1; 32],
+            },
+        },
+        signatures: vec![],
+    };
+
+    commit.signatures = keys
+        .iter()
+        .zip(signers)
+        .map(|(key, signed)| {
+            signed.then(|| {
+                let mut signature = CommitSig {
+                    timestamp: block.time.clone(),
+                    signature: vec![],
+                };
+                let sign_bytes = commit.vote_sign_bytes(&block.chain_id, &signature);
+                signature.signature = key.sign(&sign_bytes).to_bytes().to_vec();
+                signature
+            })
+        })
+        .collect();
+
+    header.signed_header.commit = commit;
+}
+
+// header at `height` signed by all the validators in `keys`
+pub fn header(
+    height: u64,
+    time: u64,
+    app_hash: &[u8],
+    keys: &[SigningKey],
+    next_keys: &[SigningKey],
+    trusted_height: u64,
+    trusted_keys: &[SigningKey],
+) -> Header {
+    let mut header = Header {
+        signed_header: SignedHeader {
+            header: BlockHeader {
+                version: Version { block: 11, app: 0 },
+                chain_id: CHAIN_ID.to_string(),
+                height,
+                time: Timestamp {
+                    seconds: time as i64,
+                    nanos: 0,
+                },
+                last_block_id: BlockId::default(),
+                last_commit_hash: vec![],
+                data_hash: vec![],
+                validators_hash: validator_set(keys).hash(),
+                next_validators_hash: validator_set(next_keys).hash(),
+                consensus_hash: vec![],
+                app_hash: app_hash.to_vec(),
+                last_results_hash: vec![],
+                evidence_hash: vec![],
+                proposer_address: vec![],
+            },
+            commit: Commit {
+                height,
+                round: 0,
+                block_id: BlockId::default(),
+                signatures: vec![],
+            },
+        },
+        validator_set: validator_set(keys),
+        trusted_height: Height::new(1, trusted_height),
+        trusted_validators: validator_set(trusted_keys),
+    };
+
+    sign(&mut header, keys, &vec![true; keys.len()]);
+
+    header
+}
+
+pub fn setup<'a>(chain: &'a App<BasicApp>, keys: &[SigningKey]) -> Proxy<'a, BasicApp, Contract> {
+    let InstantiateMsg {
+        client_state,
+        consensus_state,
+    } = instantiate_msg(chain, keys);
+
+    TendermintCodeId::store_code(chain)
+        .instantiate(client_state, consensus_state)
+        .call(&Addr::unchecked("dao"))
+        .unwrap()
+}
+
+// client at height 1-10, trusting `keys`
+pub fn instantiate_msg(chain: &App<BasicApp>, keys: &[SigningKey]) -> InstantiateMsg {
+    let now = chain.block_info().time.seconds();
+
+    InstantiateMsg::new(
+        ClientState {
+            chain_id: CHAIN_ID.to_string(),
+            trust_level: Fraction {
+                numerator: 1,
+                denominator: 3,
+            },
+            trusting_period: TRUSTING_PERIOD,
+            max_clock_drift: 10,
+            unbonding_period: UNBONDING_PERIOD,
+            latest_height: Height::new(1, 10),
+            frozen_height: None,
+        },
+        ConsensusState {
+            timestamp: (now - 100) * NANOS_PER_SECOND,
+            root: b"root".to_vec(),
+            next_validators_hash: validator_set(keys).hash(),
+        },
+    )
+}
+
+// test vectors of CometBFT `TestHeaderHash` and `TestVoteSignBytesTestVectors`
+#[rstest]
+fn test_tendermint_encodings() {
+    let sha256 = |bytes: &[u8]| Sha256::digest(bytes).to_vec();
+
+    let header = BlockHeader {
+        version: Version { block: 1, app: 2 },
+        chain_id: "chainId".to_string(),
+        height: 3,
+        // 2019-10-13T16:14:44Z
+        time: Timestamp {
+            seconds: 1570983284,
+            nanos: 0,
+        },
+        last_block_id: BlockId {
+            hash: vec![0; 32],
+            part_set_header: PartSetHeader {
+                total: 6,
+                hash: vec![0; 32],
+            },
+        },
+        last_commit_hash: sha256(b"last_commit_hash"),
+        data_hash: sha256(b"data_hash"),
+        validators_hash: sha256(b"validators_hash"),
+        next_validators_hash: sha256(b"next_validators_hash"),
+        consensus_hash: sha256(b"consensus_hash"),
+        app_hash: sha256(b"app_hash"),
+        last_results_hash: sha256(b"last_results_hash"),
+        evidence_hash: sha256(b"evidence_hash"),
+        proposer_address: sha256(b"proposer_address")[..20].to_vec(),
+    };
+
+    assert_eq!(
+        header.hash(),
+        [
+            0xf7, 0x40, 0x12, 0x1f, 0x55, 0x3b, 0x54, 0x18, 0xc3, 0xef, 0xbd, 0x34, 0x3c, 0x2d,
+            0xbf, 0xe9, 0xe0, 0x07, 0xbb, 0x67, 0xb0, 0xd0, 0x20, 0xa0, 0x74, 0x13, 0x74, 0xba,
+            0xb6, 0x52, 0x42, 0xa4,
+        ]
+    );
+
+    // precommit at height 1, round 1, for a zero block id at the zero go time
+    let commit = Commit {
+        height: 1,
+        round: 1,
+        block_id: BlockId::default(),
+        signatures: vec![],
+    };
+    let signature = CommitSig {
+        timestamp: Timestamp {
+            seconds: -62135596800,
+            nanos: 0,
+        },
+        signature: vec![],
+    };
+
+    assert_eq!(
+        commit.vote_sign_bytes("", &signature),
+        [
+            0x21, 0x08, 0x02, 0x11, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x19, 0x01,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x2a, 0x0b, 0x08, 0x80, 0x92, 0xb8, 0xc3,
+            0x98, 0xfe, 0xff, 0xff, 0xff, 0x01,
+        ]
+    );
+}
+
+#[rstest]
+fn test_tendermint_trust_level() {
+    let chain = App::default();
+
+    let code_id = TendermintCodeId::store_code(&chain);
+
+    let instantiate = |numerator, denominator| {
+        let InstantiateMsg {
+            mut client_state,
+            consensus_state,
+        } = instantiate_msg(&chain, &keys(&[1]));
+        client_state.trust_level = Fraction {
+            numerator,
+            denominator,
+        };
+
+        code_id
+            .instantiate(client_state, consensus_state)
+            .call(&Addr::unchecked("dao"))
+    };
+
+    assert_eq!(
+        instantiate(1, 4).unwrap_err(),
+        ContractError::InvalidTrustLevel {
+            numerator: 1,
+            denominator: 4
+        }
+    );
+
+    // the bound check doesn't overflow
+    instantiate(u64::MAX, u64::MAX).unwrap();
+}
+
+#[rstest]
+fn test_tendermint_header_verification() {
+    let chain = App::default();
+
+    let relayer = Addr::unchecked("relayer");
+
+    let keys_a = keys(&[1, 2, 3, 4]);
+    let keys_b = keys(&[2, 3, 4, 5]);
+    let keys_c = keys(&[6, 7, 8, 9]);
+
+    let now = chain.block_info().time.seconds();
+
+    let lightclient = setup(&chain, &keys_a);
+
+    // adjacent header, signed by the trusted next validators
+    let header_11 = header(11, now - 90, b"root-11", &keys_a, &keys_b, 10, &keys_a);
+
+    lightclient
+        .update(to_json_vec(&header_11).unwrap())
+        .call(&relayer)
+        .unwrap();
+
+    // resubmitting the same header is a no-op
+    lightclient
+        .update(to_json_vec(&header_11).unwrap())
+        .call(&relayer)
+        .unwrap();
+
+    assert_eq!(lightclient.latest_height().unwrap(), Height::new(1, 11));
+    assert_eq!(lightclient.timestamp(Height::new(1, 11)).unwrap(), now - 90);
+    assert_eq!(lightclient.status().unwrap(), Status::Active);
+    assert_eq!(
+        lightclient.client_state().unwrap().latest_height,
+        Height::new(1, 11)
+    );
+    assert_eq!(
+        from_json::<ConsensusState>(
+            lightclient
+                .consensus_state(Height::new(1, 11))
+                .unwrap()
+                .data
+        )
+        .unwrap(),
+        header_11.consensus_state().unwrap()
+    );
+    assert_eq!(
+        lightclient.update_policy().unwrap(),
+        UpdatePolicy::Permissionless
+    );
+
+    // non-adjacent header, enough trusted validators carried over
+    let header_20 = header(20, now - 80, b"root-20", &keys_b, &keys_b, 11, &keys_b);
+
+    lightclient
+        .update(to_json_vec(&header_20).unwrap())
+        .call(&relayer)
+        .unwrap();
+
+    assert_eq!(lightclient.latest_height().unwrap(), Height::new(1, 20));
+
+    // non-adjacent header, by an unrelated validator set
+    let header_30 = header(30, now - 70, b"root-30", &keys_c, &keys_c, 20, &keys_b);
+
+    assert_eq!(
+        lightclient
+            .update(to_json_vec(&header_30).unwrap())
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::InsufficientTrustedVotingPower {
+            signed: 0,
+            total: 40
+        }
+    );
+
+    // adjacent header, without a two-thirds majority
+    let mut header_21 = header(21, now - 70, b"root-21", &keys_b, &keys_b, 20, &keys_b);
+    sign(&mut header_21, &keys_b, &[true, true, false, false]);
+
+    assert_eq!(
+        lightclient
+            .update(to_json_vec(&header_21).unwrap())
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::InsufficientVotingPower {
+            signed: 20,
+            total: 40
+        }
+    );
+
+    let mut forged = header(21, now - 70, b"root-21", &keys_b, &keys_b, 20, &keys_b);
+    forged.signed_header.header.app_hash = b"forged".to_vec();
+
+    assert_eq!(
+        lightclient
+            .update(to_json_vec(&forged).unwrap())
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::CommitBlockIdMismatch
+    );
+
+    // votes for the original block
+    forged.signed_header.commit.block_id.hash = forged.signed_header.header.hash();
+
+    assert_eq!(
+        lightclient
+            .update(to_json_vec(&forged).unwrap())
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::InvalidSignature { index: 0 }
+    );
+
+    let mut other_height = header(21, now - 70, b"root-21", &keys_b, &keys_b, 20, &keys_b);
+    other_height.signed_header.commit.height = 22;
+
+    assert_eq!(
+        lightclient
+            .update(to_json_vec(&other_height).unwrap())
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::CommitHeightMismatch {
+            height: 21,
+            commit_height: 22
+        }
+    );
+
+    let mut other_chain = header(21, now - 70, b"root-21", &keys_b, &keys_b, 20, &keys_b);
+    other_chain.signed_header.header.chain_id = "other-1".to_string();
+
+    assert!(matches!(
+        lightclient
+            .update(to_json_vec(&other_chain).unwrap())
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::ChainIdMismatch { .. }
+    ));
+
+    let future = header(21, now + 100, b"root-21", &keys_b, &keys_b, 20, &keys_b);
+
+    assert!(matches!(
+        lightclient
+            .update(to_json_vec(&future).unwrap())
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::HeaderFromFuture { .. }
+    ));
+
+    // the trusting period elapses
+    chain.update_block(|block| block.time = block.time.plus_seconds(TRUSTING_PERIOD));
+
+    assert_eq!(lightclient.status().unwrap(), Status::Expired);
+
+    let late = header(21, now - 70, b"root-21", &keys_b, &keys_b, 20, &keys_b);
+
+    assert!(matches!(
+        lightclient
+            .update(to_json_vec(&late).unwrap())
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::TrustedConsensusStateExpired { .. }
+    ));
+}
+
+#[rstest]
+fn test_tendermint_update_batch() {
+    let chain = App::default();
+
+    let relayer = Addr::unchecked("relayer");
+
+    let keys_a = keys(&[1, 2, 3, 4]);
+    let keys_b = keys(&[5, 6, 7, 8]);
+
+    let now = chain.block_info().time.seconds();
+
+    let lightclient = setup(&chain, &keys_a);
+
+    // the validator set is replaced entirely, it can only be bridged header by header
+    let header_11 = header(11, now - 90, b"root-11", &keys_a, &keys_b, 10, &keys_a);
+    let header_12 = header(12, now - 80, b"root-12", &keys_b, &keys_b, 11, &keys_b);
+    let skipping = header(12, now - 80, b"root-12", &keys_b, &keys_b, 10, &keys_a);
+
+    assert_eq!(
+        lightclient
+            .update(to_json_vec(&skipping).unwrap())
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::InsufficientTrustedVotingPower {
+            signed: 0,
+            total: 40
+        }
+    );
+
+    // a failing header reverts the whole batch
+    lightclient
+        .update_batch(vec![
+            to_json_vec(&header_11).unwrap(),
+            to_json_vec(&skipping).unwrap(),
+        ])
+        .call(&relayer)
+        .unwrap_err();
+
+    assert_eq!(lightclient.latest_height().unwrap(), Height::new(1, 10));
+
+    lightclient
+        .update_batch(vec![
+            to_json_vec(&header_11).unwrap(),
+            to_json_vec(&header_12).unwrap(),
+        ])
+        .call(&relayer)
+        .unwrap();
+
+    assert_eq!(lightclient.latest_height().unwrap(), Height::new(1, 12));
+    assert_eq!(lightclient.timestamp(Height::new(1, 11)).unwrap(), now - 90);
+    assert_eq!(lightclient.timestamp(Height::new(1, 12)).unwrap(), now - 80);
+}
+
+#[rstest]
+fn test_tendermint_monotonic_time() {
+    let chain = App::default();
+
+    let relayer = Addr::unchecked("relayer");
+
+    let keys_a = keys(&[1, 2, 3, 4]);
+
+    let now = chain.block_info().time.seconds();
+
+    let lightclient = setup(&chain, &keys_a);
+
+    let header_20 = header(20, now - 80, b"root-20", &keys_a, &keys_a, 10, &keys_a);
+    let header_15 = header(15, now - 90, b"root-15", &keys_a, &keys_a, 10, &keys_a);
+
+    // heights below the latest one, between the neighbouring block times
+    for header in [&header_20, &header_15] {
+        lightclient
+            .update(to_json_vec(header).unwrap())
+            .call(&relayer)
+            .unwrap();
+    }
+
+    // later within the same second
+    let mut header_16 = header(16, now - 90, b"root-16", &keys_a, &keys_a, 15, &keys_a);
+    header_16.signed_header.header.time.nanos = 500_000_000;
+    sign(&mut header_16, &keys_a, &[true; 4]);
+
+    lightclient
+        .update(to_json_vec(&header_16).unwrap())
+        .call(&relayer)
+        .unwrap();
+
+    assert_eq!(lightclient.timestamp(Height::new(1, 16)).unwrap(), now - 90);
+    assert_eq!(lightclient.status().unwrap(), Status::Active);
+
+    // after the block time of the next higher height
+    let header_12 = header(12, now - 85, b"root-12", &keys_a, &keys_a, 10, &keys_a);
+
+    lightclient
+        .update(to_json_vec(&header_12).unwrap())
+        .call(&relayer)
+        .unwrap();
+
+    assert_eq!(lightclient.status().unwrap(), Status::Frozen);
+    assert_eq!(
+        lightclient.client_state().unwrap().frozen_height,
+        Some(Height::new(1, 12))
+    );
+    assert!(lightclient.timestamp(Height::new(1, 12)).is_err());
+}
+
+#[rstest]
+fn test_tendermint_misbehaviour() {
+    let chain = App::default();
+
+    let relayer = Addr::unchecked("relayer");
+
+    let keys_a = keys(&[1, 2, 3, 4]);
+
+    let now = chain.block_info().time.seconds();
+
+    let lightclient = setup(&chain, &keys_a);
+
+    let header_11 = header(11, now - 90, b"root-11", &keys_a, &keys_a, 10, &keys_a);
+    let conflicting = header(11, now - 90, b"fork-11", &keys_a, &keys_a, 10, &keys_a);
+
+    let no_misbehaviour = to_json_vec(&Misbehaviour {
+        header_1: header_11.clone(),
+        header_2: header_11.clone(),
+    })
+    .unwrap();
+
+    assert!(!lightclient
+        .check_for_misbehaviour(no_misbehaviour.clone())
+        .unwrap());
+
+    assert_eq!(
+        lightclient
+            .update_on_misbehaviour(no_misbehaviour)
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::NoMisbehaviour
+    );
+
+    let misbehaviour = to_json_vec(&Misbehaviour {
+        header_1: header_11.clone(),
+        header_2: conflicting.clone(),
+    })
+    .unwrap();
+
+    assert!(lightclient
+        .check_for_misbehaviour(misbehaviour.clone())
+        .unwrap());
+
+    lightclient
+        .update_on_misbehaviour(misbehaviour)
+        .call(&relayer)
+        .unwrap();
+
+    assert_eq!(lightclient.status().unwrap(), Status::Frozen);
+
+    assert_eq!(
+        lightclient
+            .update(to_json_vec(&header_11).unwrap())
+            .call(&relayer)
+            .unwrap_err(),
+        ContractError::ClientFrozen {
+            height: Height::new(1, 11)
+        }
+    );
+
+    // a conflicting update freezes the client as well
+    let lightclient = setup(&chain, &keys_a);
+
+    lightclient
+        .update(to_json_vec(&header_11).unwrap())
+        .call(&relayer)
+        .unwrap();
+
+    assert_eq!(lightclient.status().unwrap(), Status::Active);
+
+    lightclient
+        .update(to_json_vec(&conflicting).unwrap())
+        .call(&relayer)
+        .unwrap();
+
+    assert_eq!(lightclient.status().unwrap(), Status::Frozen);
+}
+
+#[rstest]
+fn test_tendermint_membership() {
+    let chain = App::default();
+
+    let relayer = Addr::unchecked("relayer");
+
+    let keys_a = keys(&[1, 2, 3, 4]);
+
+    let now = chain.block_info().time.seconds();
+
+    let (key_a, value_a) = (b"commitments/a".to_vec(), b"value-a".to_vec());
+    let (key_c, value_c) = (b"commitments/c".to_vec(), b"value-c".to_vec());
+
+    let (ibc_root, proof_a, proof_c) = iavl_pair((&key_a, &value_a), (&key_c, &value_c));
+    let (app_hash, _, ibc_store) = multistore_pair((b"bank", b"bank-root"), (IBC_STORE, &ibc_root));
+
+    let membership = |proof: &ExistenceProof| {
+        merkle_proof(vec![
+            commitment_proof::Proof::Exist(proof.clone()),
+            commitment_proof::Proof::Exist(ibc_store.clone()),
+        ])
+    };
+
+    let non_membership = |key: &[u8]| {
+        merkle_proof(vec![
+            non_existence(key, Some(proof_a.clone()), Some(proof_c.clone())),
+            commitment_proof::Proof::Exist(ibc_store.clone()),
+        ])
+    };
+
+    let lightclient = setup(&chain, &keys_a);
+
+    let header_11 = header(11, now - 90, &app_hash, &keys_a, &keys_a, 10, &keys_a);
+
+    lightclient
+        .update(to_json_vec(&header_11).unwrap())
+        .call(&relayer)
+        .unwrap();
+
+    let height = Height::new(1, 11);
+
+    assert!(lightclient
+        .check_membership(
+            key_a.clone(),
+            value_a.clone(),
+            b"ibc".to_vec(),
+            height,
+            membership(&proof_a),
+        )
+        .unwrap());
+
+    assert!(lightclient
+        .check_membership(
+            key_c.clone(),
+            value_c.clone(),
+            b"ibc".to_vec(),
+            height,
+            membership(&proof_c),
+        )
+        .unwrap());
+
+    // wrong value
+    lightclient
+        .check_membership(
+            key_a.clone(),
+            value_c.clone(),
+            b"ibc".to_vec(),
+            height,
+            membership(&proof_a),
+        )
+        .unwrap_err();
+
+    // wrong store
+    lightclient
+        .check_membership(
+            key_a.clone(),
+            value_a.clone(),
+            b"bank".to_vec(),
+            height,
+            membership(&proof_a),
+        )
+        .unwrap_err();
+
+    // unknown height
+    lightclient
+        .check_membership(
+            key_a.clone(),
+            value_a.clone(),
+            b"ibc".to_vec(),
+            Height::new(1, 12),
+            membership(&proof_a),
+        )
+        .unwrap_err();
+
+    assert!(lightclient
+        .check_non_membership(
+            b"commitments/b".to_vec(),
+            b"ibc".to_vec(),
+            height,
+            non_membership(b"commitments/b"),
+        )
+        .unwrap());
+
+    // existing keys cannot be proven absent
+    lightclient
+        .check_non_membership(
+            key_a.clone(),
+            b"ibc".to_vec(),
+            height,
+            non_membership(&key_a),
+        )
+        .unwrap_err();
+
+    lightclient
+        .check_non_membership(
+            b"commitments/d".to_vec(),
+            b"ibc".to_vec(),
+            height,
+            non_membership(b"commitments/d"),
+        )
+        .unwrap_err();
+}
+
+#[rstest]
+fn test_tendermint_batch_membership() {
+    let chain = App::default();
+
+    let relayer = Addr::unchecked("relayer");
+
+    let keys_a = keys(&[1, 2, 3, 4]);
+
+    let now = chain.block_info().time.seconds();
+
+    let (key_a, value_a) = (b"commitments/a".to_vec(), b"value-a".to_vec());
+    let (key_c, value_c) = (b"commitments/c".to_vec(), b"value-c".to_vec());
+
+    let (ibc_root, proof_a, proof_c) = iavl_pair((&key_a, &value_a), (&key_c, &value_c));
+    let (app_hash, _, ibc_store) = multistore_pair((b"bank", b"bank-root"), (IBC_STORE, &ibc_root));
+
+    let batch = merkle_proof(vec![
+        commitment_proof::Proof::Batch(BatchProof {
+            entries: [&proof_a, &proof_c]
+                .into_iter()
+                .map(|proof| BatchEntry {
+                    proof: Some(batch_entry::Proof::Exist(proof.clone())),
+                })
+                .collect(),
+        }),
+        commitment_proof::Proof::Exist(ibc_store.clone()),
+    ]);
+
+    // inner ops are only carried once
+    let compress = |proof: &ExistenceProof, index| CompressedBatchEntry {
+        proof: Some(compressed_batch_entry::Proof::Exist(
+            CompressedExistenceProof {
+                key: proof.key.clone(),
+                value: proof.value.clone(),
+                leaf: proof.leaf.clone(),
+                path: vec![index],
+            },
+        )),
+    };
+
+    let compressed = merkle_proof(vec![
+        commitment_proof::Proof::Compressed(CompressedBatchProof {
+            entries: vec![compress(&proof_a, 0), compress(&proof_c, 1)],
+            lookup_inners: vec![proof_a.path[0].clone(), proof_c.path[0].clone()],
+        }),
+        commitment_proof::Proof::Exist(ibc_store.clone()),
+    ]);
+
+    let lightclient = setup(&chain, &keys_a);
+
+    let header_11 = header(11, now - 90, &app_hash, &keys_a, &keys_a, 10, &keys_a);
+
+    lightclient
+        .update(to_json_vec(&header_11).unwrap())
+        .call(&relayer)
+        .unwrap();
+
+    let height = Height::new(1, 11);
+
+    let items = vec![
+        (key_a.clone(), value_a.clone()),
+        (key_c.clone(), value_c.clone()),
+    ];
+
+    for proof in [&batch, &compressed] {
+        assert!(lightclient
+            .verify_batch_membership(items.clone(), b"ibc".to_vec(), height, proof.clone())
+            .unwrap());
+    }
+
+    // a single existence proof is a batch of one
+    assert!(lightclient
+        .verify_batch_membership(
+            vec![(key_a.clone(), value_a.clone())],
+            b"ibc".to_vec(),
+            height,
+            merkle_proof(vec![
+                commitment_proof::Proof::Exist(proof_a.clone()),
+                commitment_proof::Proof::Exist(ibc_store.clone()),
+            ]),
+        )
+        .unwrap());
+
+    // wrong value
+    lightclient
+        .verify_batch_membership(
+            vec![
+                (key_a.clone(), value_a.clone()),
+                (key_c.clone(), value_a.clone()),
+            ],
+            b"ibc".to_vec(),
+            height,
+            batch.clone(),
+        )
+        .unwrap_err();
+
+    // key outside of the batch
+    lightclient
+        .verify_batch_membership(
+            vec![
+                (key_a.clone(), value_a.clone()),
+                (b"commitments/b".to_vec(), value_c.clone()),
+            ],
+            b"ibc".to_vec(),
+            height,
+            compressed.clone(),
+        )
+        .unwrap_err();
+
+    // wrong store
+    lightclient
+        .verify_batch_membership(items, b"bank".to_vec(), height, batch)
+        .unwrap_err();
+}
+
+#[rstest]
+fn test_tendermint_prune() {
+    let chain = App::default();
+
+    let relayer = Addr::unchecked("relayer");
+
+    let keys_a = keys(&[1, 2, 3, 4]);
+
+    let now = chain.block_info().time.seconds();
+
+    let lightclient = setup(&chain, &keys_a);
+
+    assert_eq!(lightclient.trusting_period().unwrap(), TRUSTING_PERIOD);
+
+    for height in 11..=30 {
+        let root = format!("root-{height}");
+        let header = header(
+            height,
+            now - 90 + height,
+            root.as_bytes(),
+            &keys_a,
+            &keys_a,
+            height - 1,
+            &keys_a,
+        );
+
+        lightclient
+            .update(to_json_vec(&header).unwrap())
+            .call(&relayer)
+            .unwrap();
+    }
+
+    // nothing is past the trusting period yet
+    lightclient.prune().call(&relayer).unwrap();
+
+    assert!(lightclient.timestamp(Height::new(1, 10)).is_ok());
+
+    chain.update_block(|block| block.time = block.time.plus_seconds(TRUSTING_PERIOD));
+
+    assert_eq!(lightclient.status().unwrap(), Status::Expired);
+
+    // at most `PRUNE_LIMIT` states per call, oldest first
+    lightclient.prune().call(&relayer).unwrap();
+
+    assert!(lightclient.timestamp(Height::new(1, 25)).is_err());
+    assert!(lightclient.timestamp(Height::new(1, 26)).is_ok());
+
+    lightclient.prune().call(&relayer).unwrap();
+
+    assert!(lightclient.timestamp(Height::new(1, 29)).is_err());
+
+    // the latest consensus state is never pruned
+    assert_eq!(lightclient.timestamp(Height::new(1, 30)).unwrap(), now - 60);
+    assert_eq!(lightclient.latest_height().unwrap(), Height::new(1, 30));
+}
+
+#[rstest]
+fn test_tendermint_upgrade() {
+    let chain = App::default();
+
+    let gov = Addr::unchecked("gov-module");
+    let dao = Addr::unchecked("dao");
+    let alice = Addr::unchecked("alice");
+    let relayer = Addr::unchecked("relayer");
+
+    let keys_a = keys(&[1, 2, 3, 4]);
+    let keys_b = keys(&[2, 3, 4, 5]);
+
+    let now = chain.block_info().time.seconds();
+
+    let tao_contract = TaoCodeId::store_code(&chain)
+        .instantiate()
+        .call(&gov)
+        .unwrap();
+
+    let mock_code_id = MockCodeId::store_code(&chain).code_id();
+    let tendermint_code_id = TendermintCodeId::store_code(&chain).code_id();
+
+    tao_contract
+        .approve_lightclient_code(mock_code_id, "mock".to_string())
+        .call(&gov)
+        .unwrap();
+    tao_contract
+        .approve_lightclient_code(tendermint_code_id, "tendermint".to_string())
+        .call(&gov)
+        .unwrap();
+
+    let source_contract: Proxy<_, MockContract> = create_lightclient(
+        &tao_contract,
+        &dao,
+        mock_code_id,
+        b"ibc",
+        &MockInstantiateMsg::new(vec![]),
+    );
+
+    // tracks the counterparty across its upgrade
+    let lightclient: Proxy<_, Contract> = create_lightclient(
+        &tao_contract,
+        &dao,
+        tendermint_code_id,
+        b"ibc",
+        &instantiate_msg(&chain, &keys_a),
+    );
+
+    let application_code_id = AppCodeId::store_code(&chain);
+
+    let application_1_contract = application_code_id
+        .instantiate(tao_contract.contract_addr.clone())
+        .with_admin(alice.as_str())
+        .call(&alice)
+        .unwrap();
+    let application_2_contract = application_code_id
+        .instantiate(tao_contract.contract_addr.clone())
+        .with_admin(alice.as_str())
+        .call(&alice)
+        .unwrap();
+
+    let lightclient_source = (source_contract.contract_addr.clone(), b"ibc".to_vec());
+    let lightclient_destination = (lightclient.contract_addr.clone(), b"ibc".to_vec());
+
+    application_1_contract
+        .set_allowed_channel(
+            lightclient_source.clone(),
+            lightclient_destination.clone(),
+            application_2_contract.contract_addr.clone(),
+        )
+        .call(&alice)
+        .unwrap();
+
+    tao_contract
+        .register_application(
+            application_1_contract.contract_addr.clone(),
+            lightclient_source.clone(),
+            lightclient_destination.clone(),
+            SenderPolicy::Any,
+        )
+        .call(&alice)
+        .unwrap();
+
+    // in flight across the upgrade
+    let packet = Packet {
+        header: PacketHeader {
+            lightclient_source: lightclient_source.clone(),
+            lightclient_destination: lightclient_destination.clone(),
+            nonce: 1,
+            timeout: now + 10,
+        },
+        payloads: vec![Payload {
+            header: PayloadHeader {
+                application_source: application_1_contract.contract_addr.clone(),
+                application_destination: application_2_contract.contract_addr.clone(),
+                funds: vec![],
+                gas_limit: None,
+            },
+            data: b"ping".to_vec(),
+        }],
+    };
+
+    tao_contract
+        .send_packet(packet.clone(), None)
+        .call(&alice)
+        .unwrap();
+
+    // the counterparty commits to its upgraded states at its last height, as protobuf `Any`
+    let upgraded_client = proto::Any {
+        type_url: proto::CLIENT_STATE_TYPE_URL.to_string(),
+        value: proto::ClientState {
+            chain_id: "counterparty-2".to_string(),
+            unbonding_period: Some(proto::Duration {
+                seconds: 2 * UNBONDING_PERIOD as i64,
+                nanos: 0,
+            }),
+            latest_height: Some(proto::Height {
+                revision_number: 2,
+                revision_height: 1,
+            }),
+            ..Default::default()
+        }
+        .encode_to_vec(),
+    }
+    .encode_to_vec();
+    let upgraded_consensus = proto::Any {
+        type_url: proto::CONSENSUS_STATE_TYPE_URL.to_string(),
+        value: proto::ConsensusState {
+            timestamp: Some(proto::Timestamp {
+                seconds: (now - 80) as i64,
+                nanos: 0,
+            }),
+            root: Some(proto::MerkleRoot {
+                hash: SENTINEL_ROOT.to_vec(),
+            }),
+            next_validators_hash: validator_set(&keys_b).hash(),
+        }
+        .encode_to_vec(),
+    }
+    .encode_to_vec();
+
+    let (upgrade_root, client_proof, consensus_state_proof) = iavl_pair(
+        (&upgraded_client_key(11), &upgraded_client),
+        (&upgraded_consensus_state_key(11), &upgraded_consensus),
+    );
+    let (app_hash, _, upgrade_store) =
+        multistore_pair((b"bank", b"bank-root"), (UPGRADE_STORE, &upgrade_root));
+
+    let proof = |proof: &ExistenceProof| {
+        merkle_proof(vec![
+            commitment_proof::Proof::Exist(proof.clone()),
+            commitment_proof::Proof::Exist(upgrade_store.clone()),
+        ])
+    };
+
+    let proofs = to_json_vec(&UpgradeProofs {
+        client: proof(&client_proof),
+        consensus_state: proof(&consensus_state_proof),
+    })
+    .unwrap();
+
+    // the upgraded states are committed at height 11, unknown yet
+    lightclient
+        .verify_upgrade_and_update_state(
+            upgraded_client.clone(),
+            upgraded_consensus.clone(),
+            proofs.clone(),
+        )
+        .call(&relayer)
+        .unwrap_err();
+
+    let header_11 = header(11, now - 90, &app_hash, &keys_a, &keys_b, 10, &keys_a);
+
+    lightclient
+        .update(to_json_vec(&header_11).unwrap())
+        .call(&relayer)
+        .unwrap();
+
+    let swapped = to_json_vec(&UpgradeProofs {
+        client: proof(&consensus_state_proof),
+        consensus_state: proof(&client_proof),
+    })
+    .unwrap();
+
+    lightclient
+        .verify_upgrade_and_update_state(
+            upgraded_client.clone(),
+            upgraded_consensus.clone(),
+            swapped,
+        )
+        .call(&relayer)
+        .unwrap_err();
+
+    // the states are only accepted as the `Any` the proofs commit to
+    let err = lightclient
+        .verify_upgrade_and_update_state(
+            upgraded_consensus.clone(),
+            upgraded_consensus.clone(),
+            proofs.clone(),
+        )
+        .call(&relayer)
+        .unwrap_err();
+
+    assert_eq!(
+        err,
+        ContractError::InvalidUpgradedState {
+            type_url: proto::CLIENT_STATE_TYPE_URL.to_string()
+        }
+    );
+
+    lightclient
+        .verify_upgrade_and_update_state(upgraded_client, upgraded_consensus, proofs)
+        .call(&relayer)
+        .unwrap();
+
+    assert_eq!(lightclient.latest_height().unwrap(), Height::new(2, 1));
+    assert_eq!(lightclient.timestamp(Height::new(2, 1)).unwrap(), now - 80);
+    assert_eq!(lightclient.trusting_period().unwrap(), 2 * TRUSTING_PERIOD);
+
+    // headers of the upgraded chain, on top of the upgraded consensus state
+    let (key_a, value_a) = (b"commitments/a".to_vec(), b"value-a".to_vec());
+    let (key_z, value_z) = (b"receipts/~".to_vec(), b"value-z".to_vec());
+
+    let (ibc_root, proof_a, proof_z) = iavl_pair((&key_a, &value_a), (&key_z, &value_z));
+    let (app_hash, _, ibc_store) = multistore_pair((b"bank", b"bank-root"), (IBC_STORE, &ibc_root));
+
+    chain.update_block(|block| block.time = block.time.plus_seconds(20));
+
+    let mut header_2 = header(2, now + 15, &app_hash, &keys_b, &keys_b, 1, &keys_b);
+    header_2.signed_header.header.chain_id = "counterparty-2".to_string();
+    header_2.trusted_height = Height::new(2, 1);
+    sign(&mut header_2, &keys_b, &[true; 4]);
+
+    lightclient
+        .update(to_json_vec(&header_2).unwrap())
+        .call(&relayer)
+        .unwrap();
+
+    // the packet is still known to tao under the same light client
+    let receipt_path = packet_receipt_path(
+        &connection_key(&lightclient_source, &lightclient_destination),
+        1,
+    );
+
+    tao_contract
+        .timeout_packet(
+            packet,
+            Height::new(2, 2),
+            merkle_proof(vec![
+                non_existence(&receipt_path, Some(proof_a), Some(proof_z)),
+                commitment_proof::Proof::Exist(ibc_store),
+            ]),
+        )
+        .call(&relayer)
+        .unwrap();
+}
@@ -1,12 +1,16 @@
 use eureka_application_pingpong::sv::mt::{CodeId as AppCodeId, ContractProxy};
 use eureka_lightclient_dummy::sv::mt::CodeId as lightclientCodeId;
+use eureka_lightclient_dummy::Header;
 use eureka_lightclient_interface::sv::mt::LightClientProxy;
+use eureka_lightclient_interface::{Height, UpdatePolicy};
 use eureka_tao::sv::mt::{CodeId as TaoCodeId, ContractProxy as TaoContractProxy};
-use eureka_tao::{Packet, PacketHeader, Payload, PayloadHeader};
+use eureka_tao::{Packet, PacketHeader, Payload, PayloadHeader, SenderPolicy};
 use rstest::rstest;
-use sylvia::cw_std::Addr;
+use sylvia::cw_std::{to_json_vec, Addr};
 use sylvia::multitest::App;
 
+use crate::factory::create_dummy;
+
 #[rstest]
 fn test_ibc_eureka_cw() {
     let chain_1 = App::default();
@@ -22,26 +26,47 @@ fn test_ibc_eureka_cw() {
 
     let tao_contract = tao_code_id.instantiate().call(&gov).unwrap();
 
-    let lightclient_1_contract = lightclient_code_id
-        .instantiate(vec![], vec![])
-        .call(&dao)
+    tao_contract
+        .approve_lightclient_code(lightclient_code_id.code_id(), "dummy".to_string())
+        .call(&gov)
         .unwrap();
 
-    lightclient_1_contract.update(vec![]).call(&hacker).unwrap();
+    let lightclient_1_contract = create_dummy(&tao_contract, &lightclient_code_id, &dao);
+
+    let header = to_json_vec(&Header {
+        height: Height::new(0, 1),
+        timestamp: chain_1.block_info().time.seconds(),
+    })
+    .unwrap();
+
+    // dummy headers are not verified, only allowed submitters can update
+    assert_eq!(
+        lightclient_1_contract.update_policy().unwrap(),
+        UpdatePolicy::AllowList(vec![dao.clone()])
+    );
+
+    lightclient_1_contract
+        .update(header.clone())
+        .call(&hacker)
+        .unwrap_err();
 
-    let lightclient_2_contract = lightclient_code_id
-        .instantiate(vec![], vec![])
+    lightclient_1_contract
+        .update(header.clone())
         .call(&dao)
         .unwrap();
 
-    lightclient_2_contract.update(vec![]).call(&hacker).unwrap();
+    let lightclient_2_contract = create_dummy(&tao_contract, &lightclient_code_id, &dao);
+
+    lightclient_2_contract.update(header).call(&dao).unwrap();
 
     let application_1_contract = application_code_id
         .instantiate(tao_contract.contract_addr.clone())
+        .with_admin(alice.as_str())
         .call(&alice)
         .unwrap();
     let application_2_contract = application_code_id
         .instantiate(tao_contract.contract_addr.clone())
+        .with_admin(alice.as_str())
         .call(&alice)
         .unwrap();
 
@@ -83,6 +108,38 @@ fn test_ibc_eureka_cw() {
         .call(&alice)
         .unwrap();
 
+    // only application admin can register application for a connection
+
+    tao_contract
+        .register_application(
+            application_1_contract.contract_addr.clone(),
+            (lightclient_1_contract.contract_addr.clone(), vec![]),
+            (lightclient_2_contract.contract_addr.clone(), vec![]),
+            SenderPolicy::Any,
+        )
+        .call(&hacker)
+        .unwrap_err();
+
+    tao_contract
+        .register_application(
+            application_1_contract.contract_addr.clone(),
+            (lightclient_1_contract.contract_addr.clone(), vec![]),
+            (lightclient_2_contract.contract_addr.clone(), vec![]),
+            SenderPolicy::Any,
+        )
+        .call(&alice)
+        .unwrap();
+
+    tao_contract
+        .register_application(
+            application_2_contract.contract_addr.clone(),
+            (lightclient_2_contract.contract_addr.clone(), vec![]),
+            (lightclient_1_contract.contract_addr.clone(), vec![]),
+            SenderPolicy::Any,
+        )
+        .call(&alice)
+        .unwrap();
+
     assert_eq!(application_1_contract.sent_value().unwrap(), "null");
     assert_eq!(application_1_contract.received_value().unwrap(), "null");
     assert_eq!(application_2_contract.sent_value().unwrap(), "null");
@@ -102,6 +159,7 @@ fn test_ibc_eureka_cw() {
                 application_source: application_1_contract.contract_addr.clone(),
                 application_destination: application_2_contract.contract_addr.clone(),
                 funds: vec![],
+                gas_limit: None,
             },
             data: data_1_2.as_bytes().to_vec(),
         }],
@@ -109,12 +167,12 @@ fn test_ibc_eureka_cw() {
 
     // only alice is allowed to send packet
     tao_contract
-        .send_packet(packet_1_2.clone())
+        .send_packet(packet_1_2.clone(), None)
         .call(&hacker)
         .unwrap_err();
 
     tao_contract
-        .send_packet(packet_1_2.clone())
+        .send_packet(packet_1_2.clone(), None)
         .call(&alice)
         .unwrap();
 
@@ -128,7 +186,7 @@ fn test_ibc_eureka_cw() {
 
     // anyone can relay received packet, as commitment proof is included
     tao_contract
-        .receive_packet(packet_1_2, 0, vec![])
+        .receive_packet(packet_1_2, Height::default(), vec![])
         .call(&hacker)
         .unwrap();
 
@@ -154,6 +212,7 @@ fn test_ibc_eureka_cw() {
                 application_source: application_2_contract.contract_addr.clone(),
                 application_destination: application_1_contract.contract_addr.clone(),
                 funds: vec![],
+                gas_limit: None,
             },
             data: data_2_1.as_bytes().to_vec(),
         }],
@@ -161,12 +220,12 @@ fn test_ibc_eureka_cw() {
 
     // only alice is allowed to send packet
     tao_contract
-        .send_packet(packet_2_1.clone())
+        .send_packet(packet_2_1.clone(), None)
         .call(&hacker)
         .unwrap_err();
 
     tao_contract
-        .send_packet(packet_2_1.clone())
+        .send_packet(packet_2_1.clone(), None)
         .call(&alice)
         .unwrap();
 
@@ -180,7 +239,7 @@ fn test_ibc_eureka_cw() {
 
     // anyone can relay received packet, as commitment proof is included
     tao_contract
-        .receive_packet(packet_2_1, 0, vec![])
+        .receive_packet(packet_2_1, Height::default(), vec![])
         .call(&hacker)
         .unwrap();
 
@@ -206,13 +265,14 @@ fn test_ibc_eureka_cw() {
                 application_source: application_2_contract.contract_addr.clone(),
                 application_destination: application_1_contract.contract_addr.clone(),
                 funds: vec![],
+                gas_limit: None,
             },
             data: data_2_1.as_bytes().to_vec(),
         }],
     };
 
     tao_contract
-        .send_packet(packet_2_1.clone())
+        .send_packet(packet_2_1.clone(), None)
         .call(&alice)
         .unwrap();
 
@@ -224,9 +284,21 @@ fn test_ibc_eureka_cw() {
         )
     );
 
+    // the destination reaches the timeout without receiving the packet
+    lightclient_1_contract
+        .update(
+            to_json_vec(&Header {
+                height: Height::new(0, 2),
+                timestamp: packet_2_1.header.timeout,
+            })
+            .unwrap(),
+        )
+        .call(&dao)
+        .unwrap();
+
     // anyone can relay received packet, as commitment proof is included
     tao_contract
-        .timeout_packet(packet_2_1, 0, vec![])
+        .timeout_packet(packet_2_1, Height::new(0, 2), vec![])
         .call(&hacker)
         .unwrap();
 